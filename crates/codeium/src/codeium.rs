@@ -0,0 +1,760 @@
+mod codeium_completion_provider;
+mod sign_in;
+
+pub use codeium_completion_provider::*;
+pub use sign_in::*;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_compression::futures::bufread::GzipDecoder;
+use futures::{AsyncReadExt, StreamExt};
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Global, SharedString, Task, actions};
+use http_client::{
+    AsyncBody, HttpClient, Method, Request as HttpRequest, github::get_release_by_tag_name,
+};
+use language::{
+    BufferSnapshot,
+    language_settings::{EditPredictionProvider, all_language_settings, language_settings},
+};
+use release_channel::{AppVersion, ReleaseChannel};
+use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
+use settings::SettingsStore;
+use sha2::{Digest, Sha256};
+use smol::{fs, process::Child};
+use std::{ops::Range, path::PathBuf, process::Stdio, sync::Arc, time::Duration};
+use util::ResultExt;
+use workspace::Workspace;
+
+actions!(codeium, [SignIn, SignOut]);
+
+const CREDENTIALS_URL: &str = "https://codeium.com";
+const API_SERVER_URL: &str = "https://server.codeium.com";
+const REGISTER_USER_URL: &str = "https://api.codeium.com/register_user/";
+const LANGUAGE_SERVER_REPO: &str = "Exafunction/codeium";
+const LANGUAGE_SERVER_VERSION: &str = "1.20.9";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const PORT_POLL_ATTEMPTS: usize = 50;
+
+pub fn init(http_client: Arc<dyn HttpClient>, cx: &mut App) {
+    let codeium = cx.new(|cx| {
+        cx.on_app_quit(|codeium: &mut Codeium, _| {
+            codeium.server.take();
+            let manager_dir = manager_dir();
+            async move {
+                if fs::metadata(&manager_dir).await.is_ok() {
+                    fs::remove_dir_all(&manager_dir).await.log_err();
+                }
+            }
+        })
+        .detach();
+        Codeium::new(http_client)
+    });
+    Codeium::set_global(codeium.clone(), cx);
+
+    let is_used = |cx: &App| {
+        all_language_settings(None, cx)
+            .uses_edit_prediction_provider(EditPredictionProvider::Codeium)
+    };
+    let mut used = is_used(cx);
+    if used {
+        codeium.update(cx, |codeium, cx| codeium.start(cx));
+    } else {
+        codeium.update(cx, |codeium, cx| codeium.check_api_key(cx));
+    }
+
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let now_used = is_used(cx);
+        if now_used != used {
+            used = now_used;
+            if used {
+                codeium.update(cx, |codeium, cx| codeium.start(cx));
+            } else {
+                codeium.update(cx, |codeium, cx| codeium.stop(cx));
+            }
+        }
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|_, _: &SignIn, window, cx| initiate_sign_in(window, cx));
+    })
+    .detach();
+
+    cx.on_action(|_: &SignOut, cx| {
+        if let Some(codeium) = Codeium::global(cx) {
+            codeium.update(cx, |codeium, cx| codeium.sign_out(cx));
+        }
+    });
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CodeiumStatus {
+    Stopped,
+    Starting,
+    SignedOut,
+    Running,
+    Error(SharedString),
+}
+
+pub struct Codeium {
+    http_client: Arc<dyn HttpClient>,
+    status: CodeiumStatus,
+    server: Option<CodeiumServer>,
+    /// Whether an API key is stored, so that Codeium can start without signing in.
+    has_api_key: bool,
+}
+
+#[derive(Clone)]
+struct CodeiumGlobal(Entity<Codeium>);
+
+impl Global for CodeiumGlobal {}
+
+impl Codeium {
+    fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            status: CodeiumStatus::Stopped,
+            server: None,
+            has_api_key: false,
+        }
+    }
+
+    #[cfg(test)]
+    fn fake(http_client: Arc<dyn HttpClient>, port: u16) -> Self {
+        Self {
+            http_client: http_client.clone(),
+            status: CodeiumStatus::Running,
+            server: Some(CodeiumServer {
+                api_key: "fake-api-key".into(),
+                ide_version: "0.0.0".into(),
+                port,
+                http_client,
+                _process: None,
+                _heartbeat: Task::ready(()),
+            }),
+            has_api_key: true,
+        }
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<CodeiumGlobal>()
+            .map(|model| model.0.clone())
+    }
+
+    pub fn set_global(codeium: Entity<Self>, cx: &mut App) {
+        cx.set_global(CodeiumGlobal(codeium));
+    }
+
+    pub fn status(&self) -> &CodeiumStatus {
+        &self.status
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.server.is_some()
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        self.has_api_key
+    }
+
+    fn check_api_key(&mut self, cx: &mut Context<Self>) {
+        let credentials = cx.read_credentials(CREDENTIALS_URL);
+        cx.spawn(async move |this, cx| {
+            let has_api_key = credentials.await?.is_some();
+            this.update(cx, |this, cx| {
+                this.has_api_key = has_api_key;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn start(&mut self, cx: &mut Context<Self>) {
+        if !matches!(self.status, CodeiumStatus::Stopped | CodeiumStatus::Error(_)) {
+            return;
+        }
+        self.status = CodeiumStatus::Starting;
+        cx.notify();
+
+        let http_client = self.http_client.clone();
+        let ide_version = ide_version(cx);
+        let credentials = cx.read_credentials(CREDENTIALS_URL);
+        cx.spawn(async move |this, cx| {
+            let server: Result<Option<CodeiumServer>> = async {
+                let Some((_, api_key)) = credentials.await? else {
+                    return Ok(None);
+                };
+                let api_key = String::from_utf8(api_key)?;
+                let binary_path = get_language_server_path(http_client.clone()).await?;
+                CodeiumServer::spawn(binary_path, api_key, ide_version, http_client, cx)
+                    .await
+                    .map(Some)
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                if this.status != CodeiumStatus::Starting {
+                    return;
+                }
+                match server {
+                    Ok(Some(server)) => {
+                        this.server = Some(server);
+                        this.status = CodeiumStatus::Running;
+                        this.has_api_key = true;
+                    }
+                    Ok(None) => {
+                        this.status = CodeiumStatus::SignedOut;
+                        this.has_api_key = false;
+                    }
+                    Err(error) => {
+                        log::error!("failed to start Codeium: {error:#}");
+                        this.status = CodeiumStatus::Error(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.server = None;
+        self.status = CodeiumStatus::Stopped;
+        cx.notify();
+    }
+
+    pub fn restart(&mut self, cx: &mut Context<Self>) {
+        self.stop(cx);
+        self.start(cx);
+    }
+
+    /// Exchanges the token shown on Codeium's sign-in page for an API key and stores it.
+    pub fn sign_in(&mut self, auth_token: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let http_client = self.http_client.clone();
+        cx.spawn(async move |this, cx| {
+            let api_key = register_user(http_client, auth_token).await?;
+            cx.update(|cx| cx.write_credentials(CREDENTIALS_URL, "api_key", api_key.as_bytes()))?
+                .await?;
+            this.update(cx, |this, cx| {
+                this.has_api_key = true;
+                this.restart(cx)
+            })
+        })
+    }
+
+    pub fn sign_out(&mut self, cx: &mut Context<Self>) {
+        cx.delete_credentials(CREDENTIALS_URL)
+            .detach_and_log_err(cx);
+        self.server = None;
+        self.status = CodeiumStatus::SignedOut;
+        self.has_api_key = false;
+        cx.notify();
+    }
+
+    pub fn complete(
+        &self,
+        snapshot: &BufferSnapshot,
+        cursor_offset: usize,
+        cx: &App,
+    ) -> Option<Task<Result<Vec<CodeiumCompletion>>>> {
+        let server = self.server.as_ref()?;
+        let language_name = snapshot.language().map(|language| language.name());
+        let editor_language = language_name
+            .as_ref()
+            .map_or("plaintext".to_string(), |name| name.as_ref().to_lowercase());
+        let settings = language_settings(language_name, snapshot.file(), cx);
+        let request = GetCompletionsRequest {
+            metadata: server.metadata(),
+            document: Document {
+                text: snapshot.text(),
+                editor_language,
+                cursor_offset,
+                line_ending: "\n",
+                absolute_path: snapshot
+                    .file()
+                    .and_then(|file| Some(file.as_local()?.abs_path(cx).to_string_lossy().into())),
+            },
+            editor_options: EditorOptions {
+                tab_size: settings.tab_size.get(),
+                insert_spaces: !settings.hard_tabs,
+            },
+        };
+        let http_client = server.http_client.clone();
+        let port = server.port;
+        Some(cx.background_spawn(async move {
+            let response: GetCompletionsResponse =
+                language_server_request(&http_client, port, "GetCompletions", &request).await?;
+            Ok(response
+                .completion_items
+                .into_iter()
+                .map(|item| CodeiumCompletion {
+                    id: item.completion.completion_id,
+                    range: item.range.start_offset..item.range.end_offset,
+                    text: item.completion.text,
+                })
+                .collect())
+        }))
+    }
+
+    pub fn accept_completion(&self, completion_id: String, cx: &App) {
+        let Some(server) = self.server.as_ref() else {
+            return;
+        };
+        let request = AcceptCompletionRequest {
+            metadata: server.metadata(),
+            completion_id,
+        };
+        let http_client = server.http_client.clone();
+        let port = server.port;
+        cx.background_spawn(async move {
+            language_server_request::<_, serde_json::Value>(
+                &http_client,
+                port,
+                "AcceptCompletion",
+                &request,
+            )
+            .await
+            .log_err();
+        })
+        .detach();
+    }
+}
+
+pub struct CodeiumCompletion {
+    pub id: String,
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+struct CodeiumServer {
+    api_key: String,
+    ide_version: String,
+    port: u16,
+    http_client: Arc<dyn HttpClient>,
+    _process: Option<Child>,
+    _heartbeat: Task<()>,
+}
+
+impl CodeiumServer {
+    async fn spawn(
+        binary_path: PathBuf,
+        api_key: String,
+        ide_version: String,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Result<Self> {
+        let manager_dir = manager_dir();
+        fs::create_dir_all(&manager_dir).await?;
+        let mut stale_files = fs::read_dir(&manager_dir).await?;
+        while let Some(stale_file) = stale_files.next().await {
+            fs::remove_file(stale_file?.path()).await.log_err();
+        }
+
+        let process = util::command::new_smol_command(&binary_path)
+            .arg("--api_server_url")
+            .arg(API_SERVER_URL)
+            .arg("--manager_dir")
+            .arg(&manager_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start the Codeium language server")?;
+
+        let mut port = None;
+        for _ in 0..PORT_POLL_ATTEMPTS {
+            cx.background_executor().timer(PORT_POLL_INTERVAL).await;
+            let mut files = fs::read_dir(&manager_dir).await?;
+            while let Some(file) = files.next().await {
+                port = port.or_else(|| file.ok()?.file_name().to_str()?.parse::<u16>().ok());
+            }
+            if port.is_some() {
+                break;
+            }
+        }
+        let port = port.context("the Codeium language server did not report its port")?;
+
+        let heartbeat = cx.background_spawn({
+            let http_client = http_client.clone();
+            let request = HeartbeatRequest {
+                metadata: Metadata::new(&api_key, &ide_version),
+            };
+            let executor = cx.background_executor().clone();
+            async move {
+                loop {
+                    executor.timer(HEARTBEAT_INTERVAL).await;
+                    language_server_request::<_, serde_json::Value>(
+                        &http_client,
+                        port,
+                        "Heartbeat",
+                        &request,
+                    )
+                    .await
+                    .log_err();
+                }
+            }
+        });
+
+        Ok(Self {
+            api_key,
+            ide_version,
+            port,
+            http_client,
+            _process: Some(process),
+            _heartbeat: heartbeat,
+        })
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::new(&self.api_key, &self.ide_version)
+    }
+}
+
+/// Zed's version as reported to Codeium, with the release channel for pre-releases, e.g.
+/// "0.190.0-preview".
+fn ide_version(cx: &App) -> String {
+    let version = AppVersion::global(cx);
+    match ReleaseChannel::try_global(cx) {
+        Some(ReleaseChannel::Stable) | None => version.to_string(),
+        Some(channel) => format!("{version}-{}", channel.dev_name()),
+    }
+}
+
+/// The server announces its port by creating a file named after it. Each Zed process gets its
+/// own directory so that clearing out stale files never touches another instance's, and removes
+/// it when quitting.
+fn manager_dir() -> PathBuf {
+    paths::codeium_dir()
+        .join("manager")
+        .join(std::process::id().to_string())
+}
+
+async fn get_language_server_path(http_client: Arc<dyn HttpClient>) -> Result<PathBuf> {
+    let codeium_dir = paths::codeium_dir();
+    fs::create_dir_all(codeium_dir)
+        .await
+        .with_context(|| format!("could not create Codeium directory at {codeium_dir:?}"))?;
+
+    let binary_path = codeium_dir.join(format!(
+        "language_server_{LANGUAGE_SERVER_VERSION}{}",
+        std::env::consts::EXE_SUFFIX
+    ));
+    if fs::metadata(&binary_path)
+        .await
+        .map_or(false, |metadata| metadata.is_file())
+    {
+        return Ok(binary_path);
+    }
+
+    let asset = match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => "language_server_macos_arm",
+        ("macos", "x86_64") => "language_server_macos_x64",
+        ("linux", "aarch64") => "language_server_linux_arm",
+        ("linux", "x86_64") => "language_server_linux_x64",
+        ("windows", "x86_64") => "language_server_windows_x64.exe",
+        (os, arch) => return Err(anyhow!("Codeium is not available for {os} on {arch}")),
+    };
+    let compressed = download_language_server(http_client, &format!("{asset}.gz")).await?;
+
+    let download_path = binary_path.with_extension("download");
+    let mut file = fs::File::create(&download_path)
+        .await
+        .with_context(|| format!("unable to create file at {download_path:?}"))?;
+    futures::io::copy(GzipDecoder::new(compressed.as_slice()), &mut file)
+        .await
+        .with_context(|| format!("unable to write the language server to {download_path:?}"))?;
+    #[cfg(not(windows))]
+    {
+        file.set_permissions(<fs::Permissions as fs::unix::PermissionsExt>::from_mode(
+            0o755,
+        ))
+        .await?;
+    }
+    drop(file);
+    fs::rename(&download_path, &binary_path).await?;
+
+    Ok(binary_path)
+}
+
+/// Downloads a release asset of the language server, checking it against the digest GitHub
+/// published for it.
+async fn download_language_server(
+    http_client: Arc<dyn HttpClient>,
+    asset_name: &str,
+) -> Result<Vec<u8>> {
+    let release = get_release_by_tag_name(
+        LANGUAGE_SERVER_REPO,
+        &format!("language-server-v{LANGUAGE_SERVER_VERSION}"),
+        http_client.clone(),
+    )
+    .await?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == asset_name)
+        .with_context(|| format!("no asset found matching {asset_name:?}"))?;
+    let expected_digest = asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .with_context(|| format!("no checksum published for {asset_name:?}"))?;
+
+    let mut response = http_client
+        .get(&asset.browser_download_url, AsyncBody::default(), true)
+        .await
+        .context("unable to download the Codeium language server")?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "unable to download the Codeium language server: {}",
+            response.status()
+        ));
+    }
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+
+    let digest = format!("{:x}", Sha256::digest(&body));
+    if !digest.eq_ignore_ascii_case(expected_digest) {
+        return Err(anyhow!(
+            "checksum mismatch for {asset_name:?}: expected {expected_digest}, got {digest}"
+        ));
+    }
+    Ok(body)
+}
+
+async fn register_user(http_client: Arc<dyn HttpClient>, auth_token: String) -> Result<String> {
+    #[derive(Serialize)]
+    struct RegisterUserRequest {
+        firebase_id_token: String,
+    }
+
+    #[derive(Deserialize)]
+    struct RegisterUserResponse {
+        api_key: String,
+    }
+
+    let body = serde_json::to_string(&RegisterUserRequest {
+        firebase_id_token: auth_token,
+    })?;
+    let mut response = http_client
+        .post_json(REGISTER_USER_URL, body.into())
+        .await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Codeium rejected the token: {} {body}", response.status()));
+    }
+    Ok(serde_json::from_str::<RegisterUserResponse>(&body)?.api_key)
+}
+
+async fn language_server_request<Req: Serialize, Res: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    port: u16,
+    method: &str,
+    request: &Req,
+) -> Result<Res> {
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(format!(
+            "http://127.0.0.1:{port}/exa.language_server_pb.LanguageServerService/{method}"
+        ))
+        .header("Content-Type", "application/json")
+        .body(AsyncBody::from(serde_json::to_string(request)?))?;
+    let mut response = http_client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("{method} failed: {} {body}", response.status()));
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[derive(Serialize)]
+struct Metadata {
+    api_key: String,
+    ide_name: &'static str,
+    ide_version: String,
+    extension_name: &'static str,
+    extension_version: String,
+}
+
+impl Metadata {
+    /// The integration ships with Zed, so it reports Zed's version for both.
+    fn new(api_key: &str, ide_version: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            ide_name: "zed",
+            ide_version: ide_version.to_string(),
+            extension_name: "zed",
+            extension_version: ide_version.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HeartbeatRequest {
+    metadata: Metadata,
+}
+
+#[derive(Serialize)]
+struct GetCompletionsRequest {
+    metadata: Metadata,
+    document: Document,
+    editor_options: EditorOptions,
+}
+
+#[derive(Serialize)]
+struct Document {
+    text: String,
+    editor_language: String,
+    cursor_offset: usize,
+    line_ending: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    absolute_path: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EditorOptions {
+    tab_size: u32,
+    insert_spaces: bool,
+}
+
+#[derive(Serialize)]
+struct AcceptCompletionRequest {
+    metadata: Metadata,
+    completion_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetCompletionsResponse {
+    #[serde(default)]
+    completion_items: Vec<CompletionItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionItem {
+    completion: CompletionText,
+    #[serde(default)]
+    range: CompletionRange,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionText {
+    completion_id: String,
+    text: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionRange {
+    #[serde(default, deserialize_with = "deserialize_offset")]
+    start_offset: usize,
+    #[serde(default, deserialize_with = "deserialize_offset")]
+    end_offset: usize,
+}
+
+/// The server's JSON encodes 64-bit integers as strings and leaves out zeroes.
+fn deserialize_offset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Offset {
+        Number(usize),
+        String(String),
+    }
+
+    match Offset::deserialize(deserializer)? {
+        Offset::Number(offset) => Ok(offset),
+        Offset::String(offset) => offset.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use http_client::{FakeHttpClient, Response};
+
+    const ASSET: &[u8] = b"compressed language server";
+
+    fn release_http_client(digest: &'static str) -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(move |request| {
+            let body = if request.uri().path().contains("/releases/tags/") {
+                serde_json::json!({
+                    "tag_name": format!("language-server-v{LANGUAGE_SERVER_VERSION}"),
+                    "prerelease": false,
+                    "tarball_url": "",
+                    "zipball_url": "",
+                    "assets": [{
+                        "name": "language_server_linux_x64.gz",
+                        "browser_download_url": "https://example.com/language_server_linux_x64.gz",
+                        "digest": digest,
+                    }],
+                })
+                .to_string()
+                .into()
+            } else {
+                ASSET.into()
+            };
+            async move { Ok(Response::builder().status(200).body(body).unwrap()) }
+        })
+    }
+
+    #[gpui::test]
+    async fn test_download_language_server_verifies_checksum() {
+        let digest = format!("sha256:{:x}", Sha256::digest(ASSET)).leak();
+        let body = download_language_server(
+            release_http_client(digest),
+            "language_server_linux_x64.gz",
+        )
+        .await
+        .unwrap();
+        assert_eq!(body, ASSET);
+
+        let error = download_language_server(
+            release_http_client("sha256:0000"),
+            "language_server_linux_x64.gz",
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("checksum mismatch"), "{error}");
+
+        let error = download_language_server(
+            release_http_client(digest),
+            "language_server_macos_arm.gz",
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("no asset found"), "{error}");
+    }
+
+    #[gpui::test]
+    fn test_sign_out_forgets_api_key(cx: &mut TestAppContext) {
+        let codeium = cx.new(|_| Codeium::fake(FakeHttpClient::with_404_response(), 0));
+        codeium.read_with(cx, |codeium, _| assert!(codeium.has_api_key()));
+
+        codeium.update(cx, |codeium, cx| codeium.sign_out(cx));
+        codeium.read_with(cx, |codeium, _| {
+            assert!(!codeium.has_api_key());
+            assert!(!codeium.is_running());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_register_user() {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri().to_string(), REGISTER_USER_URL);
+            Ok(Response::builder()
+                .status(200)
+                .body(r#"{"api_key": "the-api-key"}"#.into())
+                .unwrap())
+        });
+        let api_key = register_user(http_client, "token".into()).await.unwrap();
+        assert_eq!(api_key, "the-api-key");
+
+        let api_key = register_user(FakeHttpClient::with_404_response(), "token".into()).await;
+        assert!(api_key.is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{Codeium, CodeiumStatus};
+use anyhow::Result;
+use gpui::{App, Context, Entity, EntityId, Task};
+use inline_completion::{
+    Direction, EditPredictionError, EditPredictionProvider, EditPredictionRequestStats,
+    InlineCompletion,
+};
+use language::{Anchor, Buffer, OffsetRangeExt, ToOffset};
+use project::Project;
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+use text::Bias;
+
+pub const CODEIUM_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+
+pub struct CodeiumCompletionProvider {
+    codeium: Entity<Codeium>,
+    buffer_id: Option<EntityId>,
+    completion: Option<CurrentCompletion>,
+    pending_refresh: Option<Task<Result<()>>>,
+    request_stats: EditPredictionRequestStats,
+}
+
+struct CurrentCompletion {
+    id: String,
+    range: Range<Anchor>,
+    text: String,
+}
+
+impl CodeiumCompletionProvider {
+    pub fn new(codeium: Entity<Codeium>) -> Self {
+        Self {
+            codeium,
+            buffer_id: None,
+            completion: None,
+            pending_refresh: None,
+            request_stats: EditPredictionRequestStats::default(),
+        }
+    }
+}
+
+impl EditPredictionProvider for CodeiumCompletionProvider {
+    fn name() -> &'static str {
+        "codeium"
+    }
+
+    fn display_name() -> &'static str {
+        "Codeium"
+    }
+
+    fn show_completions_in_menu() -> bool {
+        false
+    }
+
+    fn is_enabled(&self, _buffer: &Entity<Buffer>, _cursor_position: Anchor, cx: &App) -> bool {
+        *self.codeium.read(cx).status() == CodeiumStatus::Running
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.pending_refresh.is_some()
+    }
+
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        self.request_stats.last_latency()
+    }
+
+    fn last_error(&self, _cx: &App) -> Option<EditPredictionError> {
+        self.request_stats.last_error()
+    }
+
+    fn refresh(
+        &mut self,
+        _project: Option<Entity<Project>>,
+        buffer: Entity<Buffer>,
+        cursor_position: Anchor,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let codeium = self.codeium.clone();
+        self.pending_refresh = Some(cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor()
+                    .timer(CODEIUM_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+            let cursor_offset = cursor_position.to_offset(&snapshot);
+            let Some(request) = codeium.read_with(cx, |codeium, cx| {
+                codeium.complete(&snapshot, cursor_offset, cx)
+            })?
+            else {
+                this.update(cx, |this, _| this.pending_refresh = None)?;
+                return Ok(());
+            };
+
+            let started_at = Instant::now();
+            let completions = request.await;
+            this.update(cx, |this, cx| {
+                this.request_stats.record(started_at, &completions);
+                this.pending_refresh = None;
+                let Some(completion) = completions.ok().and_then(|items| items.into_iter().next())
+                else {
+                    return;
+                };
+                let len = snapshot.len();
+                let start = snapshot.clip_offset(completion.range.start.min(len), Bias::Left);
+                let end = snapshot.clip_offset(completion.range.end.min(len), Bias::Right);
+                this.completion = Some(CurrentCompletion {
+                    id: completion.id,
+                    range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                    text: completion.text,
+                });
+                this.buffer_id = Some(buffer.entity_id());
+                cx.notify();
+            })?;
+
+            Ok(())
+        }));
+    }
+
+    fn cycle(
+        &mut self,
+        _buffer: Entity<Buffer>,
+        _cursor_position: Anchor,
+        _direction: Direction,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, cx: &mut Context<Self>) {
+        if let Some(completion) = self.completion.take() {
+            self.codeium.read(cx).accept_completion(completion.id, cx);
+        }
+        self.pending_refresh = None;
+    }
+
+    fn discard(&mut self, _cx: &mut Context<Self>) {
+        self.completion = None;
+        self.pending_refresh = None;
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<InlineCompletion> {
+        let buffer_id = buffer.entity_id();
+        let buffer = buffer.read(cx);
+        let completion = self.completion.as_ref()?;
+        if Some(buffer_id) != self.buffer_id
+            || !completion.range.start.is_valid(buffer)
+            || !completion.range.end.is_valid(buffer)
+        {
+            return None;
+        }
+
+        let mut completion_range = completion.range.to_offset(buffer);
+        let prefix_len = common_prefix(
+            buffer.chars_for_range(completion_range.clone()),
+            completion.text.chars(),
+        );
+        completion_range.start += prefix_len;
+        let suffix_len = common_prefix(
+            buffer.reversed_chars_for_range(completion_range.clone()),
+            completion.text[prefix_len..].chars().rev(),
+        );
+        completion_range.end = completion_range.end.saturating_sub(suffix_len);
+
+        let cursor_offset = cursor_position.to_offset(buffer);
+        if !completion_range.is_empty() || completion_range.start != cursor_offset {
+            return None;
+        }
+        let completion_text = &completion.text[prefix_len..completion.text.len() - suffix_len];
+        if completion_text.trim().is_empty() {
+            return None;
+        }
+        let position = cursor_position.bias_right(buffer);
+        Some(InlineCompletion {
+            id: None,
+            edits: vec![(position..position, completion_text.into())],
+            edit_preview: None,
+        })
+    }
+}
+
+fn common_prefix<T1: Iterator<Item = char>, T2: Iterator<Item = char>>(a: T1, b: T2) -> usize {
+    a.zip(b)
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use http_client::{FakeHttpClient, Response};
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_suggest_completion_at_cursor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert!(request.uri().path().ends_with("/GetCompletions"));
+            let body = serde_json::json!({
+                "completionItems": [{
+                    "completion": { "completionId": "1", "text": "    let x = 1;" },
+                    "range": { "startOffset": "12", "endOffset": "16" },
+                }],
+            });
+            Ok(Response::builder()
+                .status(200)
+                .body(body.to_string().into())
+                .unwrap())
+        });
+        let codeium = cx.new(|_| Codeium::fake(http_client, 42100));
+        let provider = cx.new(|_| CodeiumCompletionProvider::new(codeium));
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    \n}", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(16));
+
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx)
+        });
+        cx.run_until_parked();
+
+        let completion = provider
+            .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+            .unwrap();
+        assert_eq!(completion.edits.len(), 1);
+        assert_eq!(completion.edits[0].1, "let x = 1;");
+        provider.read_with(cx, |provider, cx| {
+            assert!(!provider.is_refreshing());
+            assert!(provider.last_error(cx).is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_failed_request_is_recorded(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client = FakeHttpClient::create(|_| async move {
+            Ok(Response::builder()
+                .status(500)
+                .body("internal error".into())
+                .unwrap())
+        });
+        let codeium = cx.new(|_| Codeium::fake(http_client, 42100));
+        let provider = cx.new(|_| CodeiumCompletionProvider::new(codeium));
+        let buffer = cx.new(|cx| Buffer::local("fn main() {}", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(11));
+
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx)
+        });
+        cx.run_until_parked();
+
+        assert!(
+            provider
+                .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+                .is_none()
+        );
+        let error = provider
+            .read_with(cx, |provider, cx| provider.last_error(cx))
+            .unwrap();
+        assert!(error.message.contains("GetCompletions failed"), "{}", error.message);
+    }
+}
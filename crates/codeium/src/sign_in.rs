@@ -0,0 +1,146 @@
+use crate::Codeium;
+use editor::Editor;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, Window,
+};
+use ui::{Button, Headline, HeadlineSize, prelude::*};
+use workspace::{ModalView, Workspace};
+
+fn sign_in_url() -> String {
+    format!(
+        "https://www.codeium.com/profile?response_type=token&redirect_uri=show-auth-token&state={}&scope=openid%20profile%20email&redirect_parameters_type=query",
+        uuid::Uuid::new_v4()
+    )
+}
+
+/// Opens Codeium's sign-in page and asks for the token it shows.
+pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
+    let Some(codeium) = Codeium::global(cx) else {
+        return;
+    };
+    let Some(workspace) = window.root::<Workspace>().flatten() else {
+        return;
+    };
+    cx.open_url(&sign_in_url());
+    workspace.update(cx, |workspace, cx| {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            CodeiumSignInModal::new(codeium, window, cx)
+        });
+    });
+}
+
+pub struct CodeiumSignInModal {
+    codeium: Entity<Codeium>,
+    token_editor: Entity<Editor>,
+    error: Option<SharedString>,
+    pending_sign_in: Option<Task<()>>,
+}
+
+impl CodeiumSignInModal {
+    fn new(codeium: Entity<Codeium>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let token_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Paste your Codeium token", cx);
+            editor
+        });
+        Self {
+            codeium,
+            token_editor,
+            error: None,
+            pending_sign_in: None,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let token = self.token_editor.read(cx).text(cx).trim().to_string();
+        if token.is_empty() || self.pending_sign_in.is_some() {
+            return;
+        }
+
+        let sign_in = self
+            .codeium
+            .update(cx, |codeium, cx| codeium.sign_in(token, cx));
+        self.error = None;
+        self.pending_sign_in = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = sign_in.await;
+            this.update(cx, |this, cx| {
+                this.pending_sign_in = None;
+                match result {
+                    Ok(()) => cx.emit(DismissEvent),
+                    Err(error) => {
+                        this.error = Some(format!("{error:#}").into());
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CodeiumSignInModal {}
+
+impl ModalView for CodeiumSignInModal {}
+
+impl Focusable for CodeiumSignInModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.token_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CodeiumSignInModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let signing_in = self.pending_sign_in.is_some();
+
+        v_flex()
+            .key_context("CodeiumSignIn")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w(rems(28.))
+            .p_4()
+            .gap_2()
+            .elevation_3(cx)
+            .child(Headline::new("Sign In to Codeium").size(HeadlineSize::Small))
+            .child(
+                Label::new("Sign in on the page that opened in your browser, then paste the token.")
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_sm()
+                    .child(self.token_editor.clone()),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).color(Color::Error)),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Button::new("open-sign-in-page", "Open Sign-In Page")
+                            .on_click(|_, _, cx| cx.open_url(&sign_in_url())),
+                    )
+                    .child(
+                        Button::new(
+                            "sign-in",
+                            if signing_in { "Signing In…" } else { "Sign In" },
+                        )
+                        .disabled(signing_in)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.confirm(&menu::Confirm, window, cx)
+                        })),
+                    ),
+            )
+    }
+}
@@ -46,7 +46,8 @@ actions!(
         PreviousSuggestion,
         Reinstall,
         SignIn,
-        SignOut
+        SignOut,
+        OpenLog
     ]
 );
 
@@ -82,7 +83,7 @@ pub fn init(
                 filter.hide_action_types(&copilot_auth_action_types);
                 filter.hide_action_types(&copilot_no_auth_action_types);
             }
-            Status::Authorized => {
+            Status::Authorized | Status::RateLimited | Status::Unreachable(_) => {
                 filter.hide_action_types(&copilot_no_auth_action_types);
                 filter.show_action_types(
                     copilot_action_types
@@ -155,7 +156,10 @@ impl CopilotServer {
 struct RunningCopilotServer {
     lsp: Arc<LanguageServer>,
     sign_in_status: SignInStatus,
+    /// The GitHub login the language server reported on the last sign-in status change.
+    user: Option<String>,
     registered_buffers: HashMap<EntityId, RegisteredBuffer>,
+    request_error: Option<RequestError>,
 }
 
 #[derive(Clone, Debug)]
@@ -186,11 +190,15 @@ pub enum Status {
     },
     Unauthorized,
     Authorized,
+    /// Signed in, but GitHub rejected the last completion request because of rate limiting.
+    RateLimited,
+    /// Signed in, but the last completion request couldn't reach GitHub.
+    Unreachable(Arc<str>),
 }
 
 impl Status {
     pub fn is_authorized(&self) -> bool {
-        matches!(self, Status::Authorized)
+        matches!(self, Status::Authorized | Status::RateLimited | Status::Unreachable(_))
     }
 
     pub fn is_disabled(&self) -> bool {
@@ -306,6 +314,8 @@ pub struct Copilot {
     server: CopilotServer,
     buffers: HashSet<WeakEntity<Buffer>>,
     server_id: LanguageServerId,
+    /// The settings the running server was started with.
+    settings: CopilotSettings,
     _subscription: gpui::Subscription,
 }
 
@@ -343,14 +353,30 @@ impl Copilot {
             node_runtime,
             server: CopilotServer::Disabled,
             buffers: Default::default(),
+            settings: Default::default(),
             _subscription: cx.on_app_quit(Self::shutdown_language_server),
         };
         this.start_copilot(true, false, cx);
-        cx.observe_global::<SettingsStore>(move |this, cx| this.start_copilot(true, false, cx))
-            .detach();
+        cx.observe_global::<SettingsStore>(move |this, cx| {
+            this.stop_if_settings_changed(cx);
+            this.start_copilot(true, false, cx)
+        })
+        .detach();
         this
     }
 
+    /// The proxy and enterprise URL are only read when the server starts, so it has to be
+    /// restarted for changes to them to take effect.
+    fn stop_if_settings_changed(&mut self, cx: &mut Context<Self>) {
+        let started = matches!(self.server, CopilotServer::Running(_) | CopilotServer::Error(_));
+        if !started || all_language_settings(None, cx).edit_predictions.copilot == self.settings {
+            return;
+        }
+        let shutdown = self.shutdown_language_server(cx);
+        cx.background_spawn(shutdown).detach();
+        cx.notify();
+    }
+
     fn shutdown_language_server(
         &mut self,
         _cx: &mut Context<Self>,
@@ -378,14 +404,17 @@ impl Copilot {
         }
         let language_settings = all_language_settings(None, cx);
         if check_edit_prediction_provider
-            && language_settings.edit_predictions.provider != EditPredictionProvider::Copilot
+            && !language_settings.uses_edit_prediction_provider(EditPredictionProvider::Copilot)
         {
             return;
         }
         let server_id = self.server_id;
         let fs = self.fs.clone();
         let node_runtime = self.node_runtime.clone();
-        let env = self.build_env(&language_settings.edit_predictions.copilot);
+        let settings = language_settings.edit_predictions.copilot.clone();
+        let env = self.build_env(&settings);
+        let enterprise_uri = settings.enterprise_uri.clone();
+        self.settings = settings;
         let start_task = cx
             .spawn(async move |this, cx| {
                 Self::start_language_server(
@@ -393,6 +422,7 @@ impl Copilot {
                     fs,
                     node_runtime,
                     env,
+                    enterprise_uri,
                     this,
                     awaiting_sign_in_after_start,
                     cx,
@@ -453,8 +483,11 @@ impl Copilot {
             server: CopilotServer::Running(RunningCopilotServer {
                 lsp: Arc::new(server),
                 sign_in_status: SignInStatus::Authorized,
+                user: None,
                 registered_buffers: Default::default(),
+                request_error: None,
             }),
+            settings: Default::default(),
             _subscription: cx.on_app_quit(Self::shutdown_language_server),
             buffers: Default::default(),
         });
@@ -466,6 +499,7 @@ impl Copilot {
         fs: Arc<dyn Fs>,
         node_runtime: NodeRuntime,
         env: Option<HashMap<String, String>>,
+        enterprise_uri: Option<String>,
         this: WeakEntity<Self>,
         awaiting_sign_in_after_start: bool,
         cx: &mut AsyncApp,
@@ -503,7 +537,10 @@ impl Copilot {
                 .detach();
 
             let configuration = lsp::DidChangeConfigurationParams {
-                settings: Default::default(),
+                settings: match enterprise_uri {
+                    Some(uri) => serde_json::json!({ "github-enterprise": { "uri": uri } }),
+                    None => Default::default(),
+                },
             };
 
             let editor_info = request::SetEditorInfoParams {
@@ -549,13 +586,20 @@ impl Copilot {
                         sign_in_status: SignInStatus::SignedOut {
                             awaiting_signing_in: awaiting_sign_in_after_start,
                         },
+                        user: None,
                         registered_buffers: Default::default(),
+                        request_error: None,
                     });
                     cx.emit(Event::CopilotLanguageServerStarted);
                     this.update_sign_in_status(status, cx);
                 }
                 Err(error) => {
-                    this.server = CopilotServer::Error(error.to_string().into());
+                    let settings = &all_language_settings(None, cx).edit_predictions.copilot;
+                    let message = match EndpointUnreachable::detect(&error, settings) {
+                        Some(unreachable) => unreachable.to_string(),
+                        None => error.to_string(),
+                    };
+                    this.server = CopilotServer::Error(message.into());
                     cx.notify()
                 }
             }
@@ -625,6 +669,10 @@ impl Copilot {
                                         request::SignInStatus::NotSignedIn,
                                         cx,
                                     );
+                                    let settings =
+                                        &all_language_settings(None, cx).edit_predictions.copilot;
+                                    let error = EndpointUnreachable::detect(&error, settings)
+                                        .map_or(error, anyhow::Error::new);
                                     Err(Arc::new(error))
                                 }
                             })?
@@ -639,7 +687,12 @@ impl Copilot {
                 }
             };
 
-            cx.background_spawn(task.map_err(|err| anyhow!("{:?}", err)))
+            cx.background_spawn(task.map_err(|err| {
+                match err.downcast_ref::<EndpointUnreachable>() {
+                    Some(unreachable) => anyhow::Error::new(unreachable.clone()),
+                    None => anyhow!("{:?}", err),
+                }
+            }))
         } else {
             // If we're downloading, wait until download is finished
             // If we're in a stuck state, display to the user
@@ -668,8 +721,10 @@ impl Copilot {
     }
 
     pub fn reinstall(&mut self, cx: &mut Context<Self>) -> Task<()> {
-        let language_settings = all_language_settings(None, cx);
-        let env = self.build_env(&language_settings.edit_predictions.copilot);
+        let settings = all_language_settings(None, cx).edit_predictions.copilot.clone();
+        let env = self.build_env(&settings);
+        let enterprise_uri = settings.enterprise_uri.clone();
+        self.settings = settings;
         let start_task = cx
             .spawn({
                 let fs = self.fs.clone();
@@ -677,8 +732,17 @@ impl Copilot {
                 let server_id = self.server_id;
                 async move |this, cx| {
                     clear_copilot_dir().await;
-                    Self::start_language_server(server_id, fs, node_runtime, env, this, false, cx)
-                        .await
+                    Self::start_language_server(
+                        server_id,
+                        fs,
+                        node_runtime,
+                        env,
+                        enterprise_uri,
+                        this,
+                        false,
+                        cx,
+                    )
+                    .await
                 }
             })
             .shared();
@@ -927,6 +991,7 @@ impl Copilot {
             .get_mut(&buffer.entity_id())
             .unwrap();
         let snapshot = registered_buffer.report_changes(buffer, cx);
+        let copilot_settings = all_language_settings(None, cx).edit_predictions.copilot.clone();
         let buffer = buffer.read(cx);
         let uri = registered_buffer.uri.clone();
         let position = position.to_point_utf16(buffer);
@@ -942,7 +1007,7 @@ impl Copilot {
             .map(|file| file.path().to_path_buf())
             .unwrap_or_default();
 
-        cx.background_spawn(async move {
+        cx.spawn(async move |this, cx| {
             let (version, snapshot) = snapshot.await?;
             let result = lsp
                 .request::<R>(request::GetCompletionsParams {
@@ -956,8 +1021,13 @@ impl Copilot {
                         version: version.try_into().unwrap(),
                     },
                 })
-                .await?;
-            let completions = result
+                .await;
+            let request_error = result
+                .as_ref()
+                .err()
+                .and_then(|error| RequestError::detect(error, &copilot_settings));
+            this.update(cx, |this, cx| this.set_request_error(request_error, cx))?;
+            let completions = result?
                 .completions
                 .into_iter()
                 .map(|completion| {
@@ -976,14 +1046,32 @@ impl Copilot {
         })
     }
 
+    /// The GitHub login Copilot is signed in with, if the language server reported one.
+    pub fn user(&self) -> Option<&str> {
+        match &self.server {
+            CopilotServer::Running(server) => server.user.as_deref(),
+            _ => None,
+        }
+    }
+
     pub fn status(&self) -> Status {
         match &self.server {
             CopilotServer::Starting { task } => Status::Starting { task: task.clone() },
             CopilotServer::Disabled => Status::Disabled,
             CopilotServer::Error(error) => Status::Error(error.clone()),
-            CopilotServer::Running(RunningCopilotServer { sign_in_status, .. }) => {
+            CopilotServer::Running(RunningCopilotServer {
+                sign_in_status,
+                request_error,
+                ..
+            }) => {
                 match sign_in_status {
-                    SignInStatus::Authorized { .. } => Status::Authorized,
+                    SignInStatus::Authorized { .. } => match request_error {
+                        Some(RequestError::RateLimited) => Status::RateLimited,
+                        Some(RequestError::Unreachable(message)) => {
+                            Status::Unreachable(message.clone())
+                        }
+                        None => Status::Authorized,
+                    },
                     SignInStatus::Unauthorized { .. } => Status::Unauthorized,
                     SignInStatus::SigningIn { prompt, .. } => Status::SigningIn {
                         prompt: prompt.clone(),
@@ -998,15 +1086,26 @@ impl Copilot {
         }
     }
 
+    fn set_request_error(&mut self, error: Option<RequestError>, cx: &mut Context<Self>) {
+        if let CopilotServer::Running(server) = &mut self.server {
+            if server.request_error != error {
+                server.request_error = error;
+                cx.notify();
+            }
+        }
+    }
+
     fn update_sign_in_status(&mut self, lsp_status: request::SignInStatus, cx: &mut Context<Self>) {
         self.buffers.retain(|buffer| buffer.is_upgradable());
 
         if let Ok(server) = self.server.as_running() {
+            server.request_error = None;
             match lsp_status {
-                request::SignInStatus::Ok { user: Some(_) }
-                | request::SignInStatus::MaybeOk { .. }
-                | request::SignInStatus::AlreadySignedIn { .. } => {
+                request::SignInStatus::Ok { user: Some(user) }
+                | request::SignInStatus::MaybeOk { user }
+                | request::SignInStatus::AlreadySignedIn { user } => {
                     server.sign_in_status = SignInStatus::Authorized;
+                    server.user = Some(user);
                     cx.emit(Event::CopilotAuthSignedIn);
                     for buffer in self.buffers.iter().cloned().collect::<Vec<_>>() {
                         if let Some(buffer) = buffer.upgrade() {
@@ -1014,8 +1113,9 @@ impl Copilot {
                         }
                     }
                 }
-                request::SignInStatus::NotAuthorized { .. } => {
+                request::SignInStatus::NotAuthorized { user } => {
                     server.sign_in_status = SignInStatus::Unauthorized;
+                    server.user = Some(user);
                     for buffer in self.buffers.iter().cloned().collect::<Vec<_>>() {
                         self.unregister_buffer(&buffer);
                     }
@@ -1026,6 +1126,7 @@ impl Copilot {
                             awaiting_signing_in: false,
                         };
                     }
+                    server.user = None;
                     cx.emit(Event::CopilotAuthSignedOut);
                     for buffer in self.buffers.iter().cloned().collect::<Vec<_>>() {
                         self.unregister_buffer(&buffer);
@@ -1038,6 +1139,96 @@ impl Copilot {
     }
 }
 
+/// The host Copilot signs in to: github.com, or the configured GitHub Enterprise Server.
+pub fn endpoint_host(settings: &CopilotSettings) -> String {
+    settings
+        .enterprise_uri
+        .as_deref()
+        .map(|uri| {
+            uri.trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Network errors reported by the Copilot server when it can't connect to GitHub.
+const UNREACHABLE_ENDPOINT_ERRORS: &[&str] = &[
+    "ECONNREFUSED",
+    "ECONNRESET",
+    "ENOTFOUND",
+    "ETIMEDOUT",
+    "EAI_AGAIN",
+    "certificate",
+];
+
+/// Copilot couldn't connect to GitHub, usually because a firewall or proxy blocks it.
+#[derive(Clone, Debug)]
+pub struct EndpointUnreachable {
+    endpoint: String,
+    message: String,
+    proxy: Option<String>,
+}
+
+impl EndpointUnreachable {
+    fn detect(error: &anyhow::Error, settings: &CopilotSettings) -> Option<Self> {
+        let message = format!("{error:#}");
+        if !UNREACHABLE_ENDPOINT_ERRORS
+            .iter()
+            .any(|code| message.contains(code))
+        {
+            return None;
+        }
+        Some(Self {
+            endpoint: endpoint_host(settings),
+            message,
+            proxy: settings.proxy.clone(),
+        })
+    }
+}
+
+impl std::fmt::Display for EndpointUnreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Couldn't reach {} ({}). ", self.endpoint, self.message)?;
+        match &self.proxy {
+            Some(proxy) => write!(
+                f,
+                "Check that the proxy at {proxy} is running and allows this host."
+            ),
+            None => write!(
+                f,
+                "If your network blocks it, set `edit_predictions.copilot.proxy` in your settings."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EndpointUnreachable {}
+
+/// The code of a request error for a call that GitHub answered with 429 Too Many Requests.
+const RATE_LIMITED_ERROR_CODE: i64 = 429;
+
+/// A completion request failure that calls for something other than reinstalling Copilot.
+#[derive(Clone, Debug, PartialEq)]
+enum RequestError {
+    RateLimited,
+    Unreachable(Arc<str>),
+}
+
+impl RequestError {
+    fn detect(error: &anyhow::Error, settings: &CopilotSettings) -> Option<Self> {
+        if error
+            .downcast_ref::<lsp::ResponseError>()
+            .is_some_and(|error| error.code == RATE_LIMITED_ERROR_CODE)
+        {
+            return Some(Self::RateLimited);
+        }
+        EndpointUnreachable::detect(error, settings)
+            .map(|unreachable| Self::Unreachable(unreachable.to_string().into()))
+    }
+}
+
 fn id_for_language(language: Option<&Arc<Language>>) -> String {
     language
         .map(|language| language.lsp_id())
@@ -1095,6 +1286,74 @@ mod tests {
     use gpui::TestAppContext;
     use util::path;
 
+    #[test]
+    fn test_endpoint_unreachable() {
+        let mut settings = CopilotSettings::default();
+        let error = anyhow!("request failed: getaddrinfo ENOTFOUND api.github.com");
+        let unreachable = EndpointUnreachable::detect(&error, &settings).unwrap();
+        assert!(unreachable.to_string().starts_with("Couldn't reach github.com"));
+        assert!(unreachable.to_string().contains("edit_predictions.copilot.proxy"));
+
+        settings.enterprise_uri = Some("https://github.example.com/".into());
+        settings.proxy = Some("http://localhost:8080".into());
+        let unreachable = EndpointUnreachable::detect(&error, &settings).unwrap();
+        assert!(unreachable.to_string().starts_with("Couldn't reach github.example.com"));
+        assert!(unreachable.to_string().contains("proxy at http://localhost:8080"));
+
+        let error = anyhow!("Not authorized");
+        assert!(EndpointUnreachable::detect(&error, &settings).is_none());
+    }
+
+    #[gpui::test]
+    async fn test_completion_errors_update_status(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language::init(cx);
+        });
+        let (copilot, lsp) = Copilot::fake(cx);
+        let buffer = cx.new(|cx| Buffer::local("Hello", cx));
+
+        let mut responses = vec![
+            Ok(request::GetCompletionsResult {
+                completions: Vec::new(),
+            }),
+            Err(anyhow!("request failed: getaddrinfo ENOTFOUND api.github.com")),
+            Err(lsp::ResponseError {
+                code: RATE_LIMITED_ERROR_CODE,
+                message: "rate limit exceeded".into(),
+            }
+            .into()),
+        ];
+        lsp.set_request_handler::<request::GetCompletions, _, _>(move |_, _| {
+            let response = responses.pop().unwrap();
+            async move { response }
+        });
+        let status = |cx: &mut TestAppContext| copilot.read_with(cx, |copilot, _| copilot.status());
+
+        copilot
+            .update(cx, |copilot, cx| copilot.completions(&buffer, 0, cx))
+            .await
+            .unwrap_err();
+        assert!(matches!(status(cx), Status::RateLimited));
+        assert!(status(cx).is_authorized());
+
+        copilot
+            .update(cx, |copilot, cx| copilot.completions(&buffer, 0, cx))
+            .await
+            .unwrap_err();
+        let Status::Unreachable(message) = status(cx) else {
+            panic!("expected Copilot to be unreachable");
+        };
+        assert!(message.starts_with("Couldn't reach github.com"));
+
+        copilot
+            .update(cx, |copilot, cx| copilot.completions(&buffer, 0, cx))
+            .await
+            .unwrap();
+        assert!(matches!(status(cx), Status::Authorized));
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_buffer_management(cx: &mut TestAppContext) {
         let (copilot, mut lsp) = Copilot::fake(cx);
@@ -1191,6 +1450,7 @@ mod tests {
             .update(cx, |copilot, cx| copilot.sign_out(cx))
             .await
             .unwrap();
+        copilot.read_with(cx, |copilot, _| assert_eq!(copilot.user(), None));
         assert_eq!(
             lsp.receive_notification::<lsp::notification::DidCloseTextDocument>()
                 .await,
@@ -1216,6 +1476,7 @@ mod tests {
             .update(cx, |copilot, cx| copilot.sign_in(cx))
             .await
             .unwrap();
+        copilot.read_with(cx, |copilot, _| assert_eq!(copilot.user(), Some("user-1")));
 
         assert_eq!(
             lsp.receive_notification::<lsp::notification::DidOpenTextDocument>()
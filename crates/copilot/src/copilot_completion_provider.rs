@@ -1,11 +1,17 @@
 use crate::{Completion, Copilot};
 use anyhow::Result;
 use gpui::{App, Context, Entity, EntityId, Task};
-use inline_completion::{Direction, EditPredictionProvider, InlineCompletion};
+use inline_completion::{
+    Direction, EditPredictionError, EditPredictionProvider, EditPredictionRequestStats,
+    InlineCompletion,
+};
 use language::{Buffer, OffsetRangeExt, ToOffset, language_settings::AllLanguageSettings};
 use project::Project;
 use settings::Settings;
-use std::{path::Path, time::Duration};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
 
 pub const COPILOT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
 
@@ -17,6 +23,7 @@ pub struct CopilotCompletionProvider {
     file_extension: Option<String>,
     pending_refresh: Option<Task<Result<()>>>,
     pending_cycling_refresh: Option<Task<Result<()>>>,
+    request_stats: EditPredictionRequestStats,
     copilot: Entity<Copilot>,
 }
 
@@ -30,6 +37,7 @@ impl CopilotCompletionProvider {
             file_extension: None,
             pending_refresh: None,
             pending_cycling_refresh: None,
+            request_stats: EditPredictionRequestStats::default(),
             copilot,
         }
     }
@@ -65,6 +73,14 @@ impl EditPredictionProvider for CopilotCompletionProvider {
         self.pending_refresh.is_some()
     }
 
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        self.request_stats.last_latency()
+    }
+
+    fn last_error(&self, _cx: &App) -> Option<EditPredictionError> {
+        self.request_stats.last_error()
+    }
+
     fn is_enabled(
         &self,
         _buffer: &Entity<Buffer>,
@@ -90,11 +106,16 @@ impl EditPredictionProvider for CopilotCompletionProvider {
                     .await;
             }
 
+            let started_at = Instant::now();
             let completions = copilot
                 .update(cx, |copilot, cx| {
                     copilot.completions(&buffer, cursor_position, cx)
                 })?
-                .await?;
+                .await;
+            this.update(cx, |this, _| {
+                this.request_stats.record(started_at, &completions)
+            })?;
+            let completions = completions?;
 
             this.update(cx, |this, cx| {
                 if !completions.is_empty() {
@@ -1,8 +1,9 @@
-use crate::{Copilot, Status, request::PromptUserDeviceFlow};
+use crate::{Copilot, EndpointUnreachable, Status, request::PromptUserDeviceFlow};
 use gpui::{
     Animation, AnimationExt, App, ClipboardItem, Context, DismissEvent, Element, Entity,
     EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, MouseDownEvent,
-    ParentElement, Render, Styled, Subscription, Transformation, Window, div, percentage, svg,
+    ParentElement, Render, Styled, Subscription, Transformation, WeakEntity, Window, div,
+    percentage, svg,
 };
 use std::time::Duration;
 use ui::{Button, Label, Vector, VectorName, prelude::*};
@@ -14,6 +15,8 @@ const COPILOT_SIGN_UP_URL: &str = "https://github.com/features/copilot";
 
 struct CopilotStartingToast;
 
+struct CopilotSignInErrorToast;
+
 pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
     let Some(copilot) = Copilot::global(cx) else {
         return;
@@ -42,7 +45,7 @@ pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
                 if let Some(copilot) = cx.update(|cx| Copilot::global(cx)).ok().flatten() {
                     workspace
                         .update(cx, |workspace, cx| match copilot.read(cx).status() {
-                            Status::Authorized => workspace.show_toast(
+                            status if status.is_authorized() => workspace.show_toast(
                                 Toast::new(
                                     NotificationId::unique::<CopilotStartingToast>(),
                                     "Copilot has started!",
@@ -54,9 +57,7 @@ pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
                                     &NotificationId::unique::<CopilotStartingToast>(),
                                     cx,
                                 );
-                                copilot
-                                    .update(cx, |copilot, cx| copilot.sign_in(cx))
-                                    .detach_and_log_err(cx);
+                                sign_in_and_report_errors(&copilot, cx.weak_entity(), cx);
                                 if let Some(window_handle) = cx.active_window() {
                                     window_handle
                                         .update(cx, |_, window, cx| {
@@ -74,7 +75,7 @@ pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
             .detach();
         }
         _ => {
-            copilot.update(cx, |this, cx| this.sign_in(cx)).detach();
+            sign_in_and_report_errors(&copilot, workspace.downgrade(), cx);
             workspace.update(cx, |this, cx| {
                 this.toggle_modal(window, cx, |_, cx| {
                     CopilotCodeVerification::new(&copilot, cx)
@@ -84,6 +85,37 @@ pub fn initiate_sign_in(window: &mut Window, cx: &mut App) {
     }
 }
 
+/// Cancelling the sign-in modal also fails the sign-in, so only unreachable endpoints are
+/// worth telling the user about.
+fn sign_in_and_report_errors(
+    copilot: &Entity<Copilot>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let sign_in = copilot.update(cx, |copilot, cx| copilot.sign_in(cx));
+    cx.spawn(async move |cx| {
+        let Err(error) = sign_in.await else {
+            return;
+        };
+        let Some(unreachable) = error.downcast_ref::<EndpointUnreachable>() else {
+            log::error!("{error:?}");
+            return;
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<CopilotSignInErrorToast>(),
+                        format!("Copilot sign-in failed. {unreachable}"),
+                    ),
+                    cx,
+                )
+            })
+            .log_err();
+    })
+    .detach();
+}
+
 pub struct CopilotCodeVerification {
     status: Status,
     connect_clicked: bool,
@@ -125,9 +157,8 @@ impl CopilotCodeVerification {
             _subscription: cx.observe(copilot, |this, copilot, cx| {
                 let status = copilot.read(cx).status();
                 match status {
-                    Status::Authorized | Status::Unauthorized | Status::SigningIn { .. } => {
-                        this.set_status(status, cx)
-                    }
+                    Status::Unauthorized | Status::SigningIn { .. } => this.set_status(status, cx),
+                    status if status.is_authorized() => this.set_status(status, cx),
                     _ => cx.emit(DismissEvent),
                 }
             }),
@@ -274,7 +305,7 @@ impl Render for CopilotCodeVerification {
                 self.connect_clicked = false;
                 Self::render_unauthorized_modal(cx).into_any_element()
             }
-            Status::Authorized => {
+            status if status.is_authorized() => {
                 self.connect_clicked = false;
                 Self::render_enabled_modal(cx).into_any_element()
             }
@@ -96,13 +96,16 @@ use gpui::{
     div, impl_actions, point, prelude::*, pulsating_between, px, relative, size,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
+pub use hover_links::url_ranges;
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight, find_file};
 pub use hover_popover::hover_markdown_style;
 use hover_popover::{HoverState, hide_hover};
 use indent_guides::ActiveIndentGuidesState;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use inline_completion::Direction;
-use inline_completion::{EditPredictionProvider, InlineCompletionProviderHandle};
+use inline_completion::{
+    EditPredictionProvider, EditPredictionSnooze, InlineCompletionProviderHandle,
+};
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{
@@ -2345,6 +2348,9 @@ impl Editor {
                 _subscription: cx.observe_in(&provider, window, |this, _, window, cx| {
                     if this.focus_handle.is_focused(window) {
                         this.update_visible_inline_completion(window, cx);
+                    } else {
+                        // Lets the status bar show that a prediction arrived in the background.
+                        cx.notify();
                     }
                 }),
                 provider: Arc::new(provider),
@@ -2429,7 +2435,10 @@ impl Editor {
     ) {
         if hidden != self.inline_completions_hidden_for_vim_mode {
             self.inline_completions_hidden_for_vim_mode = hidden;
-            if hidden {
+            if self.edit_predictions_idle_for_vim_mode(cx) {
+                self.discard_inline_completion(false, cx);
+                cx.notify();
+            } else if hidden {
                 self.update_visible_inline_completion(window, cx);
             } else {
                 self.refresh_inline_completion(true, false, window, cx);
@@ -2437,6 +2446,15 @@ impl Editor {
         }
     }
 
+    /// Whether predictions are held back because vim is outside of insert mode and the
+    /// `vim_insert_mode_only` setting is on.
+    pub fn edit_predictions_idle_for_vim_mode(&self, cx: &App) -> bool {
+        self.inline_completions_hidden_for_vim_mode
+            && all_language_settings(None, cx)
+                .edit_predictions
+                .vim_insert_mode_only
+    }
+
     pub fn set_menu_inline_completions_policy(&mut self, value: MenuInlineCompletionsPolicy) {
         self.menu_inline_completions_policy = value;
     }
@@ -5976,7 +5994,10 @@ impl Editor {
         let (buffer, cursor_buffer_position) =
             self.buffer.read(cx).text_anchor_for_position(cursor, cx)?;
 
-        if !self.edit_predictions_enabled_in_buffer(&buffer, cursor_buffer_position, cx) {
+        if EditPredictionSnooze::is_paused(cx)
+            || self.edit_predictions_idle_for_vim_mode(cx)
+            || !self.edit_predictions_enabled_in_buffer(&buffer, cursor_buffer_position, cx)
+        {
             self.discard_inline_completion(false, cx);
             return None;
         }
@@ -5984,7 +6005,8 @@ impl Editor {
         if !user_requested
             && (!self.should_show_edit_predictions()
                 || !self.is_focused(window)
-                || buffer.read(cx).is_empty())
+                || buffer.read(cx).is_empty()
+                || EditPredictionSnooze::is_snoozed(cx))
         {
             self.discard_inline_completion(false, cx);
             return None;
@@ -6140,7 +6162,10 @@ impl Editor {
         let cursor = self.selections.newest_anchor().head();
         let (buffer, cursor_buffer_position) =
             self.buffer.read(cx).text_anchor_for_position(cursor, cx)?;
-        if self.inline_completions_hidden_for_vim_mode || !self.should_show_edit_predictions() {
+        if self.inline_completions_hidden_for_vim_mode
+            || !self.should_show_edit_predictions()
+            || EditPredictionSnooze::is_paused(cx)
+        {
             return None;
         }
 
@@ -15348,6 +15373,28 @@ impl Editor {
         }
     }
 
+    /// Unfolds `levels` folds on the newest cursor's line if it is folded, and folds `levels`
+    /// regions around it otherwise.
+    pub fn toggle_fold_levels(
+        &mut self,
+        levels: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let line_len = display_map.buffer_snapshot.line_len(MultiBufferRow(row));
+        if display_map
+            .folds_in_range(Point::new(row, 0)..Point::new(row, line_len))
+            .next()
+            .is_some()
+        {
+            self.unfold_levels(levels, window, cx)
+        } else {
+            self.fold_levels(levels, window, cx)
+        }
+    }
+
     pub fn fold(&mut self, _: &actions::Fold, window: &mut Window, cx: &mut Context<Self>) {
         if self.is_singleton(cx) {
             let mut to_fold = Vec::new();
@@ -15533,6 +15580,34 @@ impl Editor {
         self.fold_creases(to_fold, true, window, cx);
     }
 
+    /// Folds the `levels` innermost regions around the newest cursor that aren't folded yet.
+    pub fn fold_levels(&mut self, levels: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = &display_map.buffer_snapshot;
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let is_folded = |range: &Range<Point>| {
+            display_map.folds_in_range(range.clone()).any(|fold| {
+                fold.range.start.to_point(buffer) == range.start
+                    && fold.range.end.to_point(buffer) == range.end
+            })
+        };
+
+        let mut to_fold = Vec::new();
+        for start_row in (0..=row).rev() {
+            if to_fold.len() == levels {
+                break;
+            }
+            let Some(crease) = display_map.crease_for_buffer_row(MultiBufferRow(start_row)) else {
+                continue;
+            };
+            if crease.range().end.row >= row && !is_folded(crease.range()) {
+                to_fold.push(crease);
+            }
+        }
+
+        self.fold_creases(to_fold, true, window, cx);
+    }
+
     pub fn fold_at(
         &mut self,
         buffer_row: MultiBufferRow,
@@ -15606,6 +15681,24 @@ impl Editor {
         self.unfold_ranges(&ranges, true, true, cx);
     }
 
+    /// Unfolds the `levels` outermost folds on the newest cursor's line, keeping the folds
+    /// nested deeper than that.
+    pub fn unfold_levels(&mut self, levels: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = &display_map.buffer_snapshot;
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let line = Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row)));
+        let mut folds = display_map
+            .folds_in_range(line.clone())
+            .map(|fold| fold.range.start.to_point(buffer)..fold.range.end.to_point(buffer))
+            .collect::<Vec<_>>();
+        folds.sort_by_key(|range| (range.start, Reverse(range.end)));
+        let nested = folds.split_off(levels.min(folds.len()));
+
+        self.unfold_ranges(&[line], true, true, cx);
+        self.fold_ranges(nested, false, window, cx);
+    }
+
     pub fn unfold_at(
         &mut self,
         buffer_row: MultiBufferRow,
@@ -16400,6 +16493,14 @@ impl Editor {
         cx.notify();
     }
 
+    /// Overrides how wide hard tabs are displayed in this editor. Indentation still follows
+    /// the `tab_size` setting.
+    pub fn set_tab_size_override(&mut self, tab_size: Option<NonZeroU32>, cx: &mut Context<Self>) {
+        self.display_map
+            .update(cx, |map, _| map.set_tab_size_override(tab_size));
+        cx.notify();
+    }
+
     pub fn set_hard_wrap(&mut self, hard_wrap: Option<usize>, cx: &mut Context<Self>) {
         self.hard_wrap = hard_wrap;
         cx.notify();
@@ -703,18 +703,16 @@ pub(crate) fn find_url(
         return None;
     }
 
-    let mut finder = LinkFinder::new();
-    finder.kinds(&[LinkKind::Url]);
     let input = snapshot
         .text_for_range(token_start..token_end)
         .collect::<String>();
 
     let relative_offset = offset - token_start;
-    for link in finder.links(&input) {
-        if link.start() <= relative_offset && link.end() >= relative_offset {
-            let range = snapshot.anchor_before(token_start + link.start())
-                ..snapshot.anchor_after(token_start + link.end());
-            return Some((range, link.as_str().to_string()));
+    for link in url_ranges(&input) {
+        if link.start <= relative_offset && link.end >= relative_offset {
+            let range = snapshot.anchor_before(token_start + link.start)
+                ..snapshot.anchor_after(token_start + link.end);
+            return Some((range, input[link].to_string()));
         }
     }
     None
@@ -767,18 +765,28 @@ pub(crate) fn find_url_from_range(
         .text_for_range(token_start..token_end)
         .collect::<String>();
 
-    let mut finder = LinkFinder::new();
-    finder.kinds(&[LinkKind::Url]);
-
-    if let Some(link) = finder.links(&text).next() {
-        if link.start() == 0 && link.end() == text.len() {
-            return Some(link.as_str().to_string());
+    if let Some(link) = url_ranges(&text).first() {
+        if link.start == 0 && link.end == text.len() {
+            return Some(text);
         }
     }
 
     None
 }
 
+/// Returns the byte ranges of the URLs in `text`, in order.
+///
+/// Shared by `editor::OpenUrl`, link hovering and vim's `]u`/`[u` so that they
+/// agree on what a URL is.
+pub fn url_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Url]);
+    finder
+        .links(text)
+        .map(|link| link.start()..link.end())
+        .collect()
+}
+
 pub(crate) async fn find_file(
     buffer: &Entity<language::Buffer>,
     project: Option<Entity<Project>>,
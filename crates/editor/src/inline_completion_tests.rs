@@ -1,9 +1,9 @@
 use gpui::{Entity, prelude::*};
 use indoc::indoc;
-use inline_completion::EditPredictionProvider;
+use inline_completion::{EditPredictionProvider, EditPredictionSnooze};
 use multi_buffer::{Anchor, MultiBufferSnapshot, ToPoint};
 use project::Project;
-use std::ops::Range;
+use std::{ops::Range, time::Duration};
 use text::{Point, ToOffset};
 
 use crate::{
@@ -292,6 +292,91 @@ fn propose_edits<T: ToOffset>(
     });
 }
 
+#[gpui::test]
+async fn test_edit_prediction_snooze_expires(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    cx.update(|cx| EditPredictionSnooze::snooze(Duration::from_secs(5 * 60), cx));
+    assert!(cx.update(|cx| EditPredictionSnooze::is_snoozed(cx)));
+
+    cx.executor().advance_clock(Duration::from_secs(4 * 60));
+    assert!(cx.update(|cx| EditPredictionSnooze::is_snoozed(cx)));
+
+    cx.executor().advance_clock(Duration::from_secs(60));
+    cx.run_until_parked();
+    assert!(!cx.update(|cx| EditPredictionSnooze::is_snoozed(cx)));
+
+    cx.update(|cx| {
+        EditPredictionSnooze::snooze(Duration::from_secs(60 * 60), cx);
+        EditPredictionSnooze::resume(cx);
+    });
+    assert!(!cx.update(|cx| EditPredictionSnooze::is_snoozed(cx)));
+}
+
+#[gpui::test]
+async fn test_edit_prediction_pause_blocks_requests(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let provider = cx.new(|_| FakeInlineCompletionProvider::default());
+    assign_editor_completion_provider(provider.clone(), &mut cx);
+    cx.set_state("let absolute_zero_celsius = ˇ;");
+    let refresh = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, window, cx| {
+            editor.refresh_inline_completion(false, true, window, cx);
+        });
+    };
+    let refresh_count = |cx: &mut EditorTestContext| {
+        cx.update(|_, cx| provider.read(cx).refresh_count)
+    };
+
+    refresh(&mut cx);
+    let count = refresh_count(&mut cx);
+
+    cx.update(|_, cx| EditPredictionSnooze::pause(cx));
+    assert!(cx.update(|_, cx| EditPredictionSnooze::is_paused(cx)));
+    refresh(&mut cx);
+    assert_eq!(refresh_count(&mut cx), count);
+
+    cx.update(|_, cx| EditPredictionSnooze::resume(cx));
+    refresh(&mut cx);
+    assert_eq!(refresh_count(&mut cx), count + 1);
+}
+
+#[gpui::test]
+async fn test_vim_insert_mode_only(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings
+            .edit_predictions
+            .get_or_insert_with(Default::default)
+            .vim_insert_mode_only = Some(true);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let provider = cx.new(|_| FakeInlineCompletionProvider::default());
+    assign_editor_completion_provider(provider.clone(), &mut cx);
+    cx.set_state("let absolute_zero_celsius = ˇ;");
+    let refresh_count = |cx: &mut EditorTestContext| {
+        cx.update(|_, cx| provider.read(cx).refresh_count)
+    };
+
+    cx.update_editor(|editor, window, cx| {
+        editor.set_inline_completions_hidden_for_vim_mode(true, window, cx);
+        assert!(editor.edit_predictions_idle_for_vim_mode(cx));
+    });
+    let count = refresh_count(&mut cx);
+    cx.update_editor(|editor, window, cx| {
+        editor.refresh_inline_completion(false, true, window, cx);
+    });
+    assert_eq!(refresh_count(&mut cx), count);
+
+    cx.update_editor(|editor, window, cx| {
+        editor.set_inline_completions_hidden_for_vim_mode(false, window, cx);
+        assert!(!editor.edit_predictions_idle_for_vim_mode(cx));
+    });
+    assert_eq!(refresh_count(&mut cx), count + 1);
+}
+
 fn assign_editor_completion_provider(
     provider: Entity<FakeInlineCompletionProvider>,
     cx: &mut EditorTestContext,
@@ -304,6 +389,7 @@ fn assign_editor_completion_provider(
 #[derive(Default, Clone)]
 struct FakeInlineCompletionProvider {
     completion: Option<inline_completion::InlineCompletion>,
+    refresh_count: usize,
 }
 
 impl FakeInlineCompletionProvider {
@@ -349,6 +435,7 @@ impl EditPredictionProvider for FakeInlineCompletionProvider {
         _debounce: bool,
         _cx: &mut gpui::Context<Self>,
     ) {
+        self.refresh_count += 1;
     }
 
     fn cycle(
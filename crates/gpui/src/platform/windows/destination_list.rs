@@ -13,18 +13,21 @@ use windows::{
                 Common::{IObjectArray, IObjectCollection},
                 DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
                 PropertiesSystem::IPropertyStore,
-                ShellLink,
+                SHGSI_ICONLOCATION, SHGetStockIconInfo, SHSTOCKICONINFO, SIID_FOLDER, ShellLink,
             },
         },
     },
     core::{GUID, HSTRING, Interface},
 };
 
+use util::ResultExt;
+
 use crate::{Action, MenuItem};
 
 pub(crate) struct JumpList {
     pub(crate) dock_menus: Vec<DockMenuItem>,
     pub(crate) recent_workspaces: Vec<SmallVec<[PathBuf; 2]>>,
+    pub(crate) state: JumpListState,
 }
 
 impl JumpList {
@@ -32,10 +35,18 @@ impl JumpList {
         Self {
             dock_menus: Vec::new(),
             recent_workspaces: Vec::new(),
+            state: JumpListState::Available,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum JumpListState {
+    Available,
+    /// The shell's destination list could not be created, so updates are skipped.
+    Unavailable,
+}
+
 pub(crate) struct DockMenuItem {
     pub(crate) name: String,
     pub(crate) description: String,
@@ -63,14 +74,32 @@ impl DockMenuItem {
 
 // This code is based on the example from Microsoft:
 // https://github.com/microsoft/Windows-classic-samples/blob/main/Samples/Win7Samples/winui/shell/appshellintegration/RecipePropertyHandler/RecipePropertyHandler.cpp
-pub(crate) fn update_jump_list(
-    jump_list: &JumpList,
-) -> anyhow::Result<Vec<SmallVec<[PathBuf; 2]>>> {
-    let (list, removed) = create_destination_list()?;
-    add_recent_folders(&list, &jump_list.recent_workspaces, removed.as_ref())?;
-    add_dock_menu(&list, &jump_list.dock_menus)?;
-    unsafe { list.CommitList() }?;
-    Ok(removed)
+pub(crate) fn update_jump_list(jump_list: &mut JumpList) -> Vec<SmallVec<[PathBuf; 2]>> {
+    if jump_list.state == JumpListState::Unavailable {
+        return Vec::new();
+    }
+
+    let (list, removed) = match create_destination_list() {
+        Ok(list) => list,
+        Err(error) => {
+            // Some editions (e.g. LTSC) lack the shell's destination list, and retrying
+            // would only log the same failure on every update.
+            log::warn!("Jump list is unavailable: {error:#}");
+            jump_list.state = JumpListState::Unavailable;
+            return Vec::new();
+        }
+    };
+
+    let result = add_recent_folders(&list, &jump_list.recent_workspaces, removed.as_ref())
+        .and_then(|_| add_dock_menu(&list, &jump_list.dock_menus))
+        .and_then(|_| unsafe { list.CommitList() }.map_err(Into::into));
+    match result {
+        Ok(()) => removed,
+        Err(error) => {
+            log::warn!("Failed to update jump list: {error:#}");
+            Vec::new()
+        }
+    }
 }
 
 // Copied from:
@@ -133,6 +162,11 @@ fn add_recent_folders(
     entries: &[SmallVec<[PathBuf; 2]>],
     removed: &Vec<SmallVec<[PathBuf; 2]>>,
 ) -> anyhow::Result<()> {
+    // Prefer the shell's folder icon, which Windows picks at the right size for the current DPI.
+    // Otherwise simulate it like VS Code does:
+    // https://github.com/microsoft/vscode/blob/7a5dc239516a8953105da34f84bae152421a8886/src/vs/platform/workspaces/electron-main/workspacesHistoryMainService.ts#L380
+    let icon = folder_icon_location().unwrap_or_else(|| (HSTRING::from("explorer.exe"), 0));
+
     unsafe {
         let tasks: IObjectCollection =
             CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
@@ -155,10 +189,6 @@ fn add_recent_folders(
                     .collect::<Vec<_>>()
                     .join("\n"),
             );
-            // simulate folder icon
-            // https://github.com/microsoft/vscode/blob/7a5dc239516a8953105da34f84bae152421a8886/src/vs/platform/workspaces/electron-main/workspacesHistoryMainService.ts#L380
-            let icon = HSTRING::from("explorer.exe");
-
             let display = folder_path
                 .iter()
                 .map(|p| {
@@ -171,7 +201,7 @@ fn add_recent_folders(
             tasks.AddObject(&create_shell_link(
                 argument,
                 description,
-                Some(icon),
+                Some(&icon),
                 &display,
             )?)?;
         }
@@ -186,10 +216,20 @@ fn is_item_in_array(item: &SmallVec<[PathBuf; 2]>, removed: &Vec<SmallVec<[PathB
     removed.iter().any(|removed_item| removed_item == item)
 }
 
+fn folder_icon_location() -> Option<(HSTRING, i32)> {
+    let mut info = SHSTOCKICONINFO {
+        cbSize: std::mem::size_of::<SHSTOCKICONINFO>() as u32,
+        ..Default::default()
+    };
+    unsafe { SHGetStockIconInfo(SIID_FOLDER, SHGSI_ICONLOCATION, &mut info) }.log_err()?;
+    let len = unsafe { u_strlen(info.szPath.as_ptr()) };
+    Some((HSTRING::from_wide(&info.szPath[..len as usize]), info.iIcon))
+}
+
 fn create_shell_link(
     argument: HSTRING,
     description: HSTRING,
-    icon: Option<HSTRING>,
+    icon: Option<&(HSTRING, i32)>,
     display: &str,
 ) -> anyhow::Result<IShellLinkW> {
     unsafe {
@@ -198,8 +238,8 @@ fn create_shell_link(
         link.SetPath(&exe_path)?;
         link.SetArguments(&argument)?;
         link.SetDescription(&description)?;
-        if let Some(icon) = icon {
-            link.SetIconLocation(&icon, 0)?;
+        if let Some((icon_path, icon_index)) = icon {
+            link.SetIconLocation(icon_path, *icon_index)?;
         }
         let store: IPropertyStore = link.cast()?;
         let title = PROPVARIANT::from(display);
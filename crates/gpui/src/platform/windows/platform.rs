@@ -261,7 +261,7 @@ impl WindowsPlatform {
         });
         let mut lock = self.state.borrow_mut();
         lock.jump_list.dock_menus = actions;
-        update_jump_list(&lock.jump_list).log_err();
+        update_jump_list(&mut lock.jump_list);
     }
 
     fn update_jump_list(
@@ -278,9 +278,7 @@ impl WindowsPlatform {
         let mut lock = self.state.borrow_mut();
         lock.jump_list.dock_menus = actions;
         lock.jump_list.recent_workspaces = entries;
-        update_jump_list(&lock.jump_list)
-            .log_err()
-            .unwrap_or_default()
+        update_jump_list(&mut lock.jump_list)
     }
 }
 
@@ -24,6 +24,8 @@ pub struct GithubRelease {
 pub struct GithubReleaseAsset {
     pub name: String,
     pub browser_download_url: String,
+    /// The `sha256:<hex>` digest GitHub computed when the asset was uploaded.
+    pub digest: Option<String>,
 }
 
 pub async fn latest_github_release(
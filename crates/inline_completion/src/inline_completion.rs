@@ -1,10 +1,13 @@
+use std::collections::HashMap;
 use std::ops::Range;
 use std::str::FromStr as _;
+use std::time::{Duration, Instant};
 
 use anyhow::{Result, anyhow};
+use futures::stream::BoxStream;
 use gpui::http_client::http::{HeaderMap, HeaderValue};
-use gpui::{App, Context, Entity, SharedString};
-use language::Buffer;
+use gpui::{App, Context, Entity, Global, SharedString, Task};
+use language::{Buffer, LanguageName};
 use project::Project;
 use zed_llm_client::{
     EDIT_PREDICTIONS_USAGE_AMOUNT_HEADER_NAME, EDIT_PREDICTIONS_USAGE_LIMIT_HEADER_NAME, UsageLimit,
@@ -28,13 +31,30 @@ pub struct InlineCompletion {
     pub edit_preview: Option<language::EditPreview>,
 }
 
+/// A piece of a prediction that's still being generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InlineCompletionChunk {
+    /// Text to append to the prediction.
+    Token(String),
+    /// The prediction is complete.
+    Done,
+}
+
 pub enum DataCollectionState {
     /// The provider doesn't support data collection.
     Unsupported,
     /// Data collection is enabled.
-    Enabled { is_project_open_source: bool },
+    Enabled {
+        is_project_open_source: bool,
+        /// Whether the worktree's settings decide this rather than the global choice.
+        is_worktree_override: bool,
+    },
     /// Data collection is disabled or unanswered.
-    Disabled { is_project_open_source: bool },
+    Disabled {
+        is_project_open_source: bool,
+        /// Whether the worktree's settings decide this rather than the global choice.
+        is_worktree_override: bool,
+    },
 }
 
 impl DataCollectionState {
@@ -50,13 +70,29 @@ impl DataCollectionState {
         match self {
             Self::Enabled {
                 is_project_open_source,
+                ..
             }
             | Self::Disabled {
                 is_project_open_source,
+                ..
             } => *is_project_open_source,
             _ => false,
         }
     }
+
+    pub fn is_worktree_override(&self) -> bool {
+        match self {
+            Self::Enabled {
+                is_worktree_override,
+                ..
+            }
+            | Self::Disabled {
+                is_worktree_override,
+                ..
+            } => *is_worktree_override,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -85,6 +121,157 @@ impl EditPredictionUsage {
     }
 }
 
+/// Pauses edit predictions in every editor for a while, or until Zed restarts, without changing
+/// settings.
+#[derive(Default)]
+pub struct EditPredictionSnooze {
+    until: Option<Instant>,
+    paused: bool,
+    _expire: Option<Task<()>>,
+}
+
+impl Global for EditPredictionSnooze {}
+
+impl EditPredictionSnooze {
+    pub fn snooze(duration: Duration, cx: &mut App) {
+        let expire = cx.spawn(async move |cx| {
+            cx.background_executor().timer(duration).await;
+            cx.update(Self::resume).ok();
+        });
+        cx.set_global(Self {
+            until: Some(Instant::now() + duration),
+            paused: false,
+            _expire: Some(expire),
+        });
+    }
+
+    /// Stops every prediction request, including manually triggered ones, until Zed restarts
+    /// or `resume` is called.
+    pub fn pause(cx: &mut App) {
+        cx.set_global(Self {
+            paused: true,
+            ..Self::default()
+        });
+    }
+
+    pub fn resume(cx: &mut App) {
+        cx.set_global(Self::default());
+    }
+
+    /// How much longer predictions stay snoozed, if they are.
+    pub fn remaining(cx: &App) -> Option<Duration> {
+        let until = cx.try_global::<Self>()?.until?;
+        Some(until.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn is_snoozed(cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .map_or(false, |snooze| snooze.until.is_some())
+    }
+
+    pub fn is_paused(cx: &App) -> bool {
+        cx.try_global::<Self>().map_or(false, |snooze| snooze.paused)
+    }
+}
+
+/// How a provider's most recent prediction requests went, shown in the status bar menu.
+#[derive(Clone, Debug, Default)]
+pub struct EditPredictionRequestStats {
+    last_latency: Option<Duration>,
+    last_error: Option<EditPredictionError>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EditPredictionError {
+    pub message: SharedString,
+    pub occurred_at: Instant,
+}
+
+impl EditPredictionRequestStats {
+    pub fn record<T>(&mut self, started_at: Instant, result: &Result<T>) {
+        match result {
+            Ok(_) => self.record_success(started_at),
+            Err(error) => self.record_failure(error),
+        }
+    }
+
+    pub fn record_success(&mut self, started_at: Instant) {
+        self.last_latency = Some(started_at.elapsed());
+        self.last_error = None;
+    }
+
+    pub fn record_failure(&mut self, error: &anyhow::Error) {
+        let message = error.to_string();
+        self.last_error = Some(EditPredictionError {
+            message: message.lines().next().unwrap_or_default().to_string().into(),
+            occurred_at: Instant::now(),
+        });
+    }
+
+    pub fn last_latency(&self) -> Option<Duration> {
+        self.last_latency
+    }
+
+    pub fn last_error(&self) -> Option<EditPredictionError> {
+        self.last_error.clone()
+    }
+}
+
+/// How many of a provider's predictions were shown, and how many of those were accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AcceptanceCounts {
+    pub shown: usize,
+    pub accepted: usize,
+}
+
+impl AcceptanceCounts {
+    /// The share of shown predictions that were accepted, from 0 to 1.
+    pub fn acceptance_rate(&self) -> Option<f32> {
+        (self.shown > 0).then(|| self.accepted as f32 / self.shown as f32)
+    }
+}
+
+/// A provider's acceptance counts for the current session, overall and per language.
+#[derive(Clone, Debug, Default)]
+pub struct EditPredictionAcceptanceStats {
+    total: AcceptanceCounts,
+    by_language: HashMap<LanguageName, AcceptanceCounts>,
+}
+
+impl EditPredictionAcceptanceStats {
+    pub fn record_shown(&mut self, language: Option<LanguageName>) {
+        self.total.shown += 1;
+        if let Some(language) = language {
+            self.by_language.entry(language).or_default().shown += 1;
+        }
+    }
+
+    pub fn record_accepted(&mut self, language: Option<LanguageName>) {
+        self.total.accepted += 1;
+        if let Some(language) = language {
+            self.by_language.entry(language).or_default().accepted += 1;
+        }
+    }
+
+    pub fn total(&self) -> AcceptanceCounts {
+        self.total
+    }
+
+    /// The `count` languages with the most shown predictions, most shown first.
+    pub fn top_languages(&self, count: usize) -> Vec<(LanguageName, AcceptanceCounts)> {
+        let mut languages = self
+            .by_language
+            .iter()
+            .map(|(language, counts)| (language.clone(), *counts))
+            .collect::<Vec<_>>();
+        languages.sort_by(|(a_language, a), (b_language, b)| {
+            b.shown.cmp(&a.shown).then_with(|| a_language.cmp(b_language))
+        });
+        languages.truncate(count);
+        languages
+    }
+}
+
 pub trait EditPredictionProvider: 'static + Sized {
     fn name() -> &'static str;
     fn display_name() -> &'static str;
@@ -100,6 +287,30 @@ pub trait EditPredictionProvider: 'static + Sized {
         None
     }
 
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        None
+    }
+
+    fn last_error(&self, _cx: &App) -> Option<EditPredictionError> {
+        None
+    }
+
+    fn acceptance_stats(&self, _cx: &App) -> Option<EditPredictionAcceptanceStats> {
+        None
+    }
+
+    fn reset_acceptance_stats(&mut self, _cx: &mut App) {}
+
+    /// The models predictions can come from, for providers that offer a choice.
+    fn models(&self, _cx: &App) -> Vec<SharedString> {
+        Vec::new()
+    }
+
+    /// The model predictions currently come from, if the provider knows it.
+    fn active_model(&self, _cx: &App) -> Option<SharedString> {
+        None
+    }
+
     fn toggle_data_collection(&mut self, _cx: &mut App) {}
     fn is_enabled(
         &self,
@@ -108,6 +319,10 @@ pub trait EditPredictionProvider: 'static + Sized {
         cx: &App,
     ) -> bool;
     fn is_refreshing(&self) -> bool;
+    /// Whether a completion is ready for the cursor but the editor hasn't displayed it yet.
+    fn has_pending_suggestion(&self, _cx: &App) -> bool {
+        false
+    }
     fn refresh(
         &mut self,
         project: Option<Entity<Project>>,
@@ -134,6 +349,17 @@ pub trait EditPredictionProvider: 'static + Sized {
         cursor_position: language::Anchor,
         cx: &mut Context<Self>,
     ) -> Option<InlineCompletion>;
+    /// Requests a prediction for the cursor as it's generated, for providers whose backend
+    /// streams. The editor doesn't read the stream: such providers drive `refresh` from it and
+    /// notify as each chunk arrives, so that the ghost text grows with `suggest`.
+    fn complete_streaming(
+        &mut self,
+        _buffer: &Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        _cx: &mut Context<Self>,
+    ) -> Option<BoxStream<'static, Result<InlineCompletionChunk>>> {
+        None
+    }
 }
 
 pub trait InlineCompletionProviderHandle {
@@ -149,9 +375,16 @@ pub trait InlineCompletionProviderHandle {
     fn show_tab_accept_marker(&self) -> bool;
     fn data_collection_state(&self, cx: &App) -> DataCollectionState;
     fn usage(&self, cx: &App) -> Option<EditPredictionUsage>;
+    fn last_request_latency(&self, cx: &App) -> Option<Duration>;
+    fn last_error(&self, cx: &App) -> Option<EditPredictionError>;
+    fn acceptance_stats(&self, cx: &App) -> Option<EditPredictionAcceptanceStats>;
+    fn reset_acceptance_stats(&self, cx: &mut App);
+    fn models(&self, cx: &App) -> Vec<SharedString>;
+    fn active_model(&self, cx: &App) -> Option<SharedString>;
     fn toggle_data_collection(&self, cx: &mut App);
     fn needs_terms_acceptance(&self, cx: &App) -> bool;
     fn is_refreshing(&self, cx: &App) -> bool;
+    fn has_pending_suggestion(&self, cx: &App) -> bool;
     fn refresh(
         &self,
         project: Option<Entity<Project>>,
@@ -205,6 +438,30 @@ where
         self.read(cx).usage(cx)
     }
 
+    fn last_request_latency(&self, cx: &App) -> Option<Duration> {
+        self.read(cx).last_request_latency(cx)
+    }
+
+    fn last_error(&self, cx: &App) -> Option<EditPredictionError> {
+        self.read(cx).last_error(cx)
+    }
+
+    fn acceptance_stats(&self, cx: &App) -> Option<EditPredictionAcceptanceStats> {
+        self.read(cx).acceptance_stats(cx)
+    }
+
+    fn reset_acceptance_stats(&self, cx: &mut App) {
+        self.update(cx, |this, cx| this.reset_acceptance_stats(cx))
+    }
+
+    fn models(&self, cx: &App) -> Vec<SharedString> {
+        self.read(cx).models(cx)
+    }
+
+    fn active_model(&self, cx: &App) -> Option<SharedString> {
+        self.read(cx).active_model(cx)
+    }
+
     fn toggle_data_collection(&self, cx: &mut App) {
         self.update(cx, |this, cx| this.toggle_data_collection(cx))
     }
@@ -226,6 +483,10 @@ where
         self.read(cx).is_refreshing()
     }
 
+    fn has_pending_suggestion(&self, cx: &App) -> bool {
+        self.read(cx).has_pending_suggestion(cx)
+    }
+
     fn refresh(
         &self,
         project: Option<Entity<Project>>,
@@ -1,27 +1,37 @@
 use anyhow::Result;
 use client::{UserStore, zed_urls};
+use codeium::{Codeium, CodeiumStatus};
 use copilot::{Copilot, Status};
 use editor::{
-    Editor,
+    Editor, EditorEvent, MultiBuffer,
     actions::{ShowEditPrediction, ToggleEditPrediction},
     scroll::Autoscroll,
 };
 use feature_flags::{FeatureFlagAppExt, PredictEditsRateCompletionsFeatureFlag};
 use fs::Fs;
 use gpui::{
-    Action, Animation, AnimationExt, App, AsyncWindowContext, Corner, Entity, FocusHandle,
-    Focusable, IntoElement, ParentElement, Render, Subscription, WeakEntity, actions, div,
-    pulsating_between,
+    Action, Animation, AnimationElement, AnimationExt, App, AsyncWindowContext, ClickEvent, Corner,
+    CursorStyle, Entity, FocusHandle, Focusable, Global, IntoElement, MouseButton,
+    MouseDownEvent, ParentElement, PromptLevel, Render, Subscription, Task, WeakEntity, actions,
+    div, pulsating_between,
 };
 use indoc::indoc;
-use inline_completion::EditPredictionUsage;
+use inline_completion::{AcceptanceCounts, EditPredictionSnooze, EditPredictionUsage};
 use language::{
-    EditPredictionsMode, File, Language,
-    language_settings::{self, AllLanguageSettings, EditPredictionProvider, all_language_settings},
+    EditPredictionsMode, File, Language, LanguageName,
+    language_settings::{
+        self, AllLanguageSettings, AllLanguageSettingsContent, EditPredictionProvider,
+        all_language_settings,
+    },
 };
+use ollama_completion::{LocalModel, LocalModelStatus};
 use regex::Regex;
-use settings::{Settings, SettingsStore, update_settings_file};
+use settings::{
+    Settings, SettingsStore, parse_json_with_comments, update_local_settings_file,
+    update_settings_file,
+};
 use std::{
+    path::{Path, PathBuf},
     sync::{Arc, LazyLock},
     time::Duration,
 };
@@ -30,32 +40,146 @@ use ui::{
     Clickable, ContextMenu, ContextMenuEntry, DocumentationSide, IconButton, IconButtonShape,
     Indicator, PopoverMenu, PopoverMenuHandle, ProgressBar, Tooltip, prelude::*,
 };
-use util::maybe;
+use util::{maybe, truncate_and_trailoff};
+use vim_mode_setting::VimModeSetting;
 use workspace::{
-    StatusItemView, Toast, Workspace, create_and_open_local_file, item::ItemHandle,
-    notifications::NotificationId,
+    StatusItemView, Toast, Workspace, create_and_open_local_file,
+    item::ItemHandle,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
 };
 use zed_actions::OpenBrowser;
 use zed_llm_client::UsageLimit;
-use zeta::RateCompletions;
-
-actions!(edit_prediction, [ToggleMenu]);
+use zeta::{RateCompletions, RateLastCompletionBad, RateLastCompletionGood};
+
+actions!(
+    edit_prediction,
+    [
+        ToggleMenu,
+        CycleProvider,
+        ToggleForAllFiles,
+        ToggleForLanguage,
+        ExcludeFile
+    ]
+);
 
 const COPILOT_SETTINGS_URL: &str = "https://github.com/settings/copilot";
 
+const SNOOZE_DURATIONS: [(&str, Duration); 3] = [
+    ("For 5 Minutes", Duration::from_secs(5 * 60)),
+    ("For 30 Minutes", Duration::from_secs(30 * 60)),
+    ("For 1 Hour", Duration::from_secs(60 * 60)),
+];
+
+/// The order in which `CycleProvider` rotates through the providers.
+const PROVIDERS: [EditPredictionProvider; 6] = [
+    EditPredictionProvider::Copilot,
+    EditPredictionProvider::Supermaven,
+    EditPredictionProvider::Zed,
+    EditPredictionProvider::Codeium,
+    EditPredictionProvider::Ollama,
+    EditPredictionProvider::None,
+];
+
 struct CopilotErrorToast;
 
+struct CodeiumErrorToast;
+
+struct ProviderSwitchedToast;
+
+struct ExcludedGlobToast;
+
+struct GlobalToggleToast;
+
+struct SupermavenActivatedToast;
+
+/// The provider set with [`InlineCompletionButton::set_provider_override`].
+pub struct ProviderOverride(Option<EditPredictionProvider>);
+
+impl Global for ProviderOverride {}
+
+/// The provider that editors and the status bar use in place of the configured one, if any.
+pub fn provider_override(cx: &App) -> Option<EditPredictionProvider> {
+    cx.try_global::<ProviderOverride>()
+        .and_then(|provider_override| provider_override.0)
+}
+
+/// How long the cursor has to rest before the button follows it into another language, such
+/// as an injected one.
+const LANGUAGE_UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// How long to wait for Supermaven to report the account as activated after "Sign In"
+/// opened the activation page.
+const SUPERMAVEN_ACTIVATION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A Supermaven activation started from the menu, so that the button can confirm it once
+/// the agent reports the account as ready.
+struct PendingSupermavenActivation {
+    workspace: WeakEntity<Workspace>,
+    _timeout: Task<()>,
+}
+
 pub struct InlineCompletionButton {
     editor_subscription: Option<(Subscription, usize)>,
-    editor_enabled: Option<bool>,
+    _selections_subscription: Option<Subscription>,
+    update_language: Task<()>,
+    editor_status: Option<FileStatus>,
     editor_show_predictions: bool,
+    editor_idle_for_vim_mode: bool,
     editor_focus_handle: Option<FocusHandle>,
     language: Option<Arc<Language>>,
+    /// The language of the active buffer as a whole, which decides its provider.
+    file_language: Option<LanguageName>,
     file: Option<Arc<dyn File>>,
     edit_prediction_provider: Option<Arc<dyn inline_completion::InlineCompletionProviderHandle>>,
     fs: Arc<dyn Fs>,
     user_store: Entity<UserStore>,
     popover_menu_handle: PopoverMenuHandle<ContextMenu>,
+    supermaven_activation: Option<PendingSupermavenActivation>,
+}
+
+/// Whether edit predictions are available for the active file, and which rule decided it.
+#[derive(Clone)]
+enum FileStatus {
+    Enabled,
+    ExcludedByGlob { glob: String, is_default: bool },
+    DisabledForLanguage(Arc<Language>),
+    ProviderNotSignedIn(EditPredictionProvider),
+}
+
+/// How much of the workspace around the active file an "Exclude" entry adds to `disabled_globs`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Exclusion {
+    File,
+    Folder,
+    Extension,
+}
+
+impl Exclusion {
+    const ALL: [Exclusion; 3] = [Exclusion::File, Exclusion::Folder, Exclusion::Extension];
+
+    fn label(self) -> &'static str {
+        match self {
+            Exclusion::File => "Exclude This File",
+            Exclusion::Folder => "Exclude Folder",
+            Exclusion::Extension => "Exclude by Extension",
+        }
+    }
+
+    /// Returns the glob for a file at `path`, relative to its worktree, or `None` if there's
+    /// nothing to exclude at this scope (e.g. a file without an extension).
+    fn glob(self, path: &Path) -> Option<String> {
+        match self {
+            Exclusion::File => Some(path.to_string_lossy().into_owned()),
+            Exclusion::Folder => {
+                let folder = path.parent().filter(|parent| !parent.as_os_str().is_empty())?;
+                Some(folder.join("**").to_string_lossy().into_owned())
+            }
+            Exclusion::Extension => {
+                let extension = path.extension()?.to_string_lossy();
+                Some(format!("**/*.{extension}"))
+            }
+        }
+    }
 }
 
 enum SupermavenButtonStatus {
@@ -69,7 +193,21 @@ impl Render for InlineCompletionButton {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let all_language_settings = all_language_settings(None, cx);
 
-        match all_language_settings.edit_predictions.provider {
+        if !all_language_settings.edit_predictions.show_status_button {
+            let this = cx.entity().clone();
+            return div().child(
+                PopoverMenu::new("edit-prediction-menu")
+                    .menu(move |window, cx| {
+                        this.update(cx, |this, cx| this.build_menu_without_button(window, cx))
+                    })
+                    .anchor(Corner::BottomRight)
+                    .trigger(HiddenButtonAnchor)
+                    .with_handle(self.popover_menu_handle.clone()),
+            );
+        }
+
+        let idle_for_vim_mode = self.editor_idle_for_vim_mode;
+        let button = match self.provider(cx) {
             EditPredictionProvider::None => div(),
 
             EditPredictionProvider::Copilot => {
@@ -78,12 +216,15 @@ impl Render for InlineCompletionButton {
                 };
                 let status = copilot.read(cx).status();
 
-                let enabled = self.editor_enabled.unwrap_or(false);
+                let enabled = self.editor_enabled().unwrap_or(false);
 
                 let icon = match status {
-                    Status::Error(_) => IconName::CopilotError,
+                    Status::Error(_) | Status::RateLimited => IconName::CopilotError,
+                    Status::Unreachable(_) => IconName::Disconnected,
                     Status::Authorized => {
-                        if enabled {
+                        if let Some(icon) = snooze_icon(cx) {
+                            icon
+                        } else if enabled {
                             IconName::Copilot
                         } else {
                             IconName::CopilotDisabled
@@ -91,64 +232,92 @@ impl Render for InlineCompletionButton {
                     }
                     _ => IconName::CopilotInit,
                 };
+                let problem = copilot_status_meta(&status);
 
-                if let Status::Error(e) = status {
+                if let Status::Error(_) = status {
                     return div().child(
                         IconButton::new("copilot-error", icon)
                             .icon_size(IconSize::Small)
                             .on_click(cx.listener(move |_, _, window, cx| {
                                 if let Some(workspace) = window.root::<Workspace>().flatten() {
                                     workspace.update(cx, |workspace, cx| {
-                                        workspace.show_toast(
-                                            Toast::new(
-                                                NotificationId::unique::<CopilotErrorToast>(),
-                                                format!("Copilot can't be started: {}", e),
-                                            )
-                                            .on_click(
-                                                "Reinstall Copilot",
-                                                |_, cx| {
-                                                    if let Some(copilot) = Copilot::global(cx) {
-                                                        copilot
-                                                            .update(cx, |copilot, cx| {
-                                                                copilot.reinstall(cx)
-                                                            })
-                                                            .detach();
-                                                    }
-                                                },
-                                            ),
-                                            cx,
-                                        );
+                                        show_copilot_error(&status, workspace, cx)
                                     });
                                 }
                             }))
-                            .tooltip(|window, cx| {
-                                Tooltip::for_action("GitHub Copilot", &ToggleMenu, window, cx)
+                            .tooltip(move |window, cx| {
+                                Tooltip::with_meta(
+                                    "GitHub Copilot",
+                                    Some(&ToggleMenu),
+                                    problem.unwrap_or_default(),
+                                    window,
+                                    cx,
+                                )
                             }),
                     );
                 }
+                let is_starting =
+                    matches!(status, Status::Starting { .. } | Status::SigningIn { .. });
+                let needs_sign_in =
+                    matches!(status, Status::SignedOut { .. } | Status::Unauthorized);
+                let is_authorized = status.is_authorized();
+                let show_predictions = self.editor_show_predictions;
+                let hidden_for_file = is_authorized && enabled && !show_predictions;
+                let rate_limited = matches!(status, Status::RateLimited);
                 let this = cx.entity().clone();
 
-                div().child(
-                    PopoverMenu::new("copilot")
-                        .menu(move |window, cx| {
-                            Some(match status {
-                                Status::Authorized => this.update(cx, |this, cx| {
-                                    this.build_copilot_context_menu(window, cx)
-                                }),
-                                _ => this.update(cx, |this, cx| {
-                                    this.build_copilot_start_menu(window, cx)
-                                }),
-                            })
+                let popover_menu = PopoverMenu::new("copilot")
+                    .menu(move |window, cx| {
+                        Some(if status.is_authorized() {
+                            this.update(cx, |this, cx| this.build_copilot_context_menu(window, cx))
+                        } else {
+                            this.update(cx, |this, cx| this.build_copilot_start_menu(window, cx))
                         })
-                        .anchor(Corner::BottomRight)
-                        .trigger_with_tooltip(
-                            IconButton::new("copilot-icon", icon),
-                            |window, cx| {
-                                Tooltip::for_action("GitHub Copilot", &ToggleMenu, window, cx)
-                            },
+                    })
+                    .anchor(Corner::BottomRight)
+                    .with_handle(self.popover_menu_handle.clone());
+                let tooltip = move |window: &mut Window, cx: &mut App| {
+                    if let Some(meta) = problem {
+                        Tooltip::with_meta("GitHub Copilot", Some(&ToggleMenu), meta, window, cx)
+                    } else if let Some(meta) = status_meta(idle_for_vim_mode, cx) {
+                        Tooltip::with_meta("GitHub Copilot", Some(&ToggleMenu), meta, window, cx)
+                    } else if is_authorized {
+                        Tooltip::with_meta(
+                            "GitHub Copilot",
+                            Some(&ToggleMenu),
+                            file_state_meta(enabled, show_predictions),
+                            window,
+                            cx,
                         )
-                        .with_handle(self.popover_menu_handle.clone()),
-                )
+                    } else {
+                        Tooltip::for_action("GitHub Copilot", &ToggleMenu, window, cx)
+                    }
+                };
+                let status_bar_background = cx.theme().colors().status_bar_background;
+                let icon_button = IconButton::new("copilot-icon", icon)
+                    .when(rate_limited, |this| this.icon_color(Color::Warning))
+                    .when(hidden_for_file, |this| {
+                        this.indicator(Indicator::dot().color(Color::Muted))
+                            .indicator_border_color(Some(status_bar_background))
+                    })
+                    .when(needs_sign_in, |this| {
+                        this.indicator(Indicator::dot().color(Color::Warning))
+                            .indicator_border_color(Some(status_bar_background))
+                    });
+
+                let popover_menu = if is_starting {
+                    popover_menu.trigger(StatusTrigger::busy(
+                        icon_button.when(!self.popover_menu_handle.is_deployed(), |button| {
+                            button.tooltip(tooltip)
+                        }),
+                        hidden_for_file || needs_sign_in,
+                        cx,
+                    ))
+                } else {
+                    popover_menu.trigger_with_tooltip(icon_button, tooltip)
+                };
+
+                div().child(popover_menu)
             }
 
             EditPredictionProvider::Supermaven => {
@@ -178,45 +347,96 @@ impl Render for InlineCompletionButton {
                     }
                 };
 
-                let icon = status.to_icon();
+                let icon = match status {
+                    SupermavenButtonStatus::Ready => {
+                        snooze_icon(cx).unwrap_or_else(|| status.to_icon())
+                    }
+                    _ => status.to_icon(),
+                };
                 let tooltip_text = status.to_tooltip();
                 let has_menu = status.has_menu();
+                let is_ready = matches!(status, SupermavenButtonStatus::Ready);
+                let enabled = self.editor_enabled().unwrap_or(false);
+                let show_predictions = self.editor_show_predictions;
+                let hidden_for_file = is_ready && enabled && !show_predictions;
+                let status_bar_background = cx.theme().colors().status_bar_background;
+                let is_initializing = matches!(status, SupermavenButtonStatus::Initializing);
                 let this = cx.entity().clone();
                 let fs = self.fs.clone();
+                let user_store = self.user_store.clone();
+
+                if is_initializing {
+                    return div().child(StatusTrigger::busy(
+                        IconButton::new("supermaven-icon", icon)
+                            .tooltip(Tooltip::text(tooltip_text)),
+                        false,
+                        cx,
+                    ));
+                }
 
                 return div().child(
                     PopoverMenu::new("supermaven")
                         .menu(move |window, cx| match &status {
                             SupermavenButtonStatus::NeedsActivation(activate_url) => {
-                                Some(ContextMenu::build(window, cx, |menu, _, _| {
-                                    let fs = fs.clone();
+                                Some(ContextMenu::build(window, cx, |menu, _, cx| {
                                     let activate_url = activate_url.clone();
-                                    menu.entry("Sign In", None, move |_, cx| {
-                                        cx.open_url(activate_url.as_str())
-                                    })
-                                    .entry(
-                                        "Use Copilot",
-                                        None,
-                                        move |_, cx| {
-                                            set_completion_provider(
-                                                fs.clone(),
-                                                cx,
-                                                EditPredictionProvider::Copilot,
-                                            )
-                                        },
+                                    let this = this.clone();
+                                    let menu = menu.entry("Sign In", None, move |window, cx| {
+                                        cx.open_url(activate_url.as_str());
+                                        let workspace = window.root::<Workspace>().flatten();
+                                        this.update(cx, |this, cx| {
+                                            this.await_supermaven_activation(workspace, cx)
+                                        });
+                                    });
+                                    add_use_provider_entries(
+                                        menu,
+                                        EditPredictionProvider::Supermaven,
+                                        fs.clone(),
+                                        user_store.clone(),
+                                        cx,
                                     )
                                 }))
                             }
                             SupermavenButtonStatus::Ready => Some(this.update(cx, |this, cx| {
                                 this.build_supermaven_context_menu(window, cx)
                             })),
-                            _ => None,
+                            SupermavenButtonStatus::Errored(_) => {
+                                Some(ContextMenu::build(window, cx, |menu, _, _| {
+                                    menu.action(
+                                        "Restart Agent",
+                                        supermaven::RestartAgent.boxed_clone(),
+                                    )
+                                    .entry("View Diagnostics", None, open_supermaven_diagnostics)
+                                }))
+                            }
+                            SupermavenButtonStatus::Initializing => None,
                         })
                         .anchor(Corner::BottomRight)
                         .trigger_with_tooltip(
-                            IconButton::new("supermaven-icon", icon),
+                            IconButton::new("supermaven-icon", icon).when(hidden_for_file, |this| {
+                                this.indicator(Indicator::dot().color(Color::Muted))
+                                    .indicator_border_color(Some(status_bar_background))
+                            }),
                             move |window, cx| {
-                                if has_menu {
+                                if let Some(meta) =
+                                    status_meta(idle_for_vim_mode, cx).filter(|_| has_menu)
+                                {
+                                    Tooltip::with_meta(
+                                        tooltip_text.clone(),
+                                        Some(&ToggleMenu),
+                                        meta,
+                                        window,
+                                        cx,
+                                    )
+                                } else if is_ready {
+                                    Tooltip::with_meta(
+                                        tooltip_text.clone(),
+                                        Some(&ToggleMenu),
+                                        file_state_meta(enabled, show_predictions),
+                                        window,
+                                        cx,
+                                    )
+                                } else if has_menu {
                                     Tooltip::for_action(
                                         tooltip_text.clone(),
                                         &ToggleMenu,
@@ -232,10 +452,176 @@ impl Render for InlineCompletionButton {
                 );
             }
 
+            EditPredictionProvider::Codeium => {
+                let Some(codeium) = Codeium::global(cx) else {
+                    return div();
+                };
+                let status = codeium.read(cx).status().clone();
+                let status_bar_background = cx.theme().colors().status_bar_background;
+
+                if let CodeiumStatus::Error(error) = status {
+                    return div().child(
+                        IconButton::new("codeium-error", IconName::Sparkle)
+                            .icon_size(IconSize::Small)
+                            .indicator(Indicator::dot().color(Color::Error))
+                            .indicator_border_color(Some(status_bar_background))
+                            .on_click(cx.listener(move |_, _, window, cx| {
+                                if let Some(workspace) = window.root::<Workspace>().flatten() {
+                                    workspace.update(cx, |workspace, cx| {
+                                        workspace.show_toast(
+                                            Toast::new(
+                                                NotificationId::unique::<CodeiumErrorToast>(),
+                                                format!("Codeium can't be started: {error}"),
+                                            )
+                                            .on_click(
+                                                "Restart Codeium",
+                                                |_, cx| {
+                                                    if let Some(codeium) = Codeium::global(cx) {
+                                                        codeium.update(cx, |codeium, cx| {
+                                                            codeium.restart(cx)
+                                                        });
+                                                    }
+                                                },
+                                            ),
+                                            cx,
+                                        );
+                                    });
+                                }
+                            }))
+                            .tooltip(|window, cx| {
+                                Tooltip::for_action("Codeium", &ToggleMenu, window, cx)
+                            }),
+                    );
+                }
+
+                let enabled = self.editor_enabled().unwrap_or(false);
+                let is_running = matches!(status, CodeiumStatus::Running);
+                let is_starting =
+                    matches!(status, CodeiumStatus::Stopped | CodeiumStatus::Starting);
+                let show_predictions = self.editor_show_predictions;
+                let disabled_for_file = is_running && !enabled;
+                let hidden_for_file = is_running && enabled && !show_predictions;
+                let icon = snooze_icon(cx)
+                    .filter(|_| is_running)
+                    .unwrap_or(IconName::Sparkle);
+                let this = cx.entity().clone();
+
+                let popover_menu = PopoverMenu::new("codeium")
+                    .menu(move |window, cx| {
+                        Some(if is_running {
+                            this.update(cx, |this, cx| this.build_codeium_context_menu(window, cx))
+                        } else {
+                            this.update(cx, |this, cx| this.build_codeium_start_menu(window, cx))
+                        })
+                    })
+                    .anchor(Corner::BottomRight)
+                    .with_handle(self.popover_menu_handle.clone());
+                let tooltip = move |window: &mut Window, cx: &mut App| {
+                    if let Some(meta) = status_meta(idle_for_vim_mode, cx).filter(|_| is_running) {
+                        Tooltip::with_meta("Codeium", Some(&ToggleMenu), meta, window, cx)
+                    } else if is_running {
+                        Tooltip::with_meta(
+                            "Codeium",
+                            Some(&ToggleMenu),
+                            file_state_meta(enabled, show_predictions),
+                            window,
+                            cx,
+                        )
+                    } else {
+                        Tooltip::for_action("Codeium", &ToggleMenu, window, cx)
+                    }
+                };
+                let icon_button = IconButton::new("codeium-icon", icon)
+                    .when(disabled_for_file, |this| this.icon_color(Color::Muted))
+                    .when(hidden_for_file, |this| {
+                        this.indicator(Indicator::dot().color(Color::Muted))
+                            .indicator_border_color(Some(status_bar_background))
+                    });
+
+                let popover_menu = if is_starting {
+                    popover_menu.trigger(StatusTrigger::busy(
+                        icon_button.when(!self.popover_menu_handle.is_deployed(), |button| {
+                            button.tooltip(tooltip)
+                        }),
+                        hidden_for_file,
+                        cx,
+                    ))
+                } else {
+                    popover_menu.trigger_with_tooltip(icon_button, tooltip)
+                };
+
+                div().child(popover_menu)
+            }
+
+            EditPredictionProvider::Ollama => {
+                let Some(local_model) = LocalModel::global(cx) else {
+                    return div();
+                };
+                let status = local_model.read(cx).status().clone();
+                let enabled = self.editor_enabled().unwrap_or(false);
+                let unreachable = matches!(status, LocalModelStatus::Error(_));
+                let show_predictions = self.editor_show_predictions;
+                let disabled_for_file = !enabled;
+                let hidden_for_file = enabled && !show_predictions;
+                let is_busy = matches!(status, LocalModelStatus::Checking)
+                    || self
+                        .edit_prediction_provider
+                        .as_ref()
+                        .map_or(false, |provider| provider.is_refreshing(cx));
+                let icon = snooze_icon(cx).unwrap_or(IconName::AiOllama);
+                let status_bar_background = cx.theme().colors().status_bar_background;
+
+                let icon_button = IconButton::new("ollama-icon", icon)
+                    .shape(IconButtonShape::Square)
+                    .when(disabled_for_file, |this| this.icon_color(Color::Muted))
+                    .when(unreachable || hidden_for_file, |this| {
+                        this.indicator(Indicator::dot().color(if unreachable {
+                            Color::Error
+                        } else {
+                            Color::Muted
+                        }))
+                        .indicator_border_color(Some(status_bar_background))
+                    })
+                    .when(!self.popover_menu_handle.is_deployed(), |element| {
+                        element.tooltip(move |window, cx| {
+                            let meta = status_meta(idle_for_vim_mode, cx).unwrap_or_else(|| {
+                                if unreachable {
+                                    "Can't Reach Ollama".to_string()
+                                } else {
+                                    file_state_meta(enabled, show_predictions).to_string()
+                                }
+                            });
+                            Tooltip::with_meta("Local Model", Some(&ToggleMenu), meta, window, cx)
+                        })
+                    });
+
+                let this = cx.entity().clone();
+                let popover_menu = PopoverMenu::new("ollama")
+                    .menu(move |window, cx| {
+                        Some(this.update(cx, |this, cx| this.build_ollama_context_menu(window, cx)))
+                    })
+                    .anchor(Corner::BottomRight)
+                    .with_handle(self.popover_menu_handle.clone());
+
+                let popover_menu = if is_busy {
+                    popover_menu.trigger(StatusTrigger::busy(
+                        icon_button,
+                        unreachable || hidden_for_file,
+                        cx,
+                    ))
+                } else {
+                    popover_menu.trigger(icon_button)
+                };
+
+                div().child(popover_menu.into_any_element())
+            }
+
             EditPredictionProvider::Zed => {
-                let enabled = self.editor_enabled.unwrap_or(true);
+                let enabled = self.editor_enabled().unwrap_or(true);
 
-                let zeta_icon = if enabled {
+                let zeta_icon = if let Some(icon) = snooze_icon(cx) {
+                    icon
+                } else if enabled {
                     IconName::ZedPredict
                 } else {
                     IconName::ZedPredictDisabled
@@ -280,6 +666,10 @@ impl Render for InlineCompletionButton {
                 }
 
                 let show_editor_predictions = self.editor_show_predictions;
+                let has_pending_suggestion = self
+                    .edit_prediction_provider
+                    .as_ref()
+                    .map_or(false, |provider| provider.has_pending_suggestion(cx));
 
                 let icon_button = IconButton::new("zed-predict-pending-button", zeta_icon)
                     .shape(IconButtonShape::Square)
@@ -287,25 +677,33 @@ impl Render for InlineCompletionButton {
                         this.indicator(Indicator::dot().color(Color::Muted))
                             .indicator_border_color(Some(cx.theme().colors().status_bar_background))
                     })
+                    .when(has_pending_suggestion, |this| {
+                        this.indicator(Indicator::dot().color(Color::Accent))
+                            .indicator_border_color(Some(cx.theme().colors().status_bar_background))
+                    })
                     .when(!self.popover_menu_handle.is_deployed(), |element| {
                         element.tooltip(move |window, cx| {
-                            if enabled {
-                                if show_editor_predictions {
-                                    Tooltip::for_action("Edit Prediction", &ToggleMenu, window, cx)
-                                } else {
-                                    Tooltip::with_meta(
-                                        "Edit Prediction",
-                                        Some(&ToggleMenu),
-                                        "Hidden For This File",
-                                        window,
-                                        cx,
-                                    )
-                                }
+                            if has_pending_suggestion {
+                                Tooltip::with_meta(
+                                    "Edit Prediction",
+                                    Some(&ToggleMenu),
+                                    "Prediction Ready, Click to Show",
+                                    window,
+                                    cx,
+                                )
+                            } else if let Some(meta) = status_meta(idle_for_vim_mode, cx) {
+                                Tooltip::with_meta(
+                                    "Edit Prediction",
+                                    Some(&ToggleMenu),
+                                    meta,
+                                    window,
+                                    cx,
+                                )
                             } else {
                                 Tooltip::with_meta(
                                     "Edit Prediction",
                                     Some(&ToggleMenu),
-                                    "Disabled For This File",
+                                    file_state_meta(enabled, show_editor_predictions),
                                     window,
                                     cx,
                                 )
@@ -327,23 +725,38 @@ impl Render for InlineCompletionButton {
                     .as_ref()
                     .map_or(false, |provider| provider.is_refreshing(cx));
 
-                if is_refreshing {
-                    popover_menu = popover_menu.trigger(
-                        icon_button.with_animation(
-                            "pulsating-label",
-                            Animation::new(Duration::from_secs(2))
-                                .repeat()
-                                .with_easing(pulsating_between(0.2, 1.0)),
-                            |icon_button, delta| icon_button.alpha(delta),
-                        ),
-                    );
+                if let Some(editor_focus_handle) = self
+                    .editor_focus_handle
+                    .clone()
+                    .filter(|_| has_pending_suggestion)
+                {
+                    let icon_button = icon_button.on_click(move |_, window, cx| {
+                        window.focus(&editor_focus_handle);
+                        editor_focus_handle.dispatch_action(&ShowEditPrediction, window, cx);
+                    });
+                    popover_menu =
+                        popover_menu.trigger(StatusTrigger::PendingPrediction(icon_button));
+                } else if is_refreshing {
+                    let has_dot = enabled && !show_editor_predictions;
+                    popover_menu =
+                        popover_menu.trigger(StatusTrigger::busy(icon_button, has_dot, cx));
                 } else {
                     popover_menu = popover_menu.trigger(icon_button);
                 }
 
                 div().child(popover_menu.into_any_element())
             }
-        }
+        };
+
+        // Capturing the mouse down keeps the trigger from seeing the click, so the menu stays
+        // closed while predictions are toggled.
+        button.capture_any_mouse_down(cx.listener(|this, event: &MouseDownEvent, window, cx| {
+            if is_secondary_click(event) {
+                window.prevent_default();
+                cx.stop_propagation();
+                this.toggle_predictions_globally(window, cx);
+            }
+        }))
     }
 }
 
@@ -355,21 +768,53 @@ impl InlineCompletionButton {
         cx: &mut Context<Self>,
     ) -> Self {
         if let Some(copilot) = Copilot::global(cx) {
-            cx.observe(&copilot, |_, _, cx| cx.notify()).detach()
+            cx.observe(&copilot, |this, _, cx| this.refresh_editor_status(cx))
+                .detach()
+        }
+        if let Some(supermaven) = Supermaven::global(cx) {
+            cx.observe(&supermaven, |this, _, cx| {
+                this.confirm_supermaven_activation(cx);
+                this.refresh_editor_status(cx)
+            })
+            .detach()
+        }
+        if let Some(codeium) = Codeium::global(cx) {
+            cx.observe(&codeium, |this, _, cx| this.refresh_editor_status(cx))
+                .detach()
+        }
+        if let Some(local_model) = LocalModel::global(cx) {
+            cx.observe(&local_model, |_, _, cx| cx.notify()).detach()
         }
 
-        cx.observe_global::<SettingsStore>(move |_, cx| cx.notify())
+        cx.observe_global::<SettingsStore>(move |this, cx| {
+            if provider_override(cx).is_some() {
+                cx.set_global(ProviderOverride(None));
+            }
+            if this.provider(cx) != EditPredictionProvider::Supermaven {
+                this.supermaven_activation = None;
+            }
+            this.refresh_editor_status(cx)
+        })
+        .detach();
+        cx.observe_global::<EditPredictionSnooze>(move |_, cx| cx.notify())
+            .detach();
+        cx.observe_global::<ProviderOverride>(move |this, cx| this.refresh_editor_status(cx))
             .detach();
 
         Self {
             editor_subscription: None,
-            editor_enabled: None,
+            _selections_subscription: None,
+            update_language: Task::ready(()),
+            editor_status: None,
             editor_show_predictions: true,
+            editor_idle_for_vim_mode: false,
             editor_focus_handle: None,
             language: None,
+            file_language: None,
             file: None,
             edit_prediction_provider: None,
             popover_menu_handle,
+            supermaven_activation: None,
             fs,
             user_store,
         }
@@ -381,18 +826,40 @@ impl InlineCompletionButton {
         cx: &mut Context<Self>,
     ) -> Entity<ContextMenu> {
         let fs = self.fs.clone();
-        ContextMenu::build(window, cx, |menu, _, _| {
-            menu.entry("Sign In", None, copilot::initiate_sign_in)
+        let user_store = self.user_store.clone();
+        let copilot_settings = &all_language_settings(None, cx).edit_predictions.copilot;
+        let endpoint = copilot::endpoint_host(copilot_settings);
+        ContextMenu::build(window, cx, |menu, _, cx| {
+            let menu = menu
+                .label(format!("Signs in to {endpoint}"))
+                .entry("Sign In", None, copilot::initiate_sign_in)
+                .entry("Configure Enterprise URL…", None, configure_copilot_enterprise_uri)
+                .separator()
                 .entry("Disable Copilot", None, {
                     let fs = fs.clone();
                     move |_window, cx| hide_copilot(fs.clone(), cx)
-                })
-                .entry("Use Supermaven", None, {
+                });
+            add_use_provider_entries(menu, EditPredictionProvider::Copilot, fs, user_store, cx)
+        })
+    }
+
+    fn build_codeium_start_menu(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        let fs = self.fs.clone();
+        let user_store = self.user_store.clone();
+        ContextMenu::build(window, cx, |menu, _, cx| {
+            let menu = menu
+                .entry("Sign In", None, codeium::initiate_sign_in)
+                .entry("Disable Codeium", None, {
                     let fs = fs.clone();
                     move |_window, cx| {
-                        set_completion_provider(fs.clone(), cx, EditPredictionProvider::Supermaven)
+                        set_completion_provider(fs.clone(), cx, EditPredictionProvider::None)
                     }
-                })
+                });
+            add_use_provider_entries(menu, EditPredictionProvider::Codeium, fs, user_store, cx)
         })
     }
 
@@ -427,6 +894,27 @@ impl InlineCompletionButton {
                 })
             });
 
+            if let Some(error) = provider.last_error(cx) {
+                menu = menu.header("Status").label(format!(
+                    "Last request failed: {} ({} ago)",
+                    error.message,
+                    format_elapsed(error.occurred_at.elapsed())
+                ));
+                if let Some(editor_focus_handle) = self.editor_focus_handle.clone() {
+                    menu = menu.entry(
+                        "Retry",
+                        Some(Box::new(ShowEditPrediction)),
+                        move |window, cx| {
+                            editor_focus_handle.dispatch_action(&ShowEditPrediction, window, cx);
+                        },
+                    );
+                }
+            } else if let Some(latency) = provider.last_request_latency(cx) {
+                menu = menu
+                    .header("Status")
+                    .label(format!("Last suggestion: {}ms", latency.as_millis()));
+            }
+
             if let Some(usage) = usage {
                 menu = menu.header("Usage");
                 menu = menu.custom_entry(
@@ -462,6 +950,10 @@ impl InlineCompletionButton {
             }
         }
 
+        if let Some(status) = self.editor_status.clone() {
+            menu = self.add_file_status_entries(menu.header("Status for This File"), status);
+        }
+
         menu = menu.header("Show Edit Predictions For");
 
         let language_state = self.language.as_ref().map(|language| {
@@ -502,7 +994,7 @@ impl InlineCompletionButton {
                 language.name(),
                 language_enabled,
                 IconPosition::Start,
-                None,
+                Some(Box::new(ToggleForLanguage)),
                 move |_, cx| {
                     toggle_show_inline_completions_for_language(language.clone(), fs.clone(), cx)
                 },
@@ -512,19 +1004,96 @@ impl InlineCompletionButton {
         let settings = AllLanguageSettings::get_global(cx);
 
         let globally_enabled = settings.show_edit_predictions(None, cx);
-        menu = menu.toggleable_entry("All Files", globally_enabled, IconPosition::Start, None, {
+        let project_settings_path = self
+            .file
+            .as_ref()
+            .and_then(|file| project_settings_path(file, cx));
+        let project_override = self
+            .file
+            .as_ref()
+            .filter(|_| project_settings_path.is_some())
+            .and_then(|file| project_show_edit_predictions(file, cx));
+
+        if let (Some(settings_path), Some(enabled)) =
+            (project_settings_path.clone(), project_override)
+        {
             let fs = fs.clone();
-            move |_, cx| toggle_inline_completions_globally(fs.clone(), cx)
-        });
-
-        let provider = settings.edit_predictions.provider;
-        let current_mode = settings.edit_predictions_mode();
-        let subtle_mode = matches!(current_mode, EditPredictionsMode::Subtle);
-        let eager_mode = matches!(current_mode, EditPredictionsMode::Eager);
+            menu = menu.toggleable_entry(
+                "All Files (project)",
+                enabled,
+                IconPosition::Start,
+                None,
+                move |_, cx| {
+                    set_project_show_edit_predictions(
+                        Some(!enabled),
+                        settings_path.clone(),
+                        fs.clone(),
+                        cx,
+                    )
+                },
+            );
+        } else {
+            menu = menu.toggleable_entry(
+                "All Files",
+                globally_enabled,
+                IconPosition::Start,
+                Some(Box::new(ToggleForAllFiles)),
+                {
+                    let fs = fs.clone();
+                    move |_, cx| toggle_inline_completions_globally(fs.clone(), cx)
+                },
+            );
+        }
 
-        if matches!(provider, EditPredictionProvider::Zed) {
-            menu = menu
-                .separator()
+        if let Some(settings_path) = project_settings_path {
+            let enabled = project_override.unwrap_or(globally_enabled);
+            let label = if enabled {
+                "Disable for This Project Only"
+            } else {
+                "Enable for This Project Only"
+            };
+            menu = menu.entry(label, None, {
+                let fs = fs.clone();
+                let settings_path = settings_path.clone();
+                move |_, cx| {
+                    set_project_show_edit_predictions(
+                        Some(!enabled),
+                        settings_path.clone(),
+                        fs.clone(),
+                        cx,
+                    )
+                }
+            });
+            if project_override.is_some() {
+                let fs = fs.clone();
+                menu = menu.entry("Clear Project Override", None, move |_, cx| {
+                    set_project_show_edit_predictions(None, settings_path.clone(), fs.clone(), cx)
+                });
+            }
+        }
+
+        if VimModeSetting::get_global(cx).0 {
+            let insert_mode_only = settings.edit_predictions.vim_insert_mode_only;
+            menu = menu.toggleable_entry(
+                "Vim Normal Mode",
+                !insert_mode_only,
+                IconPosition::Start,
+                None,
+                {
+                    let fs = fs.clone();
+                    move |_, cx| set_vim_insert_mode_only(!insert_mode_only, fs.clone(), cx)
+                },
+            );
+        }
+
+        let provider = self.provider(cx);
+        let current_mode = settings.edit_predictions_mode();
+        let subtle_mode = matches!(current_mode, EditPredictionsMode::Subtle);
+        let eager_mode = matches!(current_mode, EditPredictionsMode::Eager);
+
+        if matches!(provider, EditPredictionProvider::Zed) {
+            menu = menu
+                .separator()
                 .header("Display Modes")
                 .item(
                     ContextMenuEntry::new("Eager")
@@ -554,6 +1123,88 @@ impl InlineCompletionButton {
                 );
         }
 
+        menu = menu.separator().header("Snooze");
+        if EditPredictionSnooze::is_paused(cx) {
+            menu = menu.entry("Resume", None, |_, cx| EditPredictionSnooze::resume(cx));
+        } else if EditPredictionSnooze::is_snoozed(cx) {
+            menu = menu.entry("Resume Now", None, |_, cx| EditPredictionSnooze::resume(cx));
+        } else {
+            for (label, duration) in SNOOZE_DURATIONS {
+                menu = menu.entry(label, None, move |_, cx| {
+                    EditPredictionSnooze::snooze(duration, cx)
+                });
+            }
+            menu = menu.entry("Until Restart", None, |_, cx| EditPredictionSnooze::pause(cx));
+        }
+
+        menu = menu.separator().header("Switch Provider");
+        let global_provider = self.global_provider(cx);
+        for option in PROVIDERS {
+            menu = menu.toggleable_entry(
+                provider_name(option),
+                option == global_provider,
+                IconPosition::Start,
+                None,
+                {
+                    let fs = fs.clone();
+                    let user_store = self.user_store.clone();
+                    move |window, cx| switch_provider(option, fs.clone(), &user_store, window, cx)
+                },
+            );
+        }
+
+        if let Some(language) = self.file_language.clone() {
+            let language_provider =
+                language_settings::language_settings(Some(language.clone()), None, cx)
+                    .edit_prediction_provider;
+            let header = match language_provider {
+                Some(provider) => format!(
+                    "Provider for {language}: {} (override)",
+                    provider_name(provider)
+                ),
+                None => format!("Provider for {language}"),
+            };
+            menu = menu.separator().header(header).toggleable_entry(
+                "Same as All Languages",
+                language_provider.is_none(),
+                IconPosition::Start,
+                None,
+                {
+                    let fs = fs.clone();
+                    let language = language.clone();
+                    move |_, cx| set_language_provider(language.clone(), None, fs.clone(), cx)
+                },
+            );
+            for option in PROVIDERS {
+                menu = menu.toggleable_entry(
+                    provider_name(option),
+                    language_provider == Some(option),
+                    IconPosition::Start,
+                    None,
+                    {
+                        let fs = fs.clone();
+                        let user_store = self.user_store.clone();
+                        let language = language.clone();
+                        move |window, cx| {
+                            if needs_zed_onboarding(option, &user_store, cx) {
+                                window.dispatch_action(
+                                    zed_actions::OpenZedPredictOnboarding.boxed_clone(),
+                                    cx,
+                                );
+                            } else {
+                                set_language_provider(
+                                    language.clone(),
+                                    Some(option),
+                                    fs.clone(),
+                                    cx,
+                                )
+                            }
+                        }
+                    },
+                );
+            }
+        }
+
         menu = menu.separator().header("Privacy Settings");
         if let Some(provider) = &self.edit_prediction_provider {
             let data_collection = provider.data_collection_state(cx);
@@ -562,7 +1213,13 @@ impl InlineCompletionButton {
                 let enabled = data_collection.is_enabled();
                 let is_open_source = data_collection.is_project_open_source();
                 let is_collecting = data_collection.is_enabled();
-                let (icon_name, icon_color) = if is_open_source && is_collecting {
+                let is_worktree_override = data_collection.is_worktree_override();
+                let is_sharing = is_collecting && (is_open_source || is_worktree_override);
+                let worktree_settings_path = self
+                    .file
+                    .as_ref()
+                    .and_then(|file| project_settings_path(file, cx));
+                let (icon_name, icon_color) = if is_sharing {
                     (IconName::Check, Color::Success)
                 } else {
                     (IconName::Check, Color::Accent)
@@ -574,26 +1231,38 @@ impl InlineCompletionButton {
                         .icon(icon_name)
                         .icon_color(icon_color)
                         .documentation_aside(DocumentationSide::Left, move |cx| {
-                            let (msg, label_color, icon_name, icon_color) = match (is_open_source, is_collecting) {
-                                (true, true) => (
+                            let (msg, label_color, icon_name, icon_color) = match (is_worktree_override, is_open_source, is_collecting) {
+                                (true, _, true) => (
+                                    "This worktree's project settings share its data.",
+                                    Color::Default,
+                                    IconName::Check,
+                                    Color::Success,
+                                ),
+                                (true, _, false) => (
+                                    "This worktree's project settings turn data sharing off.",
+                                    Color::Muted,
+                                    IconName::Close,
+                                    Color::Muted,
+                                ),
+                                (false, true, true) => (
                                     "Project identified as open source, and you're sharing data.",
                                     Color::Default,
                                     IconName::Check,
                                     Color::Success,
                                 ),
-                                (true, false) => (
+                                (false, true, false) => (
                                     "Project identified as open source, but you're not sharing data.",
                                     Color::Muted,
                                     IconName::Close,
                                     Color::Muted,
                                 ),
-                                (false, true) => (
+                                (false, false, true) => (
                                     "Project not identified as open source. No data captured.",
                                     Color::Muted,
                                     IconName::Close,
                                     Color::Muted,
                                 ),
-                                (false, false) => (
+                                (false, false, false) => (
                                     "Project not identified as open source, and setting turned off.",
                                     Color::Muted,
                                     IconName::Close,
@@ -605,7 +1274,9 @@ impl InlineCompletionButton {
                                 .child(
                                     Label::new(indoc!{
                                         "Help us improve our open dataset model by sharing data from open source repositories. \
-                                        Zed must detect a license file in your repo for this setting to take effect."
+                                        Zed must detect a license file in your repo for this setting to take effect. \
+                                        A worktree override, stored in the project settings, takes precedence over both \
+                                        this setting and the license detection."
                                     })
                                 )
                                 .child(
@@ -621,24 +1292,45 @@ impl InlineCompletionButton {
                                 )
                                 .into_any_element()
                         })
-                        .handler(move |_, cx| {
-                            provider.toggle_data_collection(cx);
+                        .handler({
+                            let fs = fs.clone();
+                            let worktree_settings_path = worktree_settings_path.clone();
+                            move |_, cx| {
+                                let settings_path = worktree_settings_path
+                                    .clone()
+                                    .filter(|_| is_worktree_override);
+                                match settings_path {
+                                    Some(settings_path) => set_project_data_collection(
+                                        Some(!enabled),
+                                        settings_path,
+                                        fs.clone(),
+                                        cx,
+                                    ),
+                                    None => provider.toggle_data_collection(cx),
+                                }
 
-                            if !enabled {
-                                telemetry::event!(
-                                    "Data Collection Enabled",
-                                    source = "Edit Prediction Status Menu"
-                                );
-                            } else {
-                                telemetry::event!(
-                                    "Data Collection Disabled",
-                                    source = "Edit Prediction Status Menu"
-                                );
+                                if !enabled {
+                                    telemetry::event!(
+                                        "Data Collection Enabled",
+                                        source = "Edit Prediction Status Menu"
+                                    );
+                                } else {
+                                    telemetry::event!(
+                                        "Data Collection Disabled",
+                                        source = "Edit Prediction Status Menu"
+                                    );
+                                }
                             }
                         })
                 );
 
-                if is_collecting && !is_open_source {
+                if is_worktree_override {
+                    menu = menu.label(if is_collecting {
+                        "Enabled (this worktree)"
+                    } else {
+                        "Disabled (this worktree)"
+                    });
+                } else if is_collecting && !is_open_source {
                     menu = menu.item(
                         ContextMenuEntry::new("No data captured.")
                             .disabled(true)
@@ -647,6 +1339,29 @@ impl InlineCompletionButton {
                             .icon_size(IconSize::Small),
                     );
                 }
+
+                if let Some(settings_path) = worktree_settings_path {
+                    let fs = fs.clone();
+                    menu = if is_worktree_override {
+                        menu.entry("Clear Worktree Override", None, move |_, cx| {
+                            set_project_data_collection(None, settings_path.clone(), fs.clone(), cx)
+                        })
+                    } else {
+                        let label = if is_sharing {
+                            "Disable for This Worktree Only"
+                        } else {
+                            "Enable for This Worktree Only"
+                        };
+                        menu.entry(label, None, move |_, cx| {
+                            set_project_data_collection(
+                                Some(!is_sharing),
+                                settings_path.clone(),
+                                fs.clone(),
+                                cx,
+                            )
+                        })
+                    };
+                }
             }
         }
 
@@ -673,13 +1388,18 @@ impl InlineCompletionButton {
                 }),
         );
 
-        if !self.editor_enabled.unwrap_or(true) {
-            menu = menu.item(
-                ContextMenuEntry::new("This file is excluded.")
-                    .disabled(true)
-                    .icon(IconName::ZedPredictDisabled)
-                    .icon_size(IconSize::Small),
-            );
+        for exclusion in Exclusion::ALL {
+            let glob = self.file.as_ref().and_then(|file| exclusion.glob(file.path()));
+            let fs = fs.clone();
+            let mut entry = ContextMenuEntry::new(exclusion.label()).disabled(glob.is_none());
+            if exclusion == Exclusion::File {
+                entry = entry.action(Box::new(ExcludeFile));
+            }
+            menu = menu.item(entry.handler(move |window, cx| {
+                if let Some(glob) = glob.clone() {
+                    exclude_glob(glob, fs.clone(), window, cx);
+                }
+            }));
         }
 
         if let Some(editor_focus_handle) = self.editor_focus_handle.clone() {
@@ -706,17 +1426,37 @@ impl InlineCompletionButton {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Entity<ContextMenu> {
+        let copilot_settings = &all_language_settings(None, cx).edit_predictions.copilot;
+        let endpoint = copilot::endpoint_host(copilot_settings);
+        let settings_url = copilot_settings_url(cx);
+        let user = Copilot::global(cx)
+            .and_then(|copilot| copilot.read(cx).user().map(ToString::to_string));
+        let signed_in_label = match &user {
+            Some(user) => format!("Signed in to {endpoint} as {user}"),
+            None => format!("Signed in to {endpoint}"),
+        };
+        let status = Copilot::global(cx).map(|copilot| copilot.read(cx).status());
         ContextMenu::build(window, cx, |menu, window, cx| {
-            self.build_language_settings_menu(menu, window, cx)
-                .separator()
-                .link(
-                    "Go to Copilot Settings",
-                    OpenBrowser {
-                        url: COPILOT_SETTINGS_URL.to_string(),
-                    }
-                    .boxed_clone(),
-                )
-                .action("Sign Out", copilot::SignOut.boxed_clone())
+            let mut menu = self.build_language_settings_menu(menu, window, cx).separator();
+            if let Some(status) = status {
+                if let Some(problem) = copilot_status_meta(&status) {
+                    menu = menu.entry(format!("{problem}: Details…"), None, move |window, cx| {
+                        if let Some(workspace) = window.root::<Workspace>().flatten() {
+                            workspace.update(cx, |workspace, cx| {
+                                show_copilot_error(&status, workspace, cx)
+                            });
+                        }
+                    });
+                }
+            }
+            menu.label(signed_in_label)
+                .link("Go to Copilot Settings", OpenBrowser { url: settings_url }.boxed_clone())
+                .entry("Configure Enterprise URL…", None, configure_copilot_enterprise_uri)
+                .entry("Edit Prediction Settings…", None, configure_edit_predictions)
+                .action("View Copilot Log", copilot::OpenLog.boxed_clone())
+                .entry("Sign Out…", None, move |window, cx| {
+                    confirm_copilot_sign_out(user.clone(), window, cx)
+                })
         })
     }
 
@@ -728,46 +1468,388 @@ impl InlineCompletionButton {
         ContextMenu::build(window, cx, |menu, window, cx| {
             self.build_language_settings_menu(menu, window, cx)
                 .separator()
+                .entry("View Diagnostics", None, open_supermaven_diagnostics)
+                .entry("Edit Prediction Settings…", None, configure_edit_predictions)
                 .action("Sign Out", supermaven::SignOut.boxed_clone())
         })
     }
 
+    fn build_codeium_context_menu(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        ContextMenu::build(window, cx, |menu, window, cx| {
+            self.build_language_settings_menu(menu, window, cx)
+                .separator()
+                .entry("Edit Prediction Settings…", None, configure_edit_predictions)
+                .action("Sign Out", codeium::SignOut.boxed_clone())
+        })
+    }
+
+    fn build_ollama_context_menu(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        let settings = all_language_settings(None, cx).edit_predictions.ollama.clone();
+        let connection = ollama_completion::connection_label(&settings);
+        let status = LocalModel::global(cx)
+            .map(|local_model| local_model.read(cx).status().clone());
+        let status_label = match status {
+            Some(LocalModelStatus::Connected) => format!("Connected to {connection}"),
+            Some(LocalModelStatus::Error(error)) => format!("Can't use {connection}: {error}"),
+            _ => format!("Connecting to {connection}…"),
+        };
+
+        ContextMenu::build(window, cx, |menu, window, cx| {
+            let menu = menu
+                .header("Local Model")
+                .label(status_label)
+                .entry("Check Connection", None, |_, cx| {
+                    if let Some(local_model) = LocalModel::global(cx) {
+                        local_model.update(cx, |local_model, cx| local_model.check_connection(cx));
+                    }
+                })
+                .separator();
+            let menu = self.add_ollama_model_entries(menu, cx);
+            self.build_language_settings_menu(menu, window, cx)
+        })
+    }
+
+    fn add_ollama_model_entries(&self, mut menu: ContextMenu, cx: &App) -> ContextMenu {
+        let Some(provider) = self.edit_prediction_provider.as_ref() else {
+            return menu;
+        };
+        let models = provider.models(cx);
+        if models.is_empty() {
+            return menu;
+        }
+        let active_model = provider.active_model(cx);
+
+        menu = menu.header("Model");
+        for model in models {
+            let fs = self.fs.clone();
+            let is_active = active_model.as_ref() == Some(&model);
+            menu = menu.toggleable_entry(
+                model.clone(),
+                is_active,
+                IconPosition::Start,
+                None,
+                move |_, cx| set_ollama_model(model.to_string(), fs.clone(), cx),
+            );
+        }
+        menu.separator()
+    }
+
     fn build_zeta_context_menu(
         &self,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Entity<ContextMenu> {
         ContextMenu::build(window, cx, |menu, window, cx| {
-            self.build_language_settings_menu(menu, window, cx).when(
+            let menu = self.build_language_settings_menu(menu, window, cx);
+            let menu = self.add_acceptance_stats_entries(menu, cx);
+            self.add_quick_rating_entries(menu).when(
                 cx.has_flag::<PredictEditsRateCompletionsFeatureFlag>(),
                 |this| this.action("Rate Completions", RateCompletions.boxed_clone()),
             )
         })
     }
 
+    fn add_acceptance_stats_entries(&self, mut menu: ContextMenu, cx: &App) -> ContextMenu {
+        let Some(provider) = self.edit_prediction_provider.clone() else {
+            return menu;
+        };
+        let Some(stats) = provider.acceptance_stats(cx) else {
+            return menu;
+        };
+
+        menu = menu
+            .separator()
+            .header("This Session")
+            .label(format_acceptance("All languages", stats.total()));
+        for (language, counts) in stats.top_languages(3) {
+            menu = menu.label(format_acceptance(language.as_ref(), counts));
+        }
+        menu.entry("Reset Statistics", None, move |_, cx| {
+            provider.reset_acceptance_stats(cx)
+        })
+    }
+
+    fn add_quick_rating_entries(&self, menu: ContextMenu) -> ContextMenu {
+        let Some(editor_focus_handle) = self.editor_focus_handle.clone() else {
+            return menu;
+        };
+
+        menu.separator()
+            .header("Rate Last Prediction")
+            .item(
+                ContextMenuEntry::new("Good")
+                    .icon(IconName::ThumbsUp)
+                    .icon_size(IconSize::Small)
+                    .action(RateLastCompletionGood.boxed_clone())
+                    .handler({
+                        let editor_focus_handle = editor_focus_handle.clone();
+                        move |window, cx| {
+                            editor_focus_handle.dispatch_action(&RateLastCompletionGood, window, cx)
+                        }
+                    }),
+            )
+            .item(
+                ContextMenuEntry::new("Bad")
+                    .icon(IconName::ThumbsDown)
+                    .icon_size(IconSize::Small)
+                    .action(RateLastCompletionBad.boxed_clone())
+                    .handler(move |window, cx| {
+                        editor_focus_handle.dispatch_action(&RateLastCompletionBad, window, cx)
+                    }),
+            )
+    }
+
+    fn add_file_status_entries(&self, menu: ContextMenu, status: FileStatus) -> ContextMenu {
+        match status {
+            FileStatus::Enabled => menu.item(
+                ContextMenuEntry::new("Enabled")
+                    .disabled(true)
+                    .icon(IconName::Check)
+                    .icon_color(Color::Success)
+                    .icon_size(IconSize::Small),
+            ),
+            FileStatus::ExcludedByGlob { glob, is_default } => {
+                let menu = menu.item(
+                    ContextMenuEntry::new(format!("Disabled — matches disabled_glob '{glob}'"))
+                        .disabled(true)
+                        .icon(IconName::ZedPredictDisabled)
+                        .icon_size(IconSize::Small),
+                );
+                if is_default {
+                    return menu;
+                }
+                let fs = self.fs.clone();
+                menu.entry(format!("Remove '{glob}' from Excluded Files"), None, move |_, cx| {
+                    remove_disabled_glob(glob.clone(), fs.clone(), cx)
+                })
+            }
+            FileStatus::DisabledForLanguage(language) => {
+                let fs = self.fs.clone();
+                menu.item(
+                    ContextMenuEntry::new(format!(
+                        "Disabled — language {} is turned off",
+                        language.name()
+                    ))
+                    .disabled(true)
+                    .icon(IconName::ZedPredictDisabled)
+                    .icon_size(IconSize::Small),
+                )
+                .entry(format!("Enable for {}", language.name()), None, move |_, cx| {
+                    toggle_show_inline_completions_for_language(language.clone(), fs.clone(), cx)
+                })
+            }
+            FileStatus::ProviderNotSignedIn(provider) => menu
+                .item(
+                    ContextMenuEntry::new("Disabled — provider not signed in")
+                        .disabled(true)
+                        .icon(IconName::ZedPredictDisabled)
+                        .icon_size(IconSize::Small),
+                )
+                .entry(format!("Sign In to {}", provider_name(provider)), None, {
+                    move |window, cx| sign_in_to_provider(provider, window, cx)
+                }),
+        }
+    }
+
+    /// Whether the active file is excluded from edit predictions by `disabled_globs`.
+    fn editor_enabled(&self) -> Option<bool> {
+        self.editor_status
+            .as_ref()
+            .map(|status| !matches!(status, FileStatus::ExcludedByGlob { .. }))
+    }
+
     pub fn update_enabled(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
         let editor = editor.read(cx);
         let snapshot = editor.buffer().read(cx).snapshot(cx);
         let suggestion_anchor = editor.selections.newest_anchor().start;
         let language = snapshot.language_at(suggestion_anchor);
         let file = snapshot.file_at(suggestion_anchor).cloned();
-        self.editor_enabled = {
-            let file = file.as_ref();
-            Some(
-                file.map(|file| {
-                    all_language_settings(Some(file), cx)
-                        .edit_predictions_enabled_for_file(file, cx)
-                })
-                .unwrap_or(true),
-            )
-        };
         self.editor_show_predictions = editor.edit_predictions_enabled();
+        self.editor_idle_for_vim_mode = editor.edit_predictions_idle_for_vim_mode(cx);
         self.edit_prediction_provider = editor.edit_prediction_provider();
         self.language = language.cloned();
+        self.file_language = editor
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).language().map(|language| language.name()));
         self.file = file;
         self.editor_focus_handle = Some(editor.focus_handle(cx));
 
+        self.refresh_editor_status(cx);
+    }
+
+    fn schedule_update_enabled(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
+        let editor = editor.downgrade();
+        self.update_language = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(LANGUAGE_UPDATE_DEBOUNCE)
+                .await;
+            if let Some(editor) = editor.upgrade() {
+                this.update(cx, |this, cx| this.update_enabled(editor, cx))
+                    .ok();
+            }
+        });
+    }
+
+    /// Uses `provider` in place of the configured one, in the status bar and in the editors,
+    /// without touching the settings file. The override is dropped as soon as the settings
+    /// change.
+    pub fn set_provider_override(
+        &mut self,
+        provider: Option<EditPredictionProvider>,
+        cx: &mut Context<Self>,
+    ) {
+        cx.set_global(ProviderOverride(provider));
+        self.refresh_editor_status(cx);
+    }
+
+    /// The provider for the active buffer, which its language can override.
+    fn provider(&self, cx: &App) -> EditPredictionProvider {
+        provider_override(cx).unwrap_or_else(|| {
+            all_language_settings(None, cx).edit_prediction_provider(self.file_language.as_ref())
+        })
+    }
+
+    fn global_provider(&self, cx: &App) -> EditPredictionProvider {
+        provider_override(cx)
+            .unwrap_or_else(|| all_language_settings(None, cx).edit_predictions.provider)
+    }
+
+    fn refresh_editor_status(&mut self, cx: &mut Context<Self>) {
+        if self.editor_subscription.is_some() {
+            self.editor_status = Some(self.file_status(cx));
+        }
+        cx.notify();
+    }
+
+    fn file_status(&self, cx: &App) -> FileStatus {
+        let settings = all_language_settings(self.file.as_ref(), cx);
+        if let Some(glob) = self
+            .file
+            .as_ref()
+            .and_then(|file| settings.edit_predictions.disabled_glob_for_file(file, cx))
+        {
+            return FileStatus::ExcludedByGlob {
+                glob: glob.glob().to_string(),
+                is_default: glob.is_default(),
+            };
+        }
+        if let Some(language) = self.language.as_ref() {
+            if !settings.show_edit_predictions(Some(language), cx) {
+                return FileStatus::DisabledForLanguage(language.clone());
+            }
+        }
+        let provider = self.provider(cx);
+        if provider_needs_sign_in(provider, &self.user_store, cx) {
+            return FileStatus::ProviderNotSignedIn(provider);
+        }
+        FileStatus::Enabled
+    }
+
+    /// The menu `ToggleMenu` opens while `show_status_button` hides the button, matching
+    /// the one the button would open in the provider's current state.
+    fn build_menu_without_button(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<ContextMenu>> {
+        match self.provider(cx) {
+            EditPredictionProvider::None => None,
+            EditPredictionProvider::Copilot => {
+                let is_authorized = Copilot::global(cx)?.read(cx).status().is_authorized();
+                Some(if is_authorized {
+                    self.build_copilot_context_menu(window, cx)
+                } else {
+                    self.build_copilot_start_menu(window, cx)
+                })
+            }
+            EditPredictionProvider::Supermaven => {
+                let is_ready = match Supermaven::global(cx)?.read(cx) {
+                    Supermaven::Spawned(agent) => {
+                        matches!(agent.account_status, AccountStatus::Ready)
+                    }
+                    _ => false,
+                };
+                is_ready.then(|| self.build_supermaven_context_menu(window, cx))
+            }
+            EditPredictionProvider::Codeium => {
+                let is_running = matches!(
+                    Codeium::global(cx)?.read(cx).status(),
+                    CodeiumStatus::Running
+                );
+                Some(if is_running {
+                    self.build_codeium_context_menu(window, cx)
+                } else {
+                    self.build_codeium_start_menu(window, cx)
+                })
+            }
+            EditPredictionProvider::Ollama => Some(self.build_ollama_context_menu(window, cx)),
+            EditPredictionProvider::Zed => Some(self.build_zeta_context_menu(window, cx)),
+        }
+    }
+
+    fn toggle_predictions_globally(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let enabled = !all_language_settings(None, cx).show_edit_predictions(None, cx);
+        toggle_inline_completions_globally(self.fs.clone(), cx);
+        self.editor_show_predictions = enabled;
         cx.notify();
+
+        if let Some(workspace) = window.root::<Workspace>().flatten() {
+            workspace.update(cx, |workspace, cx| show_global_toggle_toast(enabled, workspace, cx));
+        }
+    }
+
+    fn await_supermaven_activation(
+        &mut self,
+        workspace: Option<Entity<Workspace>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = workspace else {
+            return;
+        };
+        let timeout = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(SUPERMAVEN_ACTIVATION_TIMEOUT)
+                .await;
+            this.update(cx, |this, _| this.supermaven_activation = None)
+                .ok();
+        });
+        self.supermaven_activation = Some(PendingSupermavenActivation {
+            workspace: workspace.downgrade(),
+            _timeout: timeout,
+        });
+    }
+
+    fn confirm_supermaven_activation(&mut self, cx: &mut Context<Self>) {
+        if self.supermaven_activation.is_none()
+            || !provider_is_ready(EditPredictionProvider::Supermaven, &self.user_store, cx)
+        {
+            return;
+        }
+        if let Some(activation) = self.supermaven_activation.take() {
+            activation
+                .workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<SupermavenActivatedToast>(),
+                            "Supermaven is activated",
+                        )
+                        .autohide(),
+                        cx,
+                    )
+                })
+                .ok();
+        }
     }
 
     pub fn toggle_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -784,30 +1866,200 @@ impl StatusItemView for InlineCompletionButton {
     ) {
         if let Some(editor) = item.and_then(|item| item.act_as::<Editor>(cx)) {
             self.editor_subscription = Some((
-                cx.observe(&editor, Self::update_enabled),
+                cx.observe(&editor, Self::schedule_update_enabled),
                 editor.entity_id().as_u64() as usize,
             ));
+            self._selections_subscription = Some(cx.subscribe(
+                &editor,
+                |this, editor, event: &EditorEvent, cx| {
+                    if let EditorEvent::SelectionsChanged { .. } = event {
+                        this.schedule_update_enabled(editor, cx);
+                    }
+                },
+            ));
+            self.update_language = Task::ready(());
             self.update_enabled(editor, cx);
         } else {
             self.language = None;
+            self.file_language = None;
             self.editor_subscription = None;
-            self.editor_enabled = None;
+            self._selections_subscription = None;
+            self.update_language = Task::ready(());
+            self.editor_status = None;
         }
         cx.notify();
     }
 }
 
-impl SupermavenButtonStatus {
-    fn to_icon(&self) -> IconName {
-        match self {
-            SupermavenButtonStatus::Ready => IconName::Supermaven,
-            SupermavenButtonStatus::Errored(_) => IconName::SupermavenError,
-            SupermavenButtonStatus::NeedsActivation(_) => IconName::SupermavenInit,
-            SupermavenButtonStatus::Initializing => IconName::SupermavenInit,
-        }
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else if seconds < 60 * 60 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}h", seconds / (60 * 60))
     }
+}
 
-    fn to_tooltip(&self) -> String {
+/// The icon the status bar shows in place of the provider's while predictions are snoozed.
+fn snooze_icon(cx: &App) -> Option<IconName> {
+    if EditPredictionSnooze::is_paused(cx) {
+        Some(IconName::CircleOff)
+    } else if EditPredictionSnooze::is_snoozed(cx) {
+        Some(IconName::CountdownTimer)
+    } else {
+        None
+    }
+}
+
+/// Tooltip text saying why edit predictions aren't being requested: they are snoozed, or
+/// vim is in normal mode while `vim_insert_mode_only` is on.
+fn status_meta(idle_for_vim_mode: bool, cx: &App) -> Option<String> {
+    if EditPredictionSnooze::is_paused(cx) {
+        return Some("Paused Until Restart".to_string());
+    }
+    if let Some(remaining) = EditPredictionSnooze::remaining(cx) {
+        let minutes = remaining.as_secs().div_ceil(60);
+        return Some(if minutes == 1 {
+            "Snoozed, 1 Minute Left".to_string()
+        } else {
+            format!("Snoozed, {minutes} Minutes Left")
+        });
+    }
+    idle_for_vim_mode.then(|| "Idle (normal mode)".to_string())
+}
+
+/// Tooltip text saying whether predictions are on for the active file. GPUI has no
+/// accessibility tree yet, so the tooltip is what conveys this to the user.
+fn file_state_meta(enabled: bool, show_predictions: bool) -> &'static str {
+    if !enabled {
+        "Disabled For This File"
+    } else if !show_predictions {
+        "Hidden For This File"
+    } else {
+        "Enabled For This File"
+    }
+}
+
+/// Takes the button's place when `show_status_button` is off, so that `ToggleMenu` still
+/// opens the menu from the corner of the status bar.
+#[derive(IntoElement)]
+struct HiddenButtonAnchor;
+
+impl RenderOnce for HiddenButtonAnchor {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        div()
+    }
+}
+
+impl Clickable for HiddenButtonAnchor {
+    fn on_click(self, _: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static) -> Self {
+        self
+    }
+
+    fn cursor_style(self, _: CursorStyle) -> Self {
+        self
+    }
+}
+
+impl Toggleable for HiddenButtonAnchor {
+    fn toggle_state(self, _: bool) -> Self {
+        self
+    }
+}
+
+/// The button of a busy provider or of a prediction that's ready but not shown yet.
+#[derive(IntoElement)]
+enum StatusTrigger {
+    Pulsating(AnimationElement<IconButton>),
+    /// Marks the button as busy with a badge, or a ring around the dot it already has, when
+    /// `animate_status_icon` is off.
+    Static(IconButton),
+    /// Keeps the button's own click handler instead of the popover's, so that clicking shows
+    /// the pending prediction while `ToggleMenu` still opens the menu.
+    PendingPrediction(IconButton),
+}
+
+impl StatusTrigger {
+    fn busy(icon_button: IconButton, has_dot: bool, cx: &App) -> Self {
+        if all_language_settings(None, cx).edit_predictions.animate_status_icon {
+            Self::Pulsating(pulsating(icon_button))
+        } else if has_dot {
+            Self::Static(icon_button.indicator_border_color(Some(Color::Info.color(cx))))
+        } else {
+            Self::Static(
+                icon_button
+                    .indicator(Indicator::dot().color(Color::Info))
+                    .indicator_border_color(Some(cx.theme().colors().status_bar_background)),
+            )
+        }
+    }
+}
+
+impl RenderOnce for StatusTrigger {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        match self {
+            Self::Pulsating(button) => button.into_any_element(),
+            Self::Static(button) | Self::PendingPrediction(button) => button.into_any_element(),
+        }
+    }
+}
+
+impl Clickable for StatusTrigger {
+    fn on_click(self, handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static) -> Self {
+        match self {
+            Self::Pulsating(button) => Self::Pulsating(button.on_click(handler)),
+            Self::Static(button) => Self::Static(button.on_click(handler)),
+            Self::PendingPrediction(button) => Self::PendingPrediction(button),
+        }
+    }
+
+    fn cursor_style(self, cursor_style: CursorStyle) -> Self {
+        match self {
+            Self::Pulsating(button) => Self::Pulsating(button.cursor_style(cursor_style)),
+            Self::Static(button) => Self::Static(button.cursor_style(cursor_style)),
+            Self::PendingPrediction(button) => {
+                Self::PendingPrediction(button.cursor_style(cursor_style))
+            }
+        }
+    }
+}
+
+impl Toggleable for StatusTrigger {
+    fn toggle_state(self, selected: bool) -> Self {
+        match self {
+            Self::Pulsating(button) => Self::Pulsating(button.toggle_state(selected)),
+            Self::Static(button) => Self::Static(button.toggle_state(selected)),
+            Self::PendingPrediction(button) => {
+                Self::PendingPrediction(button.toggle_state(selected))
+            }
+        }
+    }
+}
+
+/// Pulses the button while its provider is busy, e.g. starting up or fetching a prediction.
+fn pulsating(icon_button: IconButton) -> AnimationElement<IconButton> {
+    icon_button.with_animation(
+        "pulsating-label",
+        Animation::new(Duration::from_secs(2))
+            .repeat()
+            .with_easing(pulsating_between(0.2, 1.0)),
+        |icon_button, delta| icon_button.alpha(delta),
+    )
+}
+
+impl SupermavenButtonStatus {
+    fn to_icon(&self) -> IconName {
+        match self {
+            SupermavenButtonStatus::Ready => IconName::Supermaven,
+            SupermavenButtonStatus::Errored(_) => IconName::SupermavenError,
+            SupermavenButtonStatus::NeedsActivation(_) => IconName::SupermavenInit,
+            SupermavenButtonStatus::Initializing => IconName::SupermavenInit,
+        }
+    }
+
+    fn to_tooltip(&self) -> String {
         match self {
             SupermavenButtonStatus::Ready => "Supermaven is ready".to_string(),
             SupermavenButtonStatus::Errored(error) => format!("Supermaven error: {}", error),
@@ -818,8 +2070,10 @@ impl SupermavenButtonStatus {
 
     fn has_menu(&self) -> bool {
         match self {
-            SupermavenButtonStatus::Ready | SupermavenButtonStatus::NeedsActivation(_) => true,
-            SupermavenButtonStatus::Errored(_) | SupermavenButtonStatus::Initializing => false,
+            SupermavenButtonStatus::Ready
+            | SupermavenButtonStatus::NeedsActivation(_)
+            | SupermavenButtonStatus::Errored(_) => true,
+            SupermavenButtonStatus::Initializing => false,
         }
     }
 }
@@ -827,6 +2081,82 @@ impl SupermavenButtonStatus {
 async fn open_disabled_globs_setting_in_editor(
     workspace: WeakEntity<Workspace>,
     cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    static DISABLED_GLOBS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#""disabled_globs":\s*\[\s*(?P<content>(?:.|\n)*?)\s*\]"#).unwrap()
+    });
+
+    // Ensure that we always have "inline_completions { "disabled_globs": [] }"
+    open_setting_in_editor(
+        workspace,
+        |file| {
+            file.edit_predictions
+                .get_or_insert_with(Default::default)
+                .disabled_globs
+                .get_or_insert_with(Vec::new);
+        },
+        &DISABLED_GLOBS_REGEX,
+        cx,
+    )
+    .await
+}
+
+async fn open_copilot_enterprise_uri_setting_in_editor(
+    workspace: WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    static ENTERPRISE_URI_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#""enterprise_uri":\s*"(?P<content>[^"]*)""#).unwrap()
+    });
+
+    // An empty URL is ignored, so it's safe to insert one for the user to fill in.
+    open_setting_in_editor(
+        workspace,
+        |file| {
+            file.edit_predictions
+                .get_or_insert_with(Default::default)
+                .copilot
+                .enterprise_uri
+                .get_or_insert_with(String::new);
+        },
+        &ENTERPRISE_URI_REGEX,
+        cx,
+    )
+    .await
+}
+
+async fn open_edit_prediction_provider_setting_in_editor(
+    workspace: WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    static PROVIDER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#""edit_prediction_provider":\s*"(?P<content>[^"]*)""#).unwrap()
+    });
+
+    // Writing out the provider that's already in effect doesn't change anything, and gives
+    // the cursor a key to land on next to the other edit prediction settings.
+    let provider = cx.update(|_, cx| all_language_settings(None, cx).edit_predictions.provider)?;
+    open_setting_in_editor(
+        workspace,
+        move |file| {
+            file.features
+                .get_or_insert_with(Default::default)
+                .edit_prediction_provider
+                .get_or_insert(provider);
+        },
+        &PROVIDER_REGEX,
+        cx,
+    )
+    .await
+}
+
+/// Opens the user's settings file, adding the setting if it's missing, and selects the
+/// `content` group of `regex` so that the user can start typing the value.
+async fn open_setting_in_editor(
+    workspace: WeakEntity<Workspace>,
+    update: impl FnOnce(&mut AllLanguageSettingsContent),
+    regex: &Regex,
+    cx: &mut AsyncWindowContext,
 ) -> Result<()> {
     let settings_editor = workspace
         .update_in(cx, |_, window, cx| {
@@ -844,14 +2174,7 @@ async fn open_disabled_globs_setting_in_editor(
             let text = item.buffer().read(cx).snapshot(cx).text();
 
             let settings = cx.global::<SettingsStore>();
-
-            // Ensure that we always have "inline_completions { "disabled_globs": [] }"
-            let edits = settings.edits_for_update::<AllLanguageSettings>(&text, |file| {
-                file.edit_predictions
-                    .get_or_insert_with(Default::default)
-                    .disabled_globs
-                    .get_or_insert_with(Vec::new);
-            });
+            let edits = settings.edits_for_update::<AllLanguageSettings>(&text, update);
 
             if !edits.is_empty() {
                 item.edit(edits.iter().cloned(), cx);
@@ -859,11 +2182,7 @@ async fn open_disabled_globs_setting_in_editor(
 
             let text = item.buffer().read(cx).snapshot(cx).text();
 
-            static DISABLED_GLOBS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-                Regex::new(r#""disabled_globs":\s*\[\s*(?P<content>(?:.|\n)*?)\s*\]"#).unwrap()
-            });
-            // Only capture [...]
-            let range = DISABLED_GLOBS_REGEX.captures(&text).and_then(|captures| {
+            let range = regex.captures(&text).and_then(|captures| {
                 captures
                     .name("content")
                     .map(|inner_match| inner_match.start()..inner_match.end())
@@ -878,6 +2197,64 @@ async fn open_disabled_globs_setting_in_editor(
     anyhow::Ok(())
 }
 
+fn configure_copilot_enterprise_uri(window: &mut Window, cx: &mut App) {
+    if let Some(workspace) = window.root().flatten() {
+        let workspace = workspace.downgrade();
+        window
+            .spawn(cx, async |cx| {
+                open_copilot_enterprise_uri_setting_in_editor(workspace, cx).await
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+fn configure_edit_predictions(window: &mut Window, cx: &mut App) {
+    if let Some(workspace) = window.root().flatten() {
+        let workspace = workspace.downgrade();
+        window
+            .spawn(cx, async |cx| {
+                open_edit_prediction_provider_setting_in_editor(workspace, cx).await
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+fn open_supermaven_diagnostics(window: &mut Window, cx: &mut App) {
+    let Some(workspace) = window.root::<Workspace>().flatten() else {
+        return;
+    };
+    let Some(supermaven) = Supermaven::global(cx) else {
+        return;
+    };
+    let diagnostics = supermaven.read(cx).diagnostics();
+    workspace.update(cx, |workspace, cx| {
+        workspace
+            .with_local_workspace(window, cx, move |workspace, window, cx| {
+                let project = workspace.project().clone();
+                let buffer = project.update(cx, |project, cx| {
+                    project.create_local_buffer(&diagnostics, None, cx)
+                });
+                let buffer = cx.new(|cx| {
+                    MultiBuffer::singleton(buffer, cx).with_title("Supermaven Diagnostics".into())
+                });
+                let editor = cx.new(|cx| {
+                    let mut editor = Editor::for_multibuffer(buffer, Some(project), window, cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+            .detach_and_log_err(cx);
+    });
+}
+
+fn copilot_settings_url(cx: &App) -> String {
+    match &all_language_settings(None, cx).edit_predictions.copilot.enterprise_uri {
+        Some(uri) => format!("{}/settings/copilot", uri.trim_end_matches('/')),
+        None => COPILOT_SETTINGS_URL.to_string(),
+    }
+}
+
 fn toggle_inline_completions_globally(fs: Arc<dyn Fs>, cx: &mut App) {
     let show_edit_predictions = all_language_settings(None, cx).show_edit_predictions(None, cx);
     update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
@@ -885,6 +2262,75 @@ fn toggle_inline_completions_globally(fs: Arc<dyn Fs>, cx: &mut App) {
     });
 }
 
+fn show_global_toggle_toast(enabled: bool, workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let message = if enabled {
+        "Edit predictions enabled for all files"
+    } else {
+        "Edit predictions disabled for all files"
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<GlobalToggleToast>(), message).autohide(),
+        cx,
+    );
+}
+
+/// Toggles edit predictions for all files, like the menu's "All Files" entry.
+pub fn toggle_for_all_files(
+    workspace: &mut Workspace,
+    fs: Arc<dyn Fs>,
+    cx: &mut Context<Workspace>,
+) {
+    let enabled = !all_language_settings(None, cx).show_edit_predictions(None, cx);
+    toggle_inline_completions_globally(fs, cx);
+    show_global_toggle_toast(enabled, workspace, cx);
+}
+
+/// Toggles edit predictions for the language at the cursor in the active editor.
+pub fn toggle_for_language(
+    workspace: &mut Workspace,
+    fs: Arc<dyn Fs>,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let editor = editor.read(cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let cursor = editor.selections.newest_anchor().start;
+    if let Some(language) = snapshot.language_at(cursor).cloned() {
+        toggle_show_inline_completions_for_language(language, fs, cx);
+    }
+}
+
+/// Excludes the active file from edit predictions, like the menu's "Exclude This File" entry.
+pub fn exclude_active_file(
+    workspace: &mut Workspace,
+    fs: Arc<dyn Fs>,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(glob) = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .and_then(|project_path| Exclusion::File.glob(&project_path.path))
+    else {
+        return;
+    };
+    update_settings_file::<AllLanguageSettings>(fs.clone(), cx, {
+        let glob = glob.clone();
+        move |file, _| add_disabled_glob(file, glob)
+    });
+    show_excluded_glob_toast(glob, fs, workspace, cx);
+}
+
+/// Right-click, or cmd-click on macOS, toggles predictions instead of opening the menu.
+fn is_secondary_click(event: &MouseDownEvent) -> bool {
+    match event.button {
+        MouseButton::Right => true,
+        MouseButton::Left => cfg!(target_os = "macos") && event.modifiers.platform,
+        _ => false,
+    }
+}
+
 fn set_completion_provider(fs: Arc<dyn Fs>, cx: &mut App, provider: EditPredictionProvider) {
     update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
         file.features
@@ -893,6 +2339,311 @@ fn set_completion_provider(fs: Arc<dyn Fs>, cx: &mut App, provider: EditPredicti
     });
 }
 
+/// Switches to `provider`, going through Zed's onboarding first when it's Zed and the user
+/// hasn't accepted its terms of service yet.
+fn switch_provider(
+    provider: EditPredictionProvider,
+    fs: Arc<dyn Fs>,
+    user_store: &Entity<UserStore>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    if needs_zed_onboarding(provider, user_store, cx) {
+        window.dispatch_action(zed_actions::OpenZedPredictOnboarding.boxed_clone(), cx);
+    } else {
+        set_completion_provider(fs, cx, provider);
+    }
+}
+
+fn needs_zed_onboarding(
+    provider: EditPredictionProvider,
+    user_store: &Entity<UserStore>,
+    cx: &App,
+) -> bool {
+    provider == EditPredictionProvider::Zed
+        && !user_store
+            .read(cx)
+            .current_user_has_accepted_terms()
+            .unwrap_or(false)
+}
+
+/// Sets the provider for `language`, or makes it follow the global provider again when
+/// `provider` is `None`.
+fn set_language_provider(
+    language: LanguageName,
+    provider: Option<EditPredictionProvider>,
+    fs: Arc<dyn Fs>,
+    cx: &mut App,
+) {
+    update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
+        file.languages
+            .entry(language)
+            .or_default()
+            .edit_prediction_provider = provider;
+    });
+}
+
+/// Adds a "Use …" entry to a provider's sign-in menu for every other provider, marking the
+/// one the settings select.
+fn add_use_provider_entries(
+    mut menu: ContextMenu,
+    current: EditPredictionProvider,
+    fs: Arc<dyn Fs>,
+    user_store: Entity<UserStore>,
+    cx: &App,
+) -> ContextMenu {
+    let selected = all_language_settings(None, cx).edit_predictions.provider;
+    for provider in PROVIDERS {
+        let label = match provider {
+            EditPredictionProvider::Copilot => "Use Copilot",
+            EditPredictionProvider::Supermaven => "Use Supermaven",
+            EditPredictionProvider::Zed => "Use Zed AI Predictions",
+            EditPredictionProvider::Codeium => "Use Codeium",
+            EditPredictionProvider::Ollama => "Use Local Model",
+            EditPredictionProvider::None => continue,
+        };
+        if provider == current {
+            continue;
+        }
+        let fs = fs.clone();
+        let user_store = user_store.clone();
+        menu = menu.toggleable_entry(
+            label,
+            provider == selected,
+            IconPosition::Start,
+            None,
+            move |window, cx| switch_provider(provider, fs.clone(), &user_store, window, cx),
+        );
+    }
+    menu
+}
+
+/// Switches edit predictions to the next provider that is ready to use.
+pub fn cycle_provider(workspace: &mut Workspace, fs: Arc<dyn Fs>, cx: &mut Context<Workspace>) {
+    let current = all_language_settings(None, cx).edit_predictions.provider;
+    let start = PROVIDERS
+        .iter()
+        .position(|provider| *provider == current)
+        .unwrap_or(PROVIDERS.len() - 1);
+    let user_store = workspace.user_store().clone();
+    let next = (1..=PROVIDERS.len())
+        .map(|offset| PROVIDERS[(start + offset) % PROVIDERS.len()])
+        .find(|provider| provider_is_ready(*provider, &user_store, cx))
+        .unwrap_or(EditPredictionProvider::None);
+    if next == current {
+        return;
+    }
+
+    set_completion_provider(fs, cx, next);
+
+    let message = match next {
+        EditPredictionProvider::None => "Edit predictions turned off".to_string(),
+        provider => format!("Edit predictions now use {}", provider_name(provider)),
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ProviderSwitchedToast>(), message).autohide(),
+        cx,
+    );
+}
+
+fn provider_is_ready(
+    provider: EditPredictionProvider,
+    user_store: &Entity<UserStore>,
+    cx: &App,
+) -> bool {
+    match provider {
+        EditPredictionProvider::None => true,
+        EditPredictionProvider::Copilot => Copilot::global(cx)
+            .is_some_and(|copilot| copilot.read(cx).status().is_authorized()),
+        EditPredictionProvider::Supermaven => Supermaven::global(cx).is_some_and(|supermaven| {
+            matches!(
+                supermaven.read(cx),
+                Supermaven::Spawned(agent) if matches!(agent.account_status, AccountStatus::Ready)
+            )
+        }),
+        EditPredictionProvider::Zed => user_store
+            .read(cx)
+            .current_user_has_accepted_terms()
+            .unwrap_or(false),
+        EditPredictionProvider::Codeium => {
+            Codeium::global(cx).is_some_and(|codeium| codeium.read(cx).has_api_key())
+        }
+        // The server is only checked once it's in use, so a local model counts as ready
+        // unless it is known to be unreachable.
+        EditPredictionProvider::Ollama => LocalModel::global(cx).is_some_and(|local_model| {
+            !matches!(local_model.read(cx).status(), LocalModelStatus::Error(_))
+        }),
+    }
+}
+
+fn provider_needs_sign_in(
+    provider: EditPredictionProvider,
+    user_store: &Entity<UserStore>,
+    cx: &App,
+) -> bool {
+    match provider {
+        EditPredictionProvider::Copilot => Copilot::global(cx).is_some_and(|copilot| {
+            matches!(
+                copilot.read(cx).status(),
+                Status::SignedOut { .. } | Status::Unauthorized
+            )
+        }),
+        EditPredictionProvider::Supermaven => Supermaven::global(cx).is_some_and(|supermaven| {
+            matches!(
+                supermaven.read(cx),
+                Supermaven::Spawned(agent)
+                    if matches!(agent.account_status, AccountStatus::NeedsActivation { .. })
+            )
+        }),
+        EditPredictionProvider::Zed => !user_store
+            .read(cx)
+            .current_user_has_accepted_terms()
+            .unwrap_or(false),
+        EditPredictionProvider::Codeium => Codeium::global(cx)
+            .is_some_and(|codeium| matches!(codeium.read(cx).status(), CodeiumStatus::SignedOut)),
+        EditPredictionProvider::Ollama | EditPredictionProvider::None => false,
+    }
+}
+
+fn sign_in_to_provider(provider: EditPredictionProvider, window: &mut Window, cx: &mut App) {
+    match provider {
+        EditPredictionProvider::Copilot => copilot::initiate_sign_in(window, cx),
+        EditPredictionProvider::Codeium => codeium::initiate_sign_in(window, cx),
+        EditPredictionProvider::Supermaven => {
+            if let Some(Supermaven::Spawned(agent)) = Supermaven::global(cx).map(|s| s.read(cx)) {
+                if let AccountStatus::NeedsActivation { activate_url } = &agent.account_status {
+                    cx.open_url(activate_url);
+                }
+            }
+        }
+        EditPredictionProvider::Zed => {
+            window.dispatch_action(zed_actions::OpenZedPredictOnboarding.boxed_clone(), cx)
+        }
+        EditPredictionProvider::Ollama | EditPredictionProvider::None => {}
+    }
+}
+
+/// Asks before signing out, as signing back in means going through GitHub's device flow again.
+fn confirm_copilot_sign_out(user: Option<String>, window: &mut Window, cx: &mut App) {
+    let message = match &user {
+        Some(user) => format!("Sign out of GitHub Copilot as {user}?"),
+        None => "Sign out of GitHub Copilot?".to_string(),
+    };
+    let answer = window.prompt(
+        PromptLevel::Warning,
+        &message,
+        Some("Signing back in requires entering a new device code on GitHub."),
+        &["Sign Out", "Cancel"],
+        cx,
+    );
+    cx.spawn(async move |cx| {
+        if answer.await == Ok(0) {
+            cx.update(|cx| cx.dispatch_action(&copilot::SignOut)).ok();
+        }
+    })
+    .detach();
+}
+
+fn reinstall_copilot(cx: &mut App) {
+    if let Some(copilot) = Copilot::global(cx) {
+        copilot
+            .update(cx, |copilot, cx| copilot.reinstall(cx))
+            .detach();
+    }
+}
+
+/// Describes a Copilot status that keeps predictions from showing, for the button's tooltip.
+fn copilot_status_meta(status: &Status) -> Option<&'static str> {
+    match status {
+        Status::Error(_) => Some("Failed to Start"),
+        Status::SignedOut { .. } => Some("Signed Out"),
+        Status::SigningIn { .. } => Some("Signing In…"),
+        Status::Unauthorized => Some("No Active Subscription"),
+        Status::RateLimited => Some("Rate Limited"),
+        Status::Unreachable(_) => Some("Can't Reach GitHub"),
+        Status::Starting { .. } | Status::Disabled | Status::Authorized => None,
+    }
+}
+
+/// Explains why Copilot stopped working, with a next step that fits the failure.
+fn show_copilot_error(status: &Status, workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let notification = match status {
+        Status::Error(error) => {
+            let error = error.lines().next().unwrap_or_default();
+            let message = format!(
+                "Copilot can't be started: {}",
+                truncate_and_trailoff(error, 120)
+            );
+            cx.new(|cx| {
+                MessageNotification::new(message, cx)
+                    .primary_message("Reinstall Copilot")
+                    .primary_on_click(|_, cx| reinstall_copilot(cx))
+                    .secondary_message("View Log")
+                    .secondary_on_click(|window, cx| {
+                        window.dispatch_action(copilot::OpenLog.boxed_clone(), cx)
+                    })
+            })
+        }
+        Status::RateLimited => {
+            let settings_url = copilot_settings_url(cx);
+            cx.new(|cx| {
+                MessageNotification::new(
+                    "GitHub is rate limiting Copilot requests. Wait a few minutes, \
+                    predictions resume on their own once the limit resets.",
+                    cx,
+                )
+                .primary_message("Go to Copilot Settings")
+                .primary_on_click(move |_, cx| cx.open_url(&settings_url))
+                .secondary_message("View Log")
+                .secondary_on_click(|window, cx| {
+                    window.dispatch_action(copilot::OpenLog.boxed_clone(), cx)
+                })
+            })
+        }
+        Status::Unreachable(error) => {
+            let message = error.to_string();
+            cx.new(|cx| {
+                MessageNotification::new(message, cx)
+                    .primary_message("Open Settings")
+                    .primary_on_click(|window, cx| {
+                        window.dispatch_action(zed_actions::OpenSettings.boxed_clone(), cx)
+                    })
+                    .secondary_message("View Log")
+                    .secondary_on_click(|window, cx| {
+                        window.dispatch_action(copilot::OpenLog.boxed_clone(), cx)
+                    })
+            })
+        }
+        _ => return,
+    };
+    workspace.show_notification(NotificationId::unique::<CopilotErrorToast>(), cx, |_| {
+        notification
+    });
+}
+
+fn format_acceptance(label: &str, counts: AcceptanceCounts) -> String {
+    match counts.acceptance_rate() {
+        Some(rate) => format!(
+            "{label}: {} of {} accepted ({:.0}%)",
+            counts.accepted,
+            counts.shown,
+            rate * 100.
+        ),
+        None => format!("{label}: no predictions shown"),
+    }
+}
+
+fn provider_name(provider: EditPredictionProvider) -> &'static str {
+    match provider {
+        EditPredictionProvider::None => "None",
+        EditPredictionProvider::Copilot => "GitHub Copilot",
+        EditPredictionProvider::Supermaven => "Supermaven",
+        EditPredictionProvider::Zed => "Zed",
+        EditPredictionProvider::Codeium => "Codeium",
+        EditPredictionProvider::Ollama => "Local Model",
+    }
+}
+
 fn toggle_show_inline_completions_for_language(
     language: Arc<Language>,
     fs: Arc<dyn Fs>,
@@ -908,6 +2659,138 @@ fn toggle_show_inline_completions_for_language(
     });
 }
 
+/// Adds `glob` to the user's `disabled_globs` without opening the settings file, and offers
+/// to undo it from a toast.
+fn exclude_glob(glob: String, fs: Arc<dyn Fs>, window: &mut Window, cx: &mut App) {
+    update_settings_file::<AllLanguageSettings>(fs.clone(), cx, {
+        let glob = glob.clone();
+        move |file, _| add_disabled_glob(file, glob)
+    });
+
+    if let Some(workspace) = window.root::<Workspace>().flatten() {
+        workspace.update(cx, |workspace, cx| show_excluded_glob_toast(glob, fs, workspace, cx));
+    }
+}
+
+fn show_excluded_glob_toast(
+    glob: String,
+    fs: Arc<dyn Fs>,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<ExcludedGlobToast>(),
+            format!("Edit predictions are disabled for '{glob}'"),
+        )
+        .on_click("Undo", move |_, cx| remove_disabled_glob(glob.clone(), fs.clone(), cx))
+        .autohide(),
+        cx,
+    );
+}
+
+fn add_disabled_glob(file: &mut AllLanguageSettingsContent, glob: String) {
+    let disabled_globs = file
+        .edit_predictions
+        .get_or_insert_with(Default::default)
+        .disabled_globs
+        .get_or_insert_with(Vec::new);
+    if !disabled_globs.contains(&glob) {
+        disabled_globs.push(glob);
+    }
+}
+
+fn remove_disabled_glob(glob: String, fs: Arc<dyn Fs>, cx: &mut App) {
+    update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
+        remove_disabled_glob_from(file, &glob)
+    });
+}
+
+fn remove_disabled_glob_from(file: &mut AllLanguageSettingsContent, glob: &str) {
+    if let Some(disabled_globs) = file
+        .edit_predictions
+        .as_mut()
+        .and_then(|edit_predictions| edit_predictions.disabled_globs.as_mut())
+    {
+        disabled_globs.retain(|disabled_glob| disabled_glob != glob);
+    }
+}
+
+/// Returns the `.zed/settings.json` of the local worktree that contains `file`, if it's a
+/// directory.
+fn project_settings_path(file: &Arc<dyn File>, cx: &App) -> Option<PathBuf> {
+    let local_file = file.as_local()?;
+    // Files opened on their own form a worktree of their own, with an empty path.
+    if file.path().as_os_str().is_empty() {
+        return None;
+    }
+    let abs_path = local_file.abs_path(cx);
+    let worktree_root = abs_path.ancestors().nth(file.path().components().count())?;
+    Some(worktree_root.join(paths::local_settings_file_relative_path()))
+}
+
+/// Returns the `show_edit_predictions` set in the settings file at the root of the worktree
+/// that contains `file`.
+fn project_show_edit_predictions(file: &Arc<dyn File>, cx: &App) -> Option<bool> {
+    let (_, content) = cx
+        .global::<SettingsStore>()
+        .local_settings(file.worktree_id(cx))
+        .find(|(directory, _)| directory.as_os_str().is_empty())?;
+    parse_json_with_comments::<AllLanguageSettingsContent>(&content)
+        .ok()?
+        .defaults
+        .show_edit_predictions
+}
+
+fn set_project_show_edit_predictions(
+    show_edit_predictions: Option<bool>,
+    settings_path: PathBuf,
+    fs: Arc<dyn Fs>,
+    cx: &mut App,
+) {
+    update_local_settings_file::<AllLanguageSettings>(fs, settings_path, cx, move |file, _| {
+        file.defaults.show_edit_predictions = show_edit_predictions;
+    });
+}
+
+fn set_project_data_collection(
+    data_collection: Option<bool>,
+    settings_path: PathBuf,
+    fs: Arc<dyn Fs>,
+    cx: &mut App,
+) {
+    update_local_settings_file::<AllLanguageSettings>(fs, settings_path, cx, move |file, _| {
+        set_data_collection(file, data_collection)
+    });
+}
+
+fn set_data_collection(file: &mut AllLanguageSettingsContent, data_collection: Option<bool>) {
+    file.edit_predictions
+        .get_or_insert_with(Default::default)
+        .data_collection = data_collection;
+}
+
+fn set_vim_insert_mode_only(insert_mode_only: bool, fs: Arc<dyn Fs>, cx: &mut App) {
+    update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
+        file.edit_predictions
+            .get_or_insert_with(Default::default)
+            .vim_insert_mode_only = Some(insert_mode_only);
+    });
+}
+
+fn set_ollama_model(model: String, fs: Arc<dyn Fs>, cx: &mut App) {
+    update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
+        set_ollama_model_in(file, model)
+    });
+}
+
+fn set_ollama_model_in(file: &mut AllLanguageSettingsContent, model: String) {
+    file.edit_predictions
+        .get_or_insert_with(Default::default)
+        .ollama
+        .model = Some(model);
+}
+
 fn hide_copilot(fs: Arc<dyn Fs>, cx: &mut App) {
     update_settings_file::<AllLanguageSettings>(fs, cx, move |file, _| {
         file.features
@@ -922,15 +2805,373 @@ fn toggle_edit_prediction_mode(fs: Arc<dyn Fs>, mode: EditPredictionsMode, cx: &
 
     if current_mode != mode {
         update_settings_file::<AllLanguageSettings>(fs, cx, move |settings, _cx| {
-            if let Some(edit_predictions) = settings.edit_predictions.as_mut() {
-                edit_predictions.mode = mode;
-            } else {
-                settings.edit_predictions =
-                    Some(language_settings::EditPredictionSettingsContent {
-                        mode,
+            settings
+                .edit_predictions
+                .get_or_insert_with(Default::default)
+                .mode = Some(mode);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{ExcerptRange, actions::MoveToEnd};
+    use fs::FakeFs;
+    use gpui::{Modifiers, TestAppContext, VisualTestContext, point, px};
+    use language::{Buffer, Capability, LanguageConfig, Point};
+    use project::Project;
+    use settings::parse_json_with_comments;
+    use std::{cell::Cell, rc::Rc};
+
+    #[gpui::test]
+    fn test_exclusion_globs_round_trip(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language_settings::init(cx);
+        });
+
+        let path = Path::new("src").join("parser").join("lexer.rs");
+        let root_file = Path::new("Makefile");
+        assert_eq!(Exclusion::Folder.glob(root_file), None);
+        assert_eq!(Exclusion::Extension.glob(root_file), None);
+
+        let old_text = indoc! {r#"
+            {
+              "edit_predictions": {
+                "disabled_globs": ["**/*.secret"]
+              }
+            }
+        "#};
+
+        for exclusion in Exclusion::ALL {
+            let glob = exclusion.glob(&path).unwrap();
+            match exclusion {
+                Exclusion::File => assert_eq!(glob, path.to_string_lossy()),
+                Exclusion::Folder => assert_eq!(Path::new(&glob), Path::new("src/parser/**")),
+                Exclusion::Extension => assert_eq!(glob, "**/*.rs"),
+            }
+
+            let new_text = apply_update(cx, old_text, {
+                let glob = glob.clone();
+                move |file| add_disabled_glob(file, glob)
+            });
+            assert_eq!(disabled_globs(&new_text), ["**/*.secret".to_string(), glob.clone()]);
+
+            let unchanged_text = apply_update(cx, &new_text, {
+                let glob = glob.clone();
+                move |file| add_disabled_glob(file, glob)
+            });
+            assert_eq!(disabled_globs(&unchanged_text), disabled_globs(&new_text));
+
+            let undone_text = apply_update(cx, &new_text, move |file| {
+                remove_disabled_glob_from(file, &glob)
+            });
+            assert_eq!(disabled_globs(&undone_text), ["**/*.secret".to_string()]);
+        }
+    }
+
+    #[gpui::test]
+    fn test_project_data_collection_writes_only_its_key(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language_settings::init(cx);
+        });
+
+        let new_text = apply_update(cx, "{}", |file| set_data_collection(file, Some(false)));
+        let settings = parse_json_with_comments::<serde_json::Value>(&new_text).unwrap();
+        let edit_predictions = settings["edit_predictions"].as_object().unwrap();
+        assert_eq!(edit_predictions.get("data_collection"), Some(&false.into()));
+        assert!(!edit_predictions.contains_key("mode"));
+        assert!(!edit_predictions.contains_key("enabled_in_text_threads"));
+    }
+
+    #[test]
+    fn test_file_state_meta() {
+        assert_eq!(file_state_meta(true, true), "Enabled For This File");
+        assert_eq!(file_state_meta(true, false), "Hidden For This File");
+        assert_eq!(file_state_meta(false, true), "Disabled For This File");
+        assert_eq!(file_state_meta(false, false), "Disabled For This File");
+    }
+
+    #[gpui::test]
+    fn test_ollama_model_choice_is_persisted(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language_settings::init(cx);
+        });
+
+        let new_text =
+            apply_update(cx, "{}", |file| set_ollama_model_in(file, "codellama:7b".into()));
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.set_user_settings(&new_text, cx).unwrap();
+            });
+            let settings = &all_language_settings(None, cx).edit_predictions.ollama;
+            assert_eq!(settings.model, "codellama:7b");
+        });
+
+        let settings = parse_json_with_comments::<serde_json::Value>(&new_text).unwrap();
+        let edit_predictions = settings["edit_predictions"].as_object().unwrap();
+        assert_eq!(edit_predictions.len(), 1);
+        assert_eq!(edit_predictions["ollama"], serde_json::json!({ "model": "codellama:7b" }));
+    }
+
+    #[gpui::test]
+    async fn test_secondary_click_toggles_predictions_globally(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            vim_mode_setting::init(cx);
+            SettingsStore::update_global(cx, |store: &mut SettingsStore, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.features.get_or_insert(Default::default()).edit_prediction_provider =
+                        Some(EditPredictionProvider::Copilot);
+                });
+            });
+        });
+        let (copilot, _lsp) = Copilot::fake(cx);
+        cx.update(|cx| Copilot::set_global(copilot, cx));
+
+        let fs = FakeFs::new(cx.executor());
+        fs.create_dir(paths::settings_file().parent().unwrap())
+            .await
+            .unwrap();
+        let project = Project::test(fs.clone(), [], cx).await;
+        let user_store = project.read_with(cx, |project, _| project.user_store());
+        let popover_menu_handle = PopoverMenuHandle::default();
+        let (_, cx) = cx.add_window_view({
+            let fs = fs.clone();
+            let popover_menu_handle = popover_menu_handle.clone();
+            |_, cx| InlineCompletionButton::new(fs, user_store, popover_menu_handle, cx)
+        });
+
+        let position = point(px(4.), px(4.));
+        cx.simulate_mouse_down(position, MouseButton::Right, Modifiers::none());
+        cx.simulate_mouse_up(position, MouseButton::Right, Modifiers::none());
+        cx.run_until_parked();
+
+        assert!(!popover_menu_handle.is_deployed());
+        let settings = fs.load(paths::settings_file()).await.unwrap();
+        let settings = parse_json_with_comments::<AllLanguageSettingsContent>(&settings).unwrap();
+        assert_eq!(settings.defaults.show_edit_predictions, Some(false));
+
+        cx.simulate_click(position, Modifiers::none());
+        cx.run_until_parked();
+        assert!(popover_menu_handle.is_deployed());
+    }
+
+    #[gpui::test]
+    fn test_pending_prediction_trigger_keeps_its_click_handler(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let clicked = Rc::new(Cell::new(false));
+        let popover_menu_handle = PopoverMenuHandle::default();
+        let (_, cx) = cx.add_window_view({
+            let clicked = clicked.clone();
+            let popover_menu_handle = popover_menu_handle.clone();
+            |_, _| PendingPredictionTriggerView {
+                clicked,
+                popover_menu_handle,
+            }
+        });
+
+        let position = point(px(4.), px(4.));
+        cx.simulate_click(position, Modifiers::none());
+        cx.run_until_parked();
+        assert!(clicked.get());
+        assert!(!popover_menu_handle.is_deployed());
+
+        cx.update(|window, cx| popover_menu_handle.toggle(window, cx));
+        cx.run_until_parked();
+        assert!(popover_menu_handle.is_deployed());
+    }
+
+    struct PendingPredictionTriggerView {
+        clicked: Rc<Cell<bool>>,
+        popover_menu_handle: PopoverMenuHandle<ContextMenu>,
+    }
+
+    impl Render for PendingPredictionTriggerView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            let clicked = self.clicked.clone();
+            let icon_button = IconButton::new("pending-prediction", IconName::ZedPredict)
+                .on_click(move |_, _, _| clicked.set(true));
+            PopoverMenu::new("pending-prediction-menu")
+                .menu(|window, cx| Some(ContextMenu::build(window, cx, |menu, _, _| menu)))
+                .with_handle(self.popover_menu_handle.clone())
+                .trigger(StatusTrigger::PendingPrediction(icon_button))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_provider_override_resets_on_settings_change(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs.clone(), [], cx).await;
+        let user_store = project.read_with(cx, |project, _| project.user_store());
+        let (button, cx) = cx.add_window_view(|_, cx| {
+            InlineCompletionButton::new(fs, user_store, PopoverMenuHandle::default(), cx)
+        });
+
+        button.update(cx, |button, cx| {
+            button.set_provider_override(Some(EditPredictionProvider::Ollama), cx);
+            assert_eq!(button.provider(cx), EditPredictionProvider::Ollama);
+        });
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store: &mut SettingsStore, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.show_edit_predictions = Some(false);
+                });
+            });
+        });
+        button.update(cx, |button, cx| {
+            assert_eq!(provider_override(cx), None);
+            assert_eq!(button.provider(cx), EditPredictionProvider::Zed);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_language_follows_cursor_after_debounce(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            vim_mode_setting::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs.clone(), [], cx).await;
+        let user_store = project.read_with(cx, |project, _| project.user_store());
+        let (button, cx) = cx.add_window_view(|_, cx| {
+            InlineCompletionButton::new(fs, user_store, PopoverMenuHandle::default(), cx)
+        });
+
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::new(Capability::ReadWrite);
+            for (name, text) in [("Rust", "fn main() {}\n"), ("SQL", "select 1;\n")] {
+                let language = Arc::new(Language::new(
+                    LanguageConfig {
+                        name: name.into(),
                         ..Default::default()
-                    });
+                    },
+                    None,
+                ));
+                let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+                multibuffer.push_excerpts(
+                    buffer,
+                    [ExcerptRange::new(Point::new(0, 0)..Point::new(1, 0))],
+                    cx,
+                );
             }
+            multibuffer
+        });
+        let editor = cx.new_window_entity(|window, cx| {
+            Editor::for_multibuffer(multibuffer, None, window, cx)
         });
+        button.update_in(cx, |button, window, cx| {
+            button.set_active_pane_item(Some(&editor), window, cx)
+        });
+        let language = |cx: &mut VisualTestContext| {
+            button.read_with(cx, |button, _| {
+                button.language.as_ref().map(|language| language.name())
+            })
+        };
+        assert_eq!(language(cx), Some("Rust".into()));
+
+        editor.update_in(cx, |editor, window, cx| editor.move_to_end(&MoveToEnd, window, cx));
+        cx.run_until_parked();
+        assert_eq!(language(cx), Some("Rust".into()));
+
+        cx.executor().advance_clock(LANGUAGE_UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(language(cx), Some("SQL".into()));
+    }
+
+    #[gpui::test]
+    async fn test_switching_to_zed_goes_through_onboarding(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.create_dir(paths::settings_file().parent().unwrap())
+            .await
+            .unwrap();
+        let project = Project::test(fs.clone(), [], cx).await;
+        let user_store = project.read_with(cx, |project, _| project.user_store());
+        let (_, cx) = cx.add_window_view({
+            let fs = fs.clone();
+            let user_store = user_store.clone();
+            |_, cx| InlineCompletionButton::new(fs, user_store, PopoverMenuHandle::default(), cx)
+        });
+
+        cx.update(|window, cx| {
+            switch_provider(EditPredictionProvider::Zed, fs.clone(), &user_store, window, cx)
+        });
+        cx.run_until_parked();
+        assert!(fs.load(paths::settings_file()).await.is_err());
+
+        cx.update(|window, cx| {
+            switch_provider(EditPredictionProvider::Supermaven, fs.clone(), &user_store, window, cx)
+        });
+        cx.run_until_parked();
+        let settings = fs.load(paths::settings_file()).await.unwrap();
+        let settings = parse_json_with_comments::<AllLanguageSettingsContent>(&settings).unwrap();
+        assert_eq!(
+            settings.features.and_then(|features| features.edit_prediction_provider),
+            Some(EditPredictionProvider::Supermaven)
+        );
+    }
+
+    fn apply_update(
+        cx: &mut TestAppContext,
+        text: &str,
+        update: impl FnOnce(&mut AllLanguageSettingsContent),
+    ) -> String {
+        cx.update(|cx| {
+            let edits = cx
+                .global::<SettingsStore>()
+                .edits_for_update::<AllLanguageSettings>(text, update);
+            let mut new_text = text.to_string();
+            for (range, replacement) in edits.into_iter() {
+                new_text.replace_range(range, &replacement);
+            }
+            new_text
+        })
+    }
+
+    fn disabled_globs(text: &str) -> Vec<String> {
+        parse_json_with_comments::<AllLanguageSettingsContent>(text)
+            .unwrap()
+            .edit_predictions
+            .and_then(|edit_predictions| edit_predictions.disabled_globs)
+            .unwrap_or_default()
     }
 }
@@ -43,6 +43,7 @@ use std::{
     cmp::{self, Ordering, Reverse},
     collections::{BTreeMap, BTreeSet},
     ffi::OsStr,
+    fmt,
     future::Future,
     iter::{self, Iterator, Peekable},
     mem,
@@ -4789,11 +4790,18 @@ pub(crate) fn contiguous_ranges(
     })
 }
 
+/// Decides which characters are word characters, in place of the defaults and the
+/// language's own word characters.
+pub trait WordChars: fmt::Debug + Send + Sync {
+    fn is_word(&self, c: char) -> bool;
+}
+
 #[derive(Default, Debug)]
 pub struct CharClassifier {
     scope: Option<LanguageScope>,
     for_completion: bool,
     ignore_punctuation: bool,
+    word_chars: Option<Arc<dyn WordChars>>,
 }
 
 impl CharClassifier {
@@ -4802,6 +4810,7 @@ impl CharClassifier {
             scope,
             for_completion: false,
             ignore_punctuation: false,
+            word_chars: None,
         }
     }
 
@@ -4819,6 +4828,10 @@ impl CharClassifier {
         }
     }
 
+    pub fn word_chars(self, word_chars: Option<Arc<dyn WordChars>>) -> Self {
+        Self { word_chars, ..self }
+    }
+
     pub fn is_whitespace(&self, c: char) -> bool {
         self.kind(c) == CharKind::Whitespace
     }
@@ -4832,10 +4845,30 @@ impl CharClassifier {
     }
 
     pub fn kind_with(&self, c: char, ignore_punctuation: bool) -> CharKind {
-        if c.is_alphanumeric() || c == '_' {
+        if self.is_word_char(c) {
             return CharKind::Word;
         }
 
+        if c.is_whitespace() {
+            return CharKind::Whitespace;
+        }
+
+        if ignore_punctuation {
+            CharKind::Word
+        } else {
+            CharKind::Punctuation
+        }
+    }
+
+    fn is_word_char(&self, c: char) -> bool {
+        if let Some(word_chars) = &self.word_chars {
+            return word_chars.is_word(c);
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            return true;
+        }
+
         if let Some(scope) = &self.scope {
             let characters = if self.for_completion {
                 scope.completion_query_characters()
@@ -4844,20 +4877,12 @@ impl CharClassifier {
             };
             if let Some(characters) = characters {
                 if characters.contains(&c) {
-                    return CharKind::Word;
+                    return true;
                 }
             }
         }
 
-        if c.is_whitespace() {
-            return CharKind::Whitespace;
-        }
-
-        if ignore_punctuation {
-            CharKind::Word
-        } else {
-            CharKind::Punctuation
-        }
+        false
     }
 
     pub fn kind(&self, c: char) -> CharKind {
@@ -763,6 +763,10 @@ pub struct LanguageConfig {
     /// A list of preferred debuggers for this language.
     #[serde(default)]
     pub debuggers: IndexSet<String>,
+    /// Groups of keywords that delimit a construct, such as `if`/`else`/`fi`.
+    /// Used by Vim's `%` motion to cycle between the keywords of a construct.
+    #[serde(default)]
+    pub match_words: Vec<MatchWordsConfig>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
@@ -780,6 +784,29 @@ pub struct LanguageMatcher {
     pub first_line_pattern: Option<Regex>,
 }
 
+/// Tokens that open, continue, and close a delimited construct. Entries are
+/// compared against the kinds of the tree-sitter tokens under the cursor.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+pub struct MatchWordsConfig {
+    /// Tokens that open the construct, e.g. `if` or `#ifdef`.
+    pub start: Vec<String>,
+    /// Tokens that may appear between the opening and closing tokens, e.g. `else`.
+    #[serde(default)]
+    pub middle: Vec<String>,
+    /// Tokens that close the construct, e.g. `fi` or `#endif`.
+    pub end: Vec<String>,
+}
+
+impl MatchWordsConfig {
+    pub fn contains(&self, kind: &str) -> bool {
+        self.start
+            .iter()
+            .chain(&self.middle)
+            .chain(&self.end)
+            .any(|word| word == kind)
+    }
+}
+
 /// The configuration for JSX tag auto-closing.
 #[derive(Clone, Deserialize, JsonSchema, Serialize, Debug)]
 pub struct JsxTagAutoCloseConfig {
@@ -889,6 +916,7 @@ impl Default for LanguageConfig {
             completion_query_characters: Default::default(),
             debuggers: Default::default(),
             significant_indentation: Default::default(),
+            match_words: Default::default(),
         }
     }
 }
@@ -23,7 +23,7 @@ use serde_json::Value;
 use settings::{
     Settings, SettingsLocation, SettingsSources, SettingsStore, add_references_to_properties,
 };
-use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc};
+use std::{borrow::Cow, iter, num::NonZeroU32, path::Path, sync::Arc};
 use util::serde::default_true;
 
 /// Initializes the language settings.
@@ -119,6 +119,9 @@ pub struct LanguageSettings {
     /// Controls whether edit predictions are shown immediately (true)
     /// or manually by triggering `editor::ShowEditPrediction` (false).
     pub show_edit_predictions: bool,
+    /// The edit prediction provider for this language, in place of the one set in
+    /// `features.edit_prediction_provider`.
+    pub edit_prediction_provider: Option<EditPredictionProvider>,
     /// Controls whether edit predictions are shown in the given language
     /// scopes.
     pub edit_predictions_disabled_in: Vec<String>,
@@ -215,6 +218,8 @@ pub enum EditPredictionProvider {
     Copilot,
     Supermaven,
     Zed,
+    Codeium,
+    Ollama,
 }
 
 impl EditPredictionProvider {
@@ -223,13 +228,15 @@ impl EditPredictionProvider {
             EditPredictionProvider::Zed => true,
             EditPredictionProvider::None
             | EditPredictionProvider::Copilot
-            | EditPredictionProvider::Supermaven => false,
+            | EditPredictionProvider::Supermaven
+            | EditPredictionProvider::Codeium
+            | EditPredictionProvider::Ollama => false,
         }
     }
 }
 
 /// The settings for edit predictions, such as [GitHub Copilot](https://github.com/features/copilot)
-/// or [Supermaven](https://supermaven.com).
+/// [Supermaven](https://supermaven.com) or [Codeium](https://codeium.com).
 #[derive(Clone, Debug, Default)]
 pub struct EditPredictionSettings {
     /// The provider that supplies edit predictions.
@@ -242,15 +249,31 @@ pub struct EditPredictionSettings {
     pub mode: EditPredictionsMode,
     /// Settings specific to GitHub Copilot.
     pub copilot: CopilotSettings,
+    /// Settings for predictions from a local Ollama server.
+    pub ollama: OllamaSettings,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
+    /// Whether to show the edit prediction button in the status bar.
+    pub show_status_button: bool,
+    /// Whether the status bar button pulses while its provider is busy.
+    pub animate_status_icon: bool,
+    /// Whether predictions are only requested in vim's insert and replace modes.
+    pub vim_insert_mode_only: bool,
+    /// Whether training data may be collected from the worktree, overriding the data
+    /// collection choice and open source detection.
+    pub data_collection: Option<bool>,
 }
 
 impl EditPredictionSettings {
     /// Returns whether edit predictions are enabled for the given path.
     pub fn enabled_for_file(&self, file: &Arc<dyn File>, cx: &App) -> bool {
-        !self.disabled_globs.iter().any(|glob| {
+        self.disabled_glob_for_file(file, cx).is_none()
+    }
+
+    /// Returns the disabled glob that excludes the given path, if any.
+    pub fn disabled_glob_for_file(&self, file: &Arc<dyn File>, cx: &App) -> Option<&DisabledGlob> {
+        self.disabled_globs.iter().find(|glob| {
             if glob.is_absolute {
                 file.as_local()
                     .map_or(false, |local| glob.matcher.is_match(local.abs_path(cx)))
@@ -263,8 +286,22 @@ impl EditPredictionSettings {
 
 #[derive(Clone, Debug)]
 pub struct DisabledGlob {
+    glob: String,
     matcher: GlobMatcher,
     is_absolute: bool,
+    is_default: bool,
+}
+
+impl DisabledGlob {
+    /// The glob as it was written in the settings.
+    pub fn glob(&self) -> &str {
+        &self.glob
+    }
+
+    /// Whether the glob comes from the default settings, in which case it can't be removed.
+    pub fn is_default(&self) -> bool {
+        self.is_default
+    }
 }
 
 /// The mode in which edit predictions should be displayed.
@@ -281,12 +318,22 @@ pub enum EditPredictionsMode {
     Eager,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct CopilotSettings {
     /// HTTP/HTTPS proxy to use for Copilot.
     pub proxy: Option<String>,
     /// Disable certificate verification for proxy (not recommended).
     pub proxy_no_verify: Option<bool>,
+    /// The GitHub Enterprise Server instance to sign in to, instead of github.com.
+    pub enterprise_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OllamaSettings {
+    /// The URL of the Ollama server.
+    pub api_url: String,
+    /// The model that fills in the code around the cursor.
+    pub model: String,
 }
 
 /// The settings for all languages.
@@ -481,6 +528,12 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub show_edit_predictions: Option<bool>,
+    /// The edit prediction provider for this language, in place of the one set in
+    /// `features.edit_prediction_provider`.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub edit_prediction_provider: Option<EditPredictionProvider>,
     /// Controls whether edit predictions are shown in the given language
     /// scopes.
     ///
@@ -582,15 +635,47 @@ pub struct EditPredictionSettingsContent {
     pub disabled_globs: Option<Vec<String>>,
     /// The mode used to display edit predictions in the buffer.
     /// Provider support required.
+    ///
+    /// Default: eager
     #[serde(default)]
-    pub mode: EditPredictionsMode,
+    pub mode: Option<EditPredictionsMode>,
     /// Settings specific to GitHub Copilot.
     #[serde(default)]
     pub copilot: CopilotSettingsContent,
+    /// Settings for predictions from a local Ollama server.
+    #[serde(default)]
+    pub ollama: OllamaSettingsContent,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
-    #[serde(default = "default_true")]
-    pub enabled_in_text_threads: bool,
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub enabled_in_text_threads: Option<bool>,
+    /// Whether to show the edit prediction button in the status bar.
+    /// The edit prediction actions keep working when it is hidden.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub show_status_button: Option<bool>,
+    /// Whether the edit prediction button in the status bar pulses while the provider is
+    /// busy. When off, a badge on the button marks it as busy instead.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub animate_status_icon: Option<bool>,
+    /// Whether to only request edit predictions in vim's insert and replace modes, instead
+    /// of also requesting them while navigating in normal mode.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub vim_insert_mode_only: Option<bool>,
+    /// Whether Zed may collect training data from the files this applies to. Meant for
+    /// project settings, where it takes precedence over the choice made in the edit
+    /// prediction menu and over the detection of an open source license.
+    ///
+    /// Default: none
+    #[serde(default)]
+    pub data_collection: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -605,6 +690,27 @@ pub struct CopilotSettingsContent {
     /// Default: false
     #[serde(default)]
     pub proxy_no_verify: Option<bool>,
+    /// The URL of a GitHub Enterprise Server instance to sign in to, instead of github.com.
+    /// An empty string means github.com.
+    ///
+    /// Default: none
+    #[serde(default)]
+    pub enterprise_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct OllamaSettingsContent {
+    /// The URL of the Ollama server.
+    ///
+    /// Default: "http://localhost:11434"
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// The model that fills in the code around the cursor.
+    /// It has to support fill-in-the-middle prompts.
+    ///
+    /// Default: "qwen2.5-coder:1.5b"
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 /// The settings for enabling/disabling features.
@@ -1099,6 +1205,27 @@ impl AllLanguageSettings {
             .show_edit_predictions
     }
 
+    /// Returns the edit prediction provider for the given language, which is the language's
+    /// own provider if it sets one and the global provider otherwise.
+    pub fn edit_prediction_provider(
+        &self,
+        language_name: Option<&LanguageName>,
+    ) -> EditPredictionProvider {
+        language_name
+            .and_then(|name| self.languages.get(name))
+            .unwrap_or(&self.defaults)
+            .edit_prediction_provider
+            .unwrap_or(self.edit_predictions.provider)
+    }
+
+    /// Returns whether `provider` supplies edit predictions for any language.
+    pub fn uses_edit_prediction_provider(&self, provider: EditPredictionProvider) -> bool {
+        self.edit_predictions.provider == provider
+            || iter::once(&self.defaults)
+                .chain(self.languages.values())
+                .any(|settings| settings.edit_prediction_provider == Some(provider))
+    }
+
     /// Returns the edit predictions preview mode for the given language and path.
     pub fn edit_predictions_mode(&self) -> EditPredictionsMode {
         self.edit_predictions.mode
@@ -1202,7 +1329,7 @@ impl settings::Settings for AllLanguageSettings {
         let mut edit_predictions_mode = default_value
             .edit_predictions
             .as_ref()
-            .map(|edit_predictions| edit_predictions.mode)
+            .and_then(|edit_predictions| edit_predictions.mode)
             .ok_or_else(Self::missing_default)?;
 
         let mut completion_globs: HashSet<&String> = default_value
@@ -1211,6 +1338,7 @@ impl settings::Settings for AllLanguageSettings {
             .and_then(|c| c.disabled_globs.as_ref())
             .map(|globs| globs.iter().collect())
             .ok_or_else(Self::missing_default)?;
+        let default_globs = completion_globs.clone();
 
         let mut copilot_settings = default_value
             .edit_predictions
@@ -1219,15 +1347,48 @@ impl settings::Settings for AllLanguageSettings {
             .map(|copilot| CopilotSettings {
                 proxy: copilot.proxy,
                 proxy_no_verify: copilot.proxy_no_verify,
+                enterprise_uri: copilot.enterprise_uri.filter(|uri| !uri.is_empty()),
+            })
+            .unwrap_or_default();
+
+        let mut ollama_settings = default_value
+            .edit_predictions
+            .as_ref()
+            .map(|settings| OllamaSettings {
+                api_url: settings.ollama.api_url.clone().unwrap_or_default(),
+                model: settings.ollama.model.clone().unwrap_or_default(),
             })
             .unwrap_or_default();
 
         let mut enabled_in_text_threads = default_value
             .edit_predictions
             .as_ref()
-            .map(|settings| settings.enabled_in_text_threads)
+            .and_then(|settings| settings.enabled_in_text_threads)
+            .unwrap_or(true);
+
+        let mut show_status_button = default_value
+            .edit_predictions
+            .as_ref()
+            .and_then(|settings| settings.show_status_button)
+            .unwrap_or(true);
+
+        let mut animate_status_icon = default_value
+            .edit_predictions
+            .as_ref()
+            .and_then(|settings| settings.animate_status_icon)
             .unwrap_or(true);
 
+        let mut vim_insert_mode_only = default_value
+            .edit_predictions
+            .as_ref()
+            .and_then(|settings| settings.vim_insert_mode_only)
+            .unwrap_or(false);
+
+        let mut data_collection = default_value
+            .edit_predictions
+            .as_ref()
+            .and_then(|settings| settings.data_collection);
+
         let mut file_types: FxHashMap<Arc<str>, GlobSet> = FxHashMap::default();
 
         for (language, patterns) in &default_value.file_types {
@@ -1250,8 +1411,24 @@ impl settings::Settings for AllLanguageSettings {
             }
 
             if let Some(edit_predictions) = user_settings.edit_predictions.as_ref() {
-                edit_predictions_mode = edit_predictions.mode;
-                enabled_in_text_threads = edit_predictions.enabled_in_text_threads;
+                if let Some(mode) = edit_predictions.mode {
+                    edit_predictions_mode = mode;
+                }
+                if let Some(enabled) = edit_predictions.enabled_in_text_threads {
+                    enabled_in_text_threads = enabled;
+                }
+                if let Some(show) = edit_predictions.show_status_button {
+                    show_status_button = show;
+                }
+                if let Some(animate) = edit_predictions.animate_status_icon {
+                    animate_status_icon = animate;
+                }
+                if let Some(insert_mode_only) = edit_predictions.vim_insert_mode_only {
+                    vim_insert_mode_only = insert_mode_only;
+                }
+                if let Some(enabled) = edit_predictions.data_collection {
+                    data_collection = Some(enabled);
+                }
 
                 if let Some(disabled_globs) = edit_predictions.disabled_globs.as_ref() {
                     completion_globs.extend(disabled_globs.iter());
@@ -1274,6 +1451,28 @@ impl settings::Settings for AllLanguageSettings {
                 copilot_settings.proxy_no_verify = Some(proxy_no_verify);
             }
 
+            if let Some(enterprise_uri) = user_settings
+                .edit_predictions
+                .as_ref()
+                .and_then(|settings| settings.copilot.enterprise_uri.clone())
+            {
+                copilot_settings.enterprise_uri =
+                    Some(enterprise_uri).filter(|uri| !uri.is_empty());
+            }
+
+            if let Some(ollama) = user_settings
+                .edit_predictions
+                .as_ref()
+                .map(|settings| &settings.ollama)
+            {
+                if let Some(api_url) = ollama.api_url.clone() {
+                    ollama_settings.api_url = api_url;
+                }
+                if let Some(model) = ollama.model.clone() {
+                    ollama_settings.model = model;
+                }
+            }
+
             // A user's global settings override the default global settings and
             // all default language-specific settings.
             merge_settings(&mut defaults, &user_settings.defaults);
@@ -1322,14 +1521,21 @@ impl settings::Settings for AllLanguageSettings {
                     .iter()
                     .filter_map(|g| {
                         Some(DisabledGlob {
+                            glob: g.to_string(),
                             matcher: globset::Glob::new(g).ok()?.compile_matcher(),
                             is_absolute: Path::new(g).is_absolute(),
+                            is_default: default_globs.contains(g),
                         })
                     })
                     .collect(),
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
+                ollama: ollama_settings,
                 enabled_in_text_threads,
+                show_status_button,
+                animate_status_icon,
+                vim_insert_mode_only,
+                data_collection,
             },
             defaults,
             languages,
@@ -1558,6 +1764,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.show_edit_predictions,
         src.show_edit_predictions,
     );
+    merge(
+        &mut settings.edit_prediction_provider,
+        src.edit_prediction_provider.map(Some),
+    );
     merge(
         &mut settings.edit_predictions_disabled_in,
         src.edit_predictions_disabled_in.clone(),
@@ -1685,8 +1895,10 @@ mod tests {
                         let glob_str = glob_str.as_str();
 
                         DisabledGlob {
+                            glob: glob_str.to_string(),
                             matcher: globset::Glob::new(glob_str).unwrap().compile_matcher(),
                             is_absolute: Path::new(glob_str).is_absolute(),
+                            is_default: false,
                         }
                     })
                     .collect(),
@@ -1715,6 +1927,13 @@ mod tests {
         // Test relative globs
         let settings = build_settings(&["*.rs"]);
         assert!(!settings.enabled_for_file(&test_file, &cx));
+        let settings = build_settings(&["*.txt", "*.rs"]);
+        assert_eq!(
+            settings
+                .disabled_glob_for_file(&test_file, &cx)
+                .map(|glob| glob.glob()),
+            Some("*.rs")
+        );
         let settings = build_settings(&["*.txt"]);
         assert!(settings.enabled_for_file(&test_file, &cx));
 
@@ -1784,6 +2003,36 @@ mod tests {
         assert!(!settings.enabled_for_file(&dot_env_file, &cx));
     }
 
+    #[gpui::test]
+    fn test_edit_prediction_provider_for_language(cx: &mut App) {
+        cx.set_global(SettingsStore::test(cx));
+        AllLanguageSettings::register(cx);
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.features.get_or_insert(Default::default()).edit_prediction_provider =
+                    Some(EditPredictionProvider::Copilot);
+                settings
+                    .languages
+                    .entry("Markdown".into())
+                    .or_default()
+                    .edit_prediction_provider = Some(EditPredictionProvider::Zed);
+            });
+        });
+
+        let settings = AllLanguageSettings::get_global(cx);
+        assert_eq!(
+            settings.edit_prediction_provider(Some(&"Markdown".into())),
+            EditPredictionProvider::Zed
+        );
+        assert_eq!(
+            settings.edit_prediction_provider(Some(&"Rust".into())),
+            EditPredictionProvider::Copilot
+        );
+        assert_eq!(settings.edit_prediction_provider(None), EditPredictionProvider::Copilot);
+        assert!(settings.uses_edit_prediction_provider(EditPredictionProvider::Zed));
+        assert!(!settings.uses_edit_prediction_provider(EditPredictionProvider::Supermaven));
+    }
+
     #[test]
     pub fn test_resolve_language_servers() {
         fn language_server_names(names: &[&str]) -> Vec<LanguageServerName> {
@@ -130,7 +130,9 @@ impl LanguageModelProvider for CopilotChatLanguageModelProvider {
         };
 
         let err = match copilot.read(cx).status() {
-            Status::Authorized => return Task::ready(Ok(())),
+            Status::Authorized | Status::RateLimited | Status::Unreachable(_) => {
+                return Task::ready(Ok(()));
+            }
             Status::Disabled => anyhow!(
                 "Copilot must be enabled for Copilot Chat to work. Please enable Copilot and try again."
             ),
@@ -15,8 +15,10 @@ use project::{Project, WorktreeId, search::SearchQuery};
 use std::{any::TypeId, borrow::Cow, sync::Arc};
 use ui::{Button, Checkbox, ContextMenu, Label, PopoverMenu, ToggleState, prelude::*};
 use workspace::{
-    SplitDirection, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace, WorkspaceId,
+    SplitDirection, Toast, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
+    WorkspaceId,
     item::{Item, ItemHandle},
+    notifications::NotificationId,
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
 };
 
@@ -28,6 +30,7 @@ pub struct LogStore {
     projects: HashMap<WeakEntity<Project>, ProjectState>,
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     copilot_log_subscription: Option<lsp::Subscription>,
+    copilot_server_id: Option<LanguageServerId>,
     _copilot_subscription: Option<gpui::Subscription>,
     io_tx: mpsc::UnboundedSender<(LanguageServerId, IoKind, String)>,
 }
@@ -228,6 +231,29 @@ pub fn init(cx: &mut App) {
                 );
             }
         });
+
+        let log_store = log_store.clone();
+        workspace.register_action(move |workspace, _: &copilot::OpenLog, window, cx| {
+            let Some(server_id) = log_store.read(cx).copilot_server_id else {
+                struct CopilotLogToast;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<CopilotLogToast>(),
+                        "Copilot hasn't started yet, so there is no log to show.",
+                    )
+                    .autohide(),
+                    cx,
+                );
+                return;
+            };
+            let project = workspace.project().clone();
+            let log_view = cx.new(|cx| {
+                let mut log_view = LspLogView::new(project, log_store.clone(), window, cx);
+                log_view.show_logs_for_server(server_id, window, cx);
+                log_view
+            });
+            workspace.split_item(SplitDirection::Right, Box::new(log_view), window, cx);
+        });
     })
     .detach();
 }
@@ -242,6 +268,7 @@ impl LogStore {
                 if let copilot::Event::CopilotLanguageServerStarted = inline_completion_event {
                     if let Some(server) = copilot.read(cx).language_server() {
                         let server_id = server.server_id();
+                        this.copilot_server_id = Some(server_id);
                         let weak_this = cx.weak_entity();
                         this.copilot_log_subscription =
                             Some(server.on_notification::<copilot::request::LogMessage, _>(
@@ -274,6 +301,7 @@ impl LogStore {
 
         let this = Self {
             copilot_log_subscription: None,
+            copilot_server_id: None,
             _copilot_subscription: copilot_subscription,
             projects: HashMap::default(),
             language_servers: HashMap::default(),
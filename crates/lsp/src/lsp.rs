@@ -46,6 +46,8 @@ const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
+const INTERNAL_ERROR_CODE: i64 = -32603;
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<RequestId>, Value, &mut AsyncApp)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -256,9 +258,45 @@ struct AnyNotification {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Error {
+    #[serde(default)]
+    code: i64,
     message: String,
 }
 
+impl Error {
+    /// Answers a request with the error its handler failed with, keeping the code of a
+    /// `ResponseError`.
+    fn from_handler_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<ResponseError>() {
+            Some(error) => Self {
+                code: error.code,
+                message: error.message.clone(),
+            },
+            None => Self {
+                code: INTERNAL_ERROR_CODE,
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// The error a request was answered with, with the code the other side reported.
+///
+/// [LSP Specification](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#responseError)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
 pub trait LspRequestFuture<O>: Future<Output = O> {
     fn id(&self) -> i32;
 }
@@ -995,9 +1033,9 @@ impl LanguageServer {
                                             Err(error) => Response {
                                                 jsonrpc: JSON_RPC_VERSION,
                                                 id,
-                                                value: LspResult::Error(Some(Error {
-                                                    message: error.to_string(),
-                                                })),
+                                                value: LspResult::Error(Some(
+                                                    Error::from_handler_error(&error),
+                                                )),
                                             },
                                         };
                                         if let Some(response) =
@@ -1017,6 +1055,7 @@ impl LanguageServer {
                                 id,
                                 result: None,
                                 error: Some(Error {
+                                    code: INVALID_PARAMS_ERROR_CODE,
                                     message: error.to_string(),
                                 }),
                             };
@@ -1136,7 +1175,11 @@ impl LanguageServer {
                                             Err(error).context("failed to deserialize response")
                                         }
                                     }
-                                    Err(error) => Err(anyhow!("{}", error.message)),
+                                    Err(error) => Err(ResponseError {
+                                        code: error.code,
+                                        message: error.message,
+                                    }
+                                    .into()),
                                 };
                                 _ = tx.send(response);
                             })
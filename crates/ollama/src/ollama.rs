@@ -174,6 +174,23 @@ pub struct ChatResponseDelta {
     pub done: bool,
 }
 
+/// A fill-in-the-middle request: the model generates the text between `prompt` and `suffix`.
+#[derive(Serialize, Debug)]
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    pub suffix: Option<String>,
+    pub stream: bool,
+    pub keep_alive: KeepAlive,
+    pub options: Option<ChatOptions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GenerateResponse {
+    pub response: String,
+    pub done: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LocalModelsResponse {
     pub models: Vec<LocalModelListing>,
@@ -250,6 +267,44 @@ pub async fn complete(
     }
 }
 
+/// Streams a fill-in-the-middle completion, one chunk of generated text per item.
+pub async fn stream_generate(
+    client: &dyn HttpClient,
+    api_url: &str,
+    request: GenerateRequest,
+) -> Result<BoxStream<'static, Result<GenerateResponse>>> {
+    let uri = format!("{api_url}/api/generate");
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    if response.status().is_success() {
+        let reader = BufReader::new(response.into_body());
+
+        Ok(reader
+            .lines()
+            .map(|line| match line {
+                Ok(line) => {
+                    serde_json::from_str(&line).context("Unable to parse generate response")
+                }
+                Err(e) => Err(e.into()),
+            })
+            .boxed())
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        Err(anyhow!(
+            "Failed to connect to Ollama API: {} {}",
+            response.status(),
+            body,
+        ))
+    }
+}
+
 pub async fn stream_chat_completion(
     client: &dyn HttpClient,
     api_url: &str,
@@ -399,6 +454,34 @@ mod tests {
         let _: ChatResponseDelta = serde_json::from_value(response).unwrap();
     }
 
+    #[test]
+    fn parse_generate_response() {
+        let response = serde_json::json!({
+        "model": "qwen2.5-coder:1.5b",
+        "created_at": "2024-10-08T09:21:35.478564Z",
+        "response": "a + b\n}",
+        "done": true,
+        "done_reason": "stop",
+        "total_duration": 281497917u64,
+        "eval_count": 5
+        });
+        let response: GenerateResponse = serde_json::from_value(response).unwrap();
+        assert_eq!(response.response, "a + b\n}");
+    }
+
+    #[test]
+    fn parse_streaming_generate_response() {
+        let partial = serde_json::json!({
+        "model": "qwen2.5-coder:1.5b",
+        "created_at": "2024-10-08T09:21:35.478564Z",
+        "response": "a +",
+        "done": false
+        });
+        let partial: GenerateResponse = serde_json::from_value(partial).unwrap();
+        assert_eq!(partial.response, "a +");
+        assert!(!partial.done);
+    }
+
     #[test]
     fn parse_streaming_completion() {
         let partial = serde_json::json!({
@@ -0,0 +1,336 @@
+mod ollama_completion_provider;
+
+pub use ollama_completion_provider::*;
+
+use anyhow::Result;
+use gpui::{App, AppContext as _, Context, Entity, Global, SharedString, Task};
+use http_client::HttpClient;
+use language::language_settings::{EditPredictionProvider, OllamaSettings, all_language_settings};
+use settings::SettingsStore;
+use std::sync::Arc;
+
+pub fn init(http_client: Arc<dyn HttpClient>, cx: &mut App) {
+    let ollama = cx.new(|_| LocalModel::new(http_client));
+    LocalModel::set_global(ollama.clone(), cx);
+
+    let mut settings = current_settings(cx);
+    if settings.0 {
+        ollama.update(cx, |ollama, cx| ollama.check_connection(cx));
+    }
+
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_settings = current_settings(cx);
+        if new_settings != settings {
+            settings = new_settings;
+            if settings.0 {
+                ollama.update(cx, |ollama, cx| ollama.check_connection(cx));
+            }
+        }
+    })
+    .detach();
+}
+
+/// Whether a local model is in use for any language, and the server and model to check.
+fn current_settings(cx: &App) -> (bool, String, String) {
+    let settings = all_language_settings(None, cx);
+    (
+        settings.uses_edit_prediction_provider(EditPredictionProvider::Ollama),
+        settings.edit_predictions.ollama.api_url.clone(),
+        settings.edit_predictions.ollama.model.clone(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LocalModelStatus {
+    Unknown,
+    Checking,
+    Connected,
+    Error(SharedString),
+}
+
+/// The Ollama server that the local model provider talks to, and whether it last answered.
+pub struct LocalModel {
+    http_client: Arc<dyn HttpClient>,
+    status: LocalModelStatus,
+    /// The models the server listed on the last successful check.
+    installed_models: Vec<SharedString>,
+    pending_check: Option<Task<()>>,
+}
+
+#[derive(Clone)]
+struct LocalModelGlobal(Entity<LocalModel>);
+
+impl Global for LocalModelGlobal {}
+
+impl LocalModel {
+    fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            status: LocalModelStatus::Unknown,
+            installed_models: Vec::new(),
+            pending_check: None,
+        }
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<LocalModelGlobal>()
+            .map(|model| model.0.clone())
+    }
+
+    pub fn set_global(local_model: Entity<Self>, cx: &mut App) {
+        cx.set_global(LocalModelGlobal(local_model));
+    }
+
+    pub fn status(&self) -> &LocalModelStatus {
+        &self.status
+    }
+
+    pub fn installed_models(&self) -> &[SharedString] {
+        &self.installed_models
+    }
+
+    pub fn http_client(&self) -> Arc<dyn HttpClient> {
+        self.http_client.clone()
+    }
+
+    /// Asks the server which models it has, and reports an error if the configured one is missing.
+    pub fn check_connection(&mut self, cx: &mut Context<Self>) {
+        let settings = all_language_settings(None, cx).edit_predictions.ollama.clone();
+        let http_client = self.http_client.clone();
+        self.status = LocalModelStatus::Checking;
+        cx.notify();
+
+        self.pending_check = Some(cx.spawn(async move |this, cx| {
+            let models = ollama::get_models(http_client.as_ref(), &settings.api_url, None).await;
+            let installed_models = models.as_ref().map_or(Vec::new(), |models| {
+                models
+                    .iter()
+                    .map(|model| SharedString::from(model.name.clone()))
+                    .collect()
+            });
+            let status = match models {
+                Ok(models) if models.iter().any(|m| is_same_model(&m.name, &settings.model)) => {
+                    LocalModelStatus::Connected
+                }
+                Ok(_) => LocalModelStatus::Error(
+                    format!(
+                        "{} isn't installed, run `ollama pull {}`",
+                        settings.model, settings.model
+                    )
+                    .into(),
+                ),
+                Err(error) => LocalModelStatus::Error(format!("{error:#}").into()),
+            };
+            this.update(cx, |this, cx| {
+                this.status = status;
+                this.installed_models = installed_models;
+                this.pending_check = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn record_request<T>(&mut self, result: &Result<T>, cx: &mut Context<Self>) {
+        let status = match result {
+            Ok(_) => LocalModelStatus::Connected,
+            Err(error) => LocalModelStatus::Error(format!("{error:#}").into()),
+        };
+        if status != self.status {
+            self.status = status;
+            cx.notify();
+        }
+    }
+}
+
+/// The host and model shown in the status bar menu, e.g. "localhost:11434 — qwen2.5-coder:1.5b".
+pub fn connection_label(settings: &OllamaSettings) -> String {
+    let host = settings
+        .api_url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/');
+    format!("{host} — {}", settings.model)
+}
+
+/// Ollama lists models with an explicit tag, so `qwen2.5-coder` shows up as `qwen2.5-coder:latest`.
+fn is_same_model(installed: &str, configured: &str) -> bool {
+    installed == configured || installed.strip_suffix(":latest") == Some(configured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use http_client::{FakeHttpClient, Response};
+    use inline_completion::{EditPredictionProvider as _, InlineCompletionChunk};
+    use language::Buffer;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language::init(cx);
+        });
+    }
+
+    /// Answers every generate request with `lines`, one JSON object per line.
+    fn generate_http_client(lines: &'static [&'static str]) -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(move |request| async move {
+            assert_eq!(request.uri().path(), "/api/generate");
+            let body = lines.iter().map(|line| format!("{line}\n")).collect::<String>();
+            Ok(Response::builder()
+                .status(200)
+                .body(body.into())
+                .unwrap())
+        })
+    }
+
+    fn model_listing(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "modified_at": "2024-10-08T09:21:35.478564Z",
+            "size": 986061892,
+            "digest": "6d3abb8d2d53",
+            "details": {
+                "format": "gguf",
+                "family": "qwen2",
+                "families": null,
+                "parameter_size": "1.5B",
+                "quantization_level": "Q4_K_M"
+            }
+        })
+    }
+
+    #[gpui::test]
+    async fn test_installed_models(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client = FakeHttpClient::create(|_| async move {
+            let models = [model_listing("qwen2.5-coder:1.5b"), model_listing("codellama:7b")];
+            Ok(Response::builder()
+                .status(200)
+                .body(serde_json::json!({ "models": models }).to_string().into())
+                .unwrap())
+        });
+        let local_model = cx.new(|_| LocalModel::new(http_client));
+        let provider = cx.new(|_| OllamaCompletionProvider::new(local_model.clone()));
+
+        provider.read_with(cx, |provider, cx| assert!(provider.models(cx).is_empty()));
+
+        local_model.update(cx, |local_model, cx| local_model.check_connection(cx));
+        cx.run_until_parked();
+        provider.read_with(cx, |provider, cx| {
+            assert_eq!(
+                provider.models(cx),
+                vec![
+                    SharedString::from("qwen2.5-coder:1.5b"),
+                    SharedString::from("codellama:7b")
+                ]
+            );
+            assert_eq!(provider.active_model(cx), Some("qwen2.5-coder:1.5b".into()));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_streamed_prediction(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client = generate_http_client(&[
+            r#"{"response": "let x", "done": false}"#,
+            r#"{"response": " = 1;", "done": false}"#,
+            r#"{"response": "", "done": true}"#,
+        ]);
+        let local_model = cx.new(|_| LocalModel::new(http_client));
+        let provider = cx.new(|_| OllamaCompletionProvider::new(local_model.clone()));
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    \n}", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(16));
+
+        let chunks = provider
+            .update(cx, |provider, cx| provider.complete_streaming(&buffer, cursor, cx))
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            chunks,
+            [
+                InlineCompletionChunk::Token("let x".into()),
+                InlineCompletionChunk::Token(" = 1;".into()),
+                InlineCompletionChunk::Done,
+            ]
+        );
+
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx)
+        });
+        cx.run_until_parked();
+        let completion = provider
+            .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+            .unwrap();
+        assert_eq!(completion.edits[0].1, "let x = 1;");
+        local_model.read_with(cx, |local_model, _| {
+            assert_eq!(*local_model.status(), LocalModelStatus::Connected)
+        });
+    }
+
+    #[gpui::test]
+    async fn test_error_mid_stream_clears_prediction(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client =
+            generate_http_client(&[r#"{"response": "let x", "done": false}"#, "not json"]);
+        let local_model = cx.new(|_| LocalModel::new(http_client));
+        let provider = cx.new(|_| OllamaCompletionProvider::new(local_model.clone()));
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    \n}", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(16));
+
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx)
+        });
+        cx.run_until_parked();
+
+        assert!(
+            provider
+                .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+                .is_none()
+        );
+        provider.read_with(cx, |provider, cx| {
+            assert!(!provider.is_refreshing());
+            assert!(provider.last_error(cx).is_some());
+        });
+        local_model.read_with(cx, |local_model, _| {
+            assert!(matches!(local_model.status(), LocalModelStatus::Error(_)))
+        });
+    }
+
+    #[gpui::test]
+    async fn test_recovers_after_unreachable(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client = FakeHttpClient::create(|request| async move {
+            if request.uri().path() == "/api/generate" {
+                let body = r#"{"response": "let x = 1;", "done": true}"#;
+                Ok(Response::builder().status(200).body(body.into()).unwrap())
+            } else {
+                Ok(Response::builder().status(404).body("".into()).unwrap())
+            }
+        });
+        let local_model = cx.new(|_| LocalModel::new(http_client));
+        let provider = cx.new(|_| OllamaCompletionProvider::new(local_model.clone()));
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    \n}", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(16));
+
+        local_model.update(cx, |local_model, cx| local_model.check_connection(cx));
+        cx.run_until_parked();
+        local_model.read_with(cx, |local_model, _| {
+            assert!(matches!(local_model.status(), LocalModelStatus::Error(_)))
+        });
+        provider.read_with(cx, |provider, cx| assert!(provider.is_enabled(&buffer, cursor, cx)));
+
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx)
+        });
+        cx.run_until_parked();
+        local_model.read_with(cx, |local_model, _| {
+            assert_eq!(*local_model.status(), LocalModelStatus::Connected)
+        });
+    }
+}
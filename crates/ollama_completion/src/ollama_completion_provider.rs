@@ -0,0 +1,260 @@
+use crate::LocalModel;
+use anyhow::Result;
+use futures::{
+    StreamExt as _, TryStreamExt as _,
+    stream::{self, BoxStream},
+};
+use gpui::{App, Context, Entity, EntityId, SharedString, Task};
+use inline_completion::{
+    Direction, EditPredictionError, EditPredictionProvider, EditPredictionRequestStats,
+    InlineCompletion, InlineCompletionChunk,
+};
+use language::{Anchor, Buffer, ToOffset, language_settings::all_language_settings};
+use ollama::{ChatOptions, GenerateRequest, KeepAlive};
+use project::Project;
+use std::time::{Duration, Instant};
+use text::Bias;
+
+pub const OLLAMA_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// How much of the buffer around the cursor is sent as context.
+const PREFIX_BYTES: usize = 4096;
+const SUFFIX_BYTES: usize = 1024;
+const MAX_PREDICTED_TOKENS: isize = 128;
+
+pub struct OllamaCompletionProvider {
+    local_model: Entity<LocalModel>,
+    buffer_id: Option<EntityId>,
+    completion: Option<CurrentCompletion>,
+    pending_refresh: Option<Task<Result<()>>>,
+    request_stats: EditPredictionRequestStats,
+}
+
+struct CurrentCompletion {
+    position: Anchor,
+    text: String,
+}
+
+impl OllamaCompletionProvider {
+    pub fn new(local_model: Entity<LocalModel>) -> Self {
+        Self {
+            local_model,
+            buffer_id: None,
+            completion: None,
+            pending_refresh: None,
+            request_stats: EditPredictionRequestStats::default(),
+        }
+    }
+}
+
+impl EditPredictionProvider for OllamaCompletionProvider {
+    fn name() -> &'static str {
+        "ollama"
+    }
+
+    fn display_name() -> &'static str {
+        "Local Model"
+    }
+
+    fn show_completions_in_menu() -> bool {
+        false
+    }
+
+    /// Stays on while the server is unreachable, so that the next request that goes through
+    /// clears the error shown on the status bar icon.
+    fn is_enabled(&self, _buffer: &Entity<Buffer>, _cursor_position: Anchor, _cx: &App) -> bool {
+        true
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.pending_refresh.is_some()
+    }
+
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        self.request_stats.last_latency()
+    }
+
+    fn last_error(&self, _cx: &App) -> Option<EditPredictionError> {
+        self.request_stats.last_error()
+    }
+
+    fn models(&self, cx: &App) -> Vec<SharedString> {
+        self.local_model.read(cx).installed_models().to_vec()
+    }
+
+    fn active_model(&self, cx: &App) -> Option<SharedString> {
+        let model = &all_language_settings(None, cx).edit_predictions.ollama.model;
+        Some(model.clone().into())
+    }
+
+    fn refresh(
+        &mut self,
+        _project: Option<Entity<Project>>,
+        buffer: Entity<Buffer>,
+        cursor_position: Anchor,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let local_model = self.local_model.clone();
+
+        // Replacing the task drops the previous one, which cancels its in-flight request.
+        // Local models are slow enough that finishing stale requests would queue up behind typing.
+        self.pending_refresh = Some(cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor()
+                    .timer(OLLAMA_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+
+            let position = buffer.read_with(cx, |buffer, _| cursor_position.bias_left(buffer))?;
+            let Some(mut chunks) = this.update(cx, |this, cx| {
+                this.complete_streaming(&buffer, cursor_position, cx)
+            })?
+            else {
+                return Ok(());
+            };
+
+            let started_at = Instant::now();
+            let mut text = String::new();
+            let result = async {
+                while let Some(chunk) = chunks.next().await {
+                    match chunk? {
+                        InlineCompletionChunk::Token(token) => {
+                            text.push_str(&token);
+                            this.update(cx, |this, cx| {
+                                this.completion = Some(CurrentCompletion {
+                                    position,
+                                    text: text.clone(),
+                                });
+                                this.buffer_id = Some(buffer.entity_id());
+                                cx.notify();
+                            })?;
+                        }
+                        InlineCompletionChunk::Done => break,
+                    }
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            local_model.update(cx, |local_model, cx| local_model.record_request(&result, cx))?;
+            this.update(cx, |this, cx| {
+                this.request_stats.record(started_at, &result);
+                if result.is_err() {
+                    this.completion = None;
+                }
+                this.pending_refresh = None;
+                cx.notify();
+            })?;
+
+            result
+        }));
+        cx.notify();
+    }
+
+    fn complete_streaming(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<BoxStream<'static, Result<InlineCompletionChunk>>> {
+        let settings = all_language_settings(None, cx).edit_predictions.ollama.clone();
+        let http_client = self.local_model.read(cx).http_client();
+        let snapshot = buffer.read(cx).snapshot();
+        let cursor_offset = cursor_position.to_offset(&snapshot);
+        let prefix_start =
+            snapshot.clip_offset(cursor_offset.saturating_sub(PREFIX_BYTES), Bias::Left);
+        let suffix_end = snapshot.clip_offset(
+            (cursor_offset + SUFFIX_BYTES).min(snapshot.len()),
+            Bias::Right,
+        );
+        let request = GenerateRequest {
+            model: settings.model,
+            prompt: snapshot
+                .text_for_range(prefix_start..cursor_offset)
+                .collect(),
+            suffix: Some(snapshot.text_for_range(cursor_offset..suffix_end).collect()),
+            stream: true,
+            keep_alive: KeepAlive::default(),
+            options: Some(ChatOptions {
+                num_predict: Some(MAX_PREDICTED_TOKENS),
+                stop: Some(vec!["\n\n".into()]),
+                temperature: Some(0.2),
+                ..Default::default()
+            }),
+        };
+
+        let responses = async move {
+            ollama::stream_generate(http_client.as_ref(), &settings.api_url, request).await
+        };
+        Some(
+            stream::once(responses)
+                .try_flatten()
+                .map_ok(|response| {
+                    let token = (!response.response.is_empty())
+                        .then(|| anyhow::Ok(InlineCompletionChunk::Token(response.response)));
+                    let done = response
+                        .done
+                        .then(|| anyhow::Ok(InlineCompletionChunk::Done));
+                    stream::iter(token.into_iter().chain(done))
+                })
+                .try_flatten()
+                .boxed(),
+        )
+    }
+
+    fn cycle(
+        &mut self,
+        _buffer: Entity<Buffer>,
+        _cursor_position: Anchor,
+        _direction: Direction,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, _cx: &mut Context<Self>) {
+        self.completion = None;
+        self.pending_refresh = None;
+    }
+
+    fn discard(&mut self, _cx: &mut Context<Self>) {
+        self.completion = None;
+        self.pending_refresh = None;
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<InlineCompletion> {
+        if Some(buffer.entity_id()) != self.buffer_id {
+            return None;
+        }
+        let completion = self.completion.as_ref()?;
+        let buffer = buffer.read(cx);
+        if !completion.position.is_valid(buffer) {
+            return None;
+        }
+
+        let completion_offset = completion.position.to_offset(buffer);
+        let cursor_offset = cursor_position.to_offset(buffer);
+        if cursor_offset < completion_offset {
+            return None;
+        }
+        let typed = buffer
+            .text_for_range(completion_offset..cursor_offset)
+            .collect::<String>();
+        let remaining = completion.text.strip_prefix(typed.as_str())?;
+        if remaining.trim().is_empty() {
+            return None;
+        }
+
+        let position = cursor_position.bias_right(buffer);
+        Some(InlineCompletion {
+            id: None,
+            edits: vec![(position..position, remaining.into())],
+            edit_preview: None,
+        })
+    }
+}
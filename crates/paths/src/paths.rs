@@ -356,6 +356,12 @@ pub fn supermaven_dir() -> &'static PathBuf {
     SUPERMAVEN_DIR.get_or_init(|| data_dir().join("supermaven"))
 }
 
+/// Returns the path to the Codeium directory.
+pub fn codeium_dir() -> &'static PathBuf {
+    static CODEIUM_DIR: OnceLock<PathBuf> = OnceLock::new();
+    CODEIUM_DIR.get_or_init(|| data_dir().join("codeium"))
+}
+
 /// Returns the path to the default Prettier directory.
 pub fn default_prettier_dir() -> &'static PathBuf {
     static DEFAULT_PRETTIER_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -1162,19 +1162,30 @@ impl ProjectSearchView {
     }
 
     fn select_match(&mut self, direction: Direction, window: &mut Window, cx: &mut Context<Self>) {
+        self.select_match_with_count(direction, 1, window, cx)
+    }
+
+    /// Selects the match `count` matches away from the active one in the results editor.
+    pub fn select_match_with_count(
+        &mut self,
+        direction: Direction,
+        count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if let Some(index) = self.active_match_index {
             let match_ranges = self.entity.read(cx).match_ranges.clone();
 
             if !EditorSettings::get_global(cx).search_wrap
-                && ((direction == Direction::Next && index + 1 >= match_ranges.len())
-                    || (direction == Direction::Prev && index == 0))
+                && ((direction == Direction::Next && index + count >= match_ranges.len())
+                    || (direction == Direction::Prev && index < count))
             {
                 crate::show_no_more_matches(window, cx);
                 return;
             }
 
             let new_index = self.results_editor.update(cx, |editor, cx| {
-                editor.match_index_for_direction(&match_ranges, index, direction, 1, window, cx)
+                editor.match_index_for_direction(&match_ranges, index, direction, count, window, cx)
             });
 
             let range_to_select = match_ranges[new_index].clone();
@@ -135,3 +135,12 @@ pub fn update_settings_file<T: Settings>(
 ) {
     SettingsStore::global(cx).update_settings_file::<T>(fs, update);
 }
+
+pub fn update_local_settings_file<T: Settings>(
+    fs: Arc<dyn Fs>,
+    settings_path: PathBuf,
+    cx: &App,
+    update: impl 'static + Send + FnOnce(&mut T::FileContent, &App),
+) {
+    SettingsStore::global(cx).update_local_settings_file::<T>(fs, settings_path, update);
+}
@@ -465,6 +465,41 @@ impl SettingsStore {
             .ok();
     }
 
+    /// Updates the value of a setting in a project's local settings file at `settings_path`,
+    /// creating the file if it doesn't exist yet.
+    pub fn update_local_settings_file<T: Settings>(
+        &self,
+        fs: Arc<dyn Fs>,
+        settings_path: PathBuf,
+        update: impl 'static + Send + FnOnce(&mut T::FileContent, &App),
+    ) {
+        self.setting_file_updates_tx
+            .unbounded_send(Box::new(move |cx: AsyncApp| {
+                async move {
+                    let old_text = if fs.is_file(&settings_path).await {
+                        fs.load(&settings_path).await?
+                    } else {
+                        crate::initial_project_settings_content().to_string()
+                    };
+                    let new_text = cx.read_global(|store: &SettingsStore, cx| {
+                        store.new_text_for_update::<T>(old_text, |content| update(content, cx))
+                    })?;
+                    if let Some(settings_dir) = settings_path.parent() {
+                        fs.create_dir(settings_dir).await?;
+                    }
+                    fs.atomic_write(settings_path.clone(), new_text)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to write settings to file {:?}", settings_path)
+                        })?;
+
+                    anyhow::Ok(())
+                }
+                .boxed_local()
+            }))
+            .ok();
+    }
+
     pub fn import_vscode_settings(&self, fs: Arc<dyn Fs>, vscode_settings: VsCodeSettings) {
         self.setting_file_updates_tx
             .unbounded_send(Box::new(move |cx: AsyncApp| {
@@ -1315,6 +1350,21 @@ fn update_value_in_json_text<'a>(
     }
 }
 
+/// Extends the range of a `"key": value` pair over the comma that separates it from its
+/// neighbors, so that removing it leaves valid JSON.
+fn pair_removal_range(text: &str, pair_range: Range<usize>) -> Range<usize> {
+    if let Some(rest) = text[pair_range.end..].trim_start().strip_prefix(',') {
+        let next_pair_start = text.len() - rest.trim_start().len();
+        return pair_range.start..next_pair_start;
+    }
+    let before = text[..pair_range.start].trim_end();
+    if before.ends_with(',') {
+        before.len() - 1..pair_range.end
+    } else {
+        before.len()..pair_range.end
+    }
+}
+
 fn replace_value_in_json_text(
     text: &str,
     key_path: &[&str],
@@ -1340,6 +1390,7 @@ fn replace_value_in_json_text(
     let mut depth = 0;
     let mut last_value_range = 0..0;
     let mut first_key_start = None;
+    let mut existing_key_start = 0;
     let mut existing_value_range = 0..text.len();
     let mut matches = cursor.matches(&PAIR_QUERY, syntax_tree.root_node(), text.as_bytes());
     while let Some(mat) = matches.next() {
@@ -1372,6 +1423,7 @@ fn replace_value_in_json_text(
             .unwrap_or(false);
 
         if found_key {
+            existing_key_start = key_range.start;
             existing_value_range = value_range;
             // Reset last value range when increasing in depth
             last_value_range = existing_value_range.start..existing_value_range.start;
@@ -1387,6 +1439,11 @@ fn replace_value_in_json_text(
 
     // We found the exact key we want, insert the new value
     if depth == key_path.len() {
+        // Setting a key to null is the same as leaving it out, so remove it instead.
+        if depth > 0 && new_value.is_null() {
+            let pair_range = existing_key_start..existing_value_range.end;
+            return (pair_removal_range(text, pair_range), String::new());
+        }
         let new_val = to_pretty_json(&new_value, tab_size, tab_size * depth);
         (existing_value_range, new_val)
     } else {
@@ -1712,6 +1769,42 @@ mod tests {
             cx,
         );
 
+        // removed keys
+        check_settings_update::<UserSettings>(
+            &mut store,
+            r#"{
+                "user": { "age": 36, "name": "Max", "staff": true }
+            }"#
+            .unindent(),
+            |settings| {
+                settings.age = None;
+                settings.staff = None;
+            },
+            r#"{
+                "user": { "name": "Max" }
+            }"#
+            .unindent(),
+            cx,
+        );
+        check_settings_update::<UserSettings>(
+            &mut store,
+            r#"{
+                "user": {
+                    "age": 36,
+                    "name": "Max"
+                }
+            }"#
+            .unindent(),
+            |settings| settings.age = None,
+            r#"{
+                "user": {
+                    "name": "Max"
+                }
+            }"#
+            .unindent(),
+            cx,
+        );
+
         // single-line formatting, other keys
         check_settings_update::<MultiKeySettings>(
             &mut store,
@@ -21,29 +21,49 @@ use smol::{
     io::AsyncWriteExt,
     process::{Child, ChildStdin, ChildStdout},
 };
-use std::{path::PathBuf, process::Stdio, sync::Arc};
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    mem,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use ui::prelude::*;
 use util::ResultExt;
 
-actions!(supermaven, [SignOut]);
+actions!(supermaven, [SignOut, RestartAgent]);
+
+/// How many lines of agent output and completion requests are kept for the diagnostics view.
+const MAX_LOG_LINES: usize = 200;
+const MAX_REQUEST_SUMMARIES: usize = 20;
 
 pub fn init(client: Arc<Client>, cx: &mut App) {
     let supermaven = cx.new(|_| Supermaven::Starting);
     Supermaven::set_global(supermaven.clone(), cx);
 
-    let mut provider = all_language_settings(None, cx).edit_predictions.provider;
-    if provider == language::language_settings::EditPredictionProvider::Supermaven {
+    let is_used = |cx: &App| {
+        all_language_settings(None, cx).uses_edit_prediction_provider(
+            language::language_settings::EditPredictionProvider::Supermaven,
+        )
+    };
+    let mut used = is_used(cx);
+    if used {
         supermaven.update(cx, |supermaven, cx| supermaven.start(client.clone(), cx));
     }
 
-    cx.observe_global::<SettingsStore>(move |cx| {
-        let new_provider = all_language_settings(None, cx).edit_predictions.provider;
-        if new_provider != provider {
-            provider = new_provider;
-            if provider == language::language_settings::EditPredictionProvider::Supermaven {
-                supermaven.update(cx, |supermaven, cx| supermaven.start(client.clone(), cx));
-            } else {
-                supermaven.update(cx, |supermaven, _cx| supermaven.stop());
+    cx.observe_global::<SettingsStore>({
+        let client = client.clone();
+        move |cx| {
+            let now_used = is_used(cx);
+            if now_used != used {
+                used = now_used;
+                if used {
+                    supermaven.update(cx, |supermaven, cx| supermaven.start(client.clone(), cx));
+                } else {
+                    supermaven.update(cx, |supermaven, _cx| supermaven.stop());
+                }
             }
         }
     })
@@ -54,6 +74,12 @@ pub fn init(client: Arc<Client>, cx: &mut App) {
             supermaven.update(cx, |supermaven, _cx| supermaven.sign_out());
         }
     });
+
+    cx.on_action(move |_: &RestartAgent, cx| {
+        if let Some(supermaven) = Supermaven::global(cx) {
+            supermaven.update(cx, |supermaven, cx| supermaven.restart(client.clone(), cx));
+        }
+    });
 }
 
 pub enum Supermaven {
@@ -89,17 +115,26 @@ impl Supermaven {
         if let Self::Starting = self {
             cx.spawn(async move |this, cx| {
                 let binary_path =
-                    supermaven_api::get_supermaven_agent_path(client.http_client()).await?;
+                    supermaven_api::get_supermaven_agent_path(client.http_client()).await;
 
                 this.update(cx, |this, cx| {
-                    if let Self::Starting = this {
-                        *this =
-                            Self::Spawned(SupermavenAgent::new(binary_path, client.clone(), cx)?);
+                    if !matches!(this, Self::Starting) {
+                        return;
                     }
-                    anyhow::Ok(())
+                    *this = match binary_path {
+                        Ok(binary_path) => {
+                            match SupermavenAgent::new(binary_path, client.clone(), cx) {
+                                Ok(agent) => Self::Spawned(agent),
+                                Err(error) => Self::Error { error },
+                            }
+                        }
+                        Err(error) => Self::FailedDownload { error },
+                    };
+                    cx.notify();
                 })
+                .ok();
             })
-            .detach_and_log_err(cx)
+            .detach()
         }
     }
 
@@ -107,6 +142,82 @@ impl Supermaven {
         *self = Self::Starting;
     }
 
+    /// Kills the agent, if it's running, and downloads and starts it again.
+    pub fn restart(&mut self, client: Arc<Client>, cx: &mut Context<Self>) {
+        self.stop();
+        self.start(client, cx);
+        cx.notify();
+    }
+
+    /// A plain-text report of the agent's state, recent output, and recent requests, for
+    /// figuring out why completions aren't showing up.
+    pub fn diagnostics(&self) -> String {
+        let mut report = String::from("Supermaven Diagnostics\n\n");
+        let agent = match self {
+            Self::Starting => {
+                report.push_str("Status: starting\n");
+                return report;
+            }
+            Self::FailedDownload { error } => {
+                writeln!(report, "Status: failed to download the agent\n\n{error:?}").ok();
+                return report;
+            }
+            Self::Error { error } => {
+                writeln!(report, "Status: failed to start the agent\n\n{error:?}").ok();
+                return report;
+            }
+            Self::Spawned(agent) => agent,
+        };
+
+        report.push_str("Status: running\n");
+        writeln!(report, "Agent: {}", agent.binary_path.display()).ok();
+        if let Some(version) = agent_version(&agent.binary_path) {
+            writeln!(report, "Agent version: {version}").ok();
+        }
+        let account_status = match &agent.account_status {
+            AccountStatus::Unknown => "unknown".to_string(),
+            AccountStatus::NeedsActivation { activate_url } => {
+                format!("needs activation at {activate_url}")
+            }
+            AccountStatus::Ready => "ready".to_string(),
+        };
+        writeln!(report, "Account: {account_status}").ok();
+        if let Some(service_tier) = &agent.service_tier {
+            writeln!(report, "Service tier: {service_tier:?}").ok();
+        }
+
+        writeln!(report, "\nRecent requests (newest last):").ok();
+        if agent.requests.is_empty() {
+            report.push_str("none\n");
+        }
+        for request in &agent.requests {
+            let response = match request.first_response {
+                Some(latency) => format!(
+                    "{} characters, first response after {}ms{}",
+                    request.completion_len,
+                    latency.as_millis(),
+                    if request.finished { "" } else { ", unfinished" }
+                ),
+                None => format!("no response after {}s", request.requested_at.elapsed().as_secs()),
+            };
+            writeln!(
+                report,
+                "#{} {} at offset {}: {response}",
+                request.state_id.0, request.path, request.offset
+            )
+            .ok();
+        }
+
+        writeln!(report, "\nAgent output (last {MAX_LOG_LINES} lines):").ok();
+        if agent.log.is_empty() {
+            report.push_str("none\n");
+        }
+        for line in &agent.log {
+            writeln!(report, "{line}").ok();
+        }
+        report
+    }
+
     pub fn is_enabled(&self) -> bool {
         matches!(self, Self::Spawned { .. })
     }
@@ -131,6 +242,19 @@ impl Supermaven {
             let state_id = agent.next_state_id;
             agent.next_state_id.0 += 1;
 
+            if agent.requests.len() == MAX_REQUEST_SUMMARIES {
+                agent.requests.pop_front();
+            }
+            agent.requests.push_back(RequestSummary {
+                state_id,
+                path: path.clone(),
+                offset,
+                requested_at: Instant::now(),
+                first_response: None,
+                completion_len: 0,
+                finished: false,
+            });
+
             let (updates_tx, mut updates_rx) = watch::channel();
             postage::stream::Stream::try_recv(&mut updates_rx).unwrap();
 
@@ -248,13 +372,32 @@ fn find_relevant_completion<'a>(
     best_completion
 }
 
+/// The agent binary is saved as `sm-agent-{version}`.
+fn agent_version(binary_path: &Path) -> Option<&str> {
+    binary_path.file_stem()?.to_str()?.strip_prefix("sm-agent-")
+}
+
+struct RequestSummary {
+    state_id: SupermavenCompletionStateId,
+    path: String,
+    offset: usize,
+    requested_at: Instant,
+    first_response: Option<Duration>,
+    completion_len: usize,
+    finished: bool,
+}
+
 pub struct SupermavenAgent {
     _process: Child,
+    binary_path: PathBuf,
+    log: VecDeque<String>,
+    requests: VecDeque<RequestSummary>,
     next_state_id: SupermavenCompletionStateId,
     states: BTreeMap<SupermavenCompletionStateId, SupermavenCompletionState>,
     outgoing_tx: mpsc::UnboundedSender<OutboundMessage>,
     _handle_outgoing_messages: Task<Result<()>>,
     _handle_incoming_messages: Task<Result<()>>,
+    _handle_stderr: Task<Result<()>>,
     pub account_status: AccountStatus,
     service_tier: Option<ServiceTier>,
     #[allow(dead_code)]
@@ -284,6 +427,10 @@ impl SupermavenAgent {
             .stdout
             .take()
             .context("failed to get stdout for process")?;
+        let stderr = process
+            .stderr
+            .take()
+            .context("failed to get stderr for process")?;
 
         let (outgoing_tx, outgoing_rx) = mpsc::unbounded();
 
@@ -314,6 +461,9 @@ impl SupermavenAgent {
 
         Ok(Self {
             _process: process,
+            binary_path,
+            log: VecDeque::new(),
+            requests: VecDeque::new(),
             next_state_id: SupermavenCompletionStateId::default(),
             states: BTreeMap::default(),
             outgoing_tx,
@@ -323,6 +473,14 @@ impl SupermavenAgent {
             _handle_incoming_messages: cx.spawn(async move |this, cx| {
                 Self::handle_incoming_messages(this, stdout, cx).await
             }),
+            _handle_stderr: cx.spawn(async move |this, cx| {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Some(line) = lines.next().await {
+                    let line = line.context("failed to read line from stderr")?;
+                    Self::append_log(&this, line, cx)?;
+                }
+                anyhow::Ok(())
+            }),
             account_status: AccountStatus::Unknown,
             service_tier: None,
             client,
@@ -355,6 +513,7 @@ impl SupermavenAgent {
                 continue;
             };
             let Some(line) = line.strip_prefix(MESSAGE_PREFIX) else {
+                Self::append_log(&this, line, cx)?;
                 continue;
             };
             let Some(message) = serde_json::from_str::<SupermavenMessage>(line)
@@ -364,9 +523,15 @@ impl SupermavenAgent {
                 continue;
             };
 
-            this.update(cx, |this, _cx| {
+            this.update(cx, |this, cx| {
                 if let Supermaven::Spawned(this) = this {
+                    let account_status = mem::discriminant(&this.account_status);
                     this.handle_message(message);
+                    // Lets the status button leave the activation state as soon as the
+                    // account is activated in the browser.
+                    if mem::discriminant(&this.account_status) != account_status {
+                        cx.notify();
+                    }
                 }
                 Task::ready(anyhow::Ok(()))
             })?
@@ -376,6 +541,17 @@ impl SupermavenAgent {
         Ok(())
     }
 
+    fn append_log(this: &WeakEntity<Supermaven>, line: String, cx: &mut AsyncApp) -> Result<()> {
+        this.update(cx, |this, _| {
+            if let Supermaven::Spawned(agent) = this {
+                if agent.log.len() == MAX_LOG_LINES {
+                    agent.log.pop_front();
+                }
+                agent.log.push_back(line);
+            }
+        })
+    }
+
     fn handle_message(&mut self, message: SupermavenMessage) {
         match message {
             SupermavenMessage::ActivationRequest(request) => {
@@ -395,6 +571,23 @@ impl SupermavenAgent {
             }
             SupermavenMessage::Response(response) => {
                 let state_id = SupermavenCompletionStateId(response.state_id.parse().unwrap());
+                if let Some(request) = self
+                    .requests
+                    .iter_mut()
+                    .rev()
+                    .find(|request| request.state_id == state_id)
+                {
+                    request
+                        .first_response
+                        .get_or_insert_with(|| request.requested_at.elapsed());
+                    for item in &response.items {
+                        match item {
+                            ResponseItem::Text { text } => request.completion_len += text.len(),
+                            ResponseItem::End => request.finished = true,
+                            _ => {}
+                        }
+                    }
+                }
                 if let Some(state) = self.states.get_mut(&state_id) {
                     for item in &response.items {
                         match item {
@@ -2,13 +2,15 @@ use crate::{Supermaven, SupermavenCompletionStateId};
 use anyhow::Result;
 use futures::StreamExt as _;
 use gpui::{App, Context, Entity, EntityId, Task};
-use inline_completion::{Direction, EditPredictionProvider, InlineCompletion};
+use inline_completion::{
+    Direction, EditPredictionProvider, EditPredictionRequestStats, InlineCompletion,
+};
 use language::{Anchor, Buffer, BufferSnapshot};
 use project::Project;
 use std::{
     ops::{AddAssign, Range},
     path::Path,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use text::{ToOffset, ToPoint};
 use unicode_segmentation::UnicodeSegmentation;
@@ -21,6 +23,7 @@ pub struct SupermavenCompletionProvider {
     completion_id: Option<SupermavenCompletionStateId>,
     file_extension: Option<String>,
     pending_refresh: Option<Task<Result<()>>>,
+    request_stats: EditPredictionRequestStats,
 }
 
 impl SupermavenCompletionProvider {
@@ -31,6 +34,7 @@ impl SupermavenCompletionProvider {
             completion_id: None,
             file_extension: None,
             pending_refresh: None,
+            request_stats: EditPredictionRequestStats::default(),
         }
     }
 }
@@ -119,6 +123,10 @@ impl EditPredictionProvider for SupermavenCompletionProvider {
         self.pending_refresh.is_some()
     }
 
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        self.request_stats.last_latency()
+    }
+
     fn refresh(
         &mut self,
         _project: Option<Entity<Project>>,
@@ -138,8 +146,13 @@ impl EditPredictionProvider for SupermavenCompletionProvider {
                 cx.background_executor().timer(DEBOUNCE_TIMEOUT).await;
             }
 
+            let mut started_at = Some(Instant::now());
             while let Some(()) = completion.updates.next().await {
+                let first_update_started_at = started_at.take();
                 this.update(cx, |this, cx| {
+                    if let Some(started_at) = first_update_started_at {
+                        this.request_stats.record_success(started_at);
+                    }
                     this.completion_id = Some(completion.id);
                     this.buffer_id = Some(buffer_handle.entity_id());
                     this.file_extension = buffer_handle.read(cx).file().and_then(|file| {
@@ -85,7 +85,10 @@ impl Vim {
 mod test {
     use indoc::indoc;
 
-    use crate::{state::Mode, test::NeovimBackedTestContext};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_change_list_insert(cx: &mut gpui::TestAppContext) {
@@ -140,7 +143,7 @@ mod test {
             },
             Mode::Normal,
         );
-        cx.simulate_shared_keystrokes("shift-g i 4 4 escape").await;
+        cx.simulate_shared_keystrokes("shift-g l i 4 4 escape").await;
         cx.simulate_shared_keystrokes("g ;").await;
         cx.assert_state(
             indoc! {
@@ -196,6 +199,34 @@ mod test {
         three foˇur"});
     }
 
+    #[gpui::test]
+    async fn test_gi_after_block_insert(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+            ˇone
+            two
+            three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("ctrl-v j j shift-i x escape shift-g $ g i");
+        cx.assert_state(
+            indoc! {"
+            xˇone
+            xˇtwo
+            xˇthree"},
+            Mode::Insert,
+        );
+        cx.simulate_keystrokes("y");
+        cx.assert_state(
+            indoc! {"
+            xyˇone
+            xyˇtwo
+            xyˇthree"},
+            Mode::Insert,
+        );
+    }
+
     #[gpui::test]
     async fn test_dot_mark(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -9,7 +9,7 @@ use editor::{
 };
 use gpui::{Action, App, AppContext as _, Context, Global, Window, actions, impl_internal_actions};
 use itertools::Itertools;
-use language::Point;
+use language::{Point, language_settings::SoftWrap};
 use multi_buffer::MultiBufferRow;
 use regex::Regex;
 use schemars::JsonSchema;
@@ -18,7 +18,9 @@ use serde::Deserialize;
 use std::{
     io::Write,
     iter::Peekable,
+    num::NonZeroU32,
     ops::{Deref, Range},
+    path::{Path, PathBuf},
     process::Stdio,
     str::Chars,
     sync::OnceLock,
@@ -27,7 +29,10 @@ use std::{
 use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
 use ui::ActiveTheme;
 use util::ResultExt;
-use workspace::{SaveIntent, notifications::NotifyResultExt};
+use workspace::{
+    SaveIntent, Toast, Workspace,
+    notifications::{NotificationId, NotifyResultExt},
+};
 use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
@@ -35,7 +40,7 @@ use crate::{
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
-        search::{FindCommand, ReplaceCommand, Replacement},
+        search::{FindCommand, RepeatReplaceCommand, ReplaceCommand, Replacement},
     },
     object::Object,
     state::{Mark, Mode},
@@ -52,6 +57,18 @@ pub struct YankCommand {
     range: CommandRange,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct CopyLines {
+    range: CommandRange,
+    destination: Position,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveLines {
+    range: CommandRange,
+    destination: Position,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct WithRange {
     restore_selection: bool,
@@ -70,6 +87,10 @@ pub enum VimOption {
     Wrap(bool),
     Number(bool),
     RelativeNumber(bool),
+    TabStop(u32),
+    IgnoreCase(bool),
+    ScrollOff(u32),
+    Unknown(String),
 }
 
 impl VimOption {
@@ -78,14 +99,17 @@ impl VimOption {
         let mut options = query.split(" ").collect::<Vec<_>>();
         let prefix = options.pop().unwrap_or_default();
         for option in options {
-            if let Some(opt) = Self::from(option) {
-                prefix_of_options.push(opt)
-            } else {
-                return vec![];
-            }
+            prefix_of_options
+                .push(Self::from(option).unwrap_or_else(|| Self::Unknown(option.to_string())));
         }
 
-        Self::possibilities(&prefix)
+        let mut possibilities = Self::possibilities(&prefix).collect::<Vec<_>>();
+        if possibilities.is_empty() && !prefix.is_empty() {
+            possibilities.push(Self::Unknown(prefix.to_string()));
+        }
+
+        possibilities
+            .into_iter()
             .map(|possible| {
                 let mut options = prefix_of_options.clone();
                 options.push(possible);
@@ -112,13 +136,33 @@ impl VimOption {
             (None, VimOption::RelativeNumber(false)),
             (Some("rnu"), VimOption::RelativeNumber(true)),
             (Some("nornu"), VimOption::RelativeNumber(false)),
+            (None, VimOption::IgnoreCase(true)),
+            (None, VimOption::IgnoreCase(false)),
+            (Some("ic"), VimOption::IgnoreCase(true)),
+            (Some("noic"), VimOption::IgnoreCase(false)),
         ]
         .into_iter()
-        .filter(move |(prefix, option)| prefix.unwrap_or(option.to_string()).starts_with(query))
+        .filter(move |(prefix, option)| match prefix {
+            Some(prefix) => prefix.starts_with(query),
+            None => option.to_string().starts_with(query),
+        })
         .map(|(_, option)| option)
+        .chain(
+            Self::from(query)
+                .filter(|option| matches!(option, Self::TabStop(_) | Self::ScrollOff(_))),
+        )
     }
 
     fn from(option: &str) -> Option<Self> {
+        if let Some((name, value)) = option.split_once('=') {
+            let value = value.parse().ok()?;
+            return match name {
+                "tabstop" | "ts" => Some(Self::TabStop(value)),
+                "scrolloff" | "so" => Some(Self::ScrollOff(value)),
+                _ => None,
+            };
+        }
+
         match option {
             "wrap" => Some(Self::Wrap(true)),
             "nowrap" => Some(Self::Wrap(false)),
@@ -133,18 +177,28 @@ impl VimOption {
             "norelativenumber" => Some(Self::RelativeNumber(false)),
             "nornu" => Some(Self::RelativeNumber(false)),
 
+            "ignorecase" => Some(Self::IgnoreCase(true)),
+            "ic" => Some(Self::IgnoreCase(true)),
+            "noignorecase" => Some(Self::IgnoreCase(false)),
+            "noic" => Some(Self::IgnoreCase(false)),
+
             _ => None,
         }
     }
 
-    fn to_string(&self) -> &'static str {
+    fn to_string(&self) -> String {
         match self {
-            VimOption::Wrap(true) => "wrap",
-            VimOption::Wrap(false) => "nowrap",
-            VimOption::Number(true) => "number",
-            VimOption::Number(false) => "nonumber",
-            VimOption::RelativeNumber(true) => "relativenumber",
-            VimOption::RelativeNumber(false) => "norelativenumber",
+            VimOption::Wrap(true) => "wrap".into(),
+            VimOption::Wrap(false) => "nowrap".into(),
+            VimOption::Number(true) => "number".into(),
+            VimOption::Number(false) => "nonumber".into(),
+            VimOption::RelativeNumber(true) => "relativenumber".into(),
+            VimOption::RelativeNumber(false) => "norelativenumber".into(),
+            VimOption::TabStop(size) => format!("tabstop={size}"),
+            VimOption::IgnoreCase(true) => "ignorecase".into(),
+            VimOption::IgnoreCase(false) => "noignorecase".into(),
+            VimOption::ScrollOff(lines) => format!("scrolloff={lines}"),
+            VimOption::Unknown(name) => name.clone(),
         }
     }
 }
@@ -154,20 +208,45 @@ pub struct VimSet {
     options: Vec<VimOption>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeleteMarks {
+    Marks(String),
+    AllLocal,
+}
+
+/// `:marks {names}`, listing only the marks whose names appear in `names`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowMarks {
+    pub(crate) names: String,
+}
+
+/// `:cd {path}`, which reopens the window with `path` as its root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeDirectory {
+    path: String,
+}
+
+struct WorkingDirectoryToast;
+
 #[derive(Debug)]
 struct WrappedAction(Box<dyn Action>);
 
-actions!(vim, [VisualCommand, CountCommand, ShellCommand]);
+actions!(vim, [VisualCommand, CountCommand, ShellCommand, PrintWorkingDirectory]);
 impl_internal_actions!(
     vim,
     [
         GoToLine,
         YankCommand,
+        CopyLines,
+        MoveLines,
         WithRange,
         WithCount,
         OnMatchingLines,
         ShellExec,
         VimSet,
+        DeleteMarks,
+        ShowMarks,
+        ChangeDirectory,
     ]
 );
 
@@ -194,23 +273,129 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     // Vim::action(editor, cx, |vim, action: &StartOfLine, window, cx| {
     Vim::action(editor, cx, |vim, action: &VimSet, window, cx| {
         for option in action.options.iter() {
-            vim.update_editor(window, cx, |_, editor, _, cx| match option {
-                VimOption::Wrap(true) => {
-                    editor
-                        .set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-                }
-                VimOption::Wrap(false) => {
-                    editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            let result = match option {
+                VimOption::TabStop(size) => vim.set_tab_stop(*size, window, cx),
+                VimOption::IgnoreCase(ignore_case) => {
+                    vim.ignore_case = *ignore_case;
+                    Ok(())
                 }
-                VimOption::Number(enabled) => {
-                    editor.set_show_line_numbers(*enabled, cx);
-                }
-                VimOption::RelativeNumber(enabled) => {
-                    editor.set_relative_line_number(Some(*enabled), cx);
+                VimOption::Unknown(name) => Err(anyhow!("E518: Unknown option: {name}")),
+                _ => {
+                    vim.update_editor(window, cx, |_, editor, _, cx| match option {
+                        VimOption::Wrap(true) => {
+                            editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
+                        }
+                        VimOption::Wrap(false) => {
+                            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+                        }
+                        VimOption::Number(enabled) => {
+                            editor.set_show_line_numbers(*enabled, cx);
+                        }
+                        VimOption::RelativeNumber(enabled) => {
+                            editor.set_relative_line_number(Some(*enabled), cx);
+                        }
+                        VimOption::ScrollOff(lines) => {
+                            editor.set_vertical_scroll_margin(*lines as usize, cx);
+                        }
+                        VimOption::TabStop(_)
+                        | VimOption::IgnoreCase(_)
+                        | VimOption::Unknown(_) => {}
+                    });
+                    Ok(())
                 }
-            });
+            };
+            if let e @ Err(_) = result {
+                let Some(workspace) = vim.workspace(window) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    e.notify_err(workspace, cx);
+                });
+                return;
+            }
         }
     });
+    Vim::action(editor, cx, |vim, action: &DeleteMarks, window, cx| {
+        let result = match action {
+            DeleteMarks::Marks(marks) => DeleteMarks::mark_names(marks),
+            DeleteMarks::AllLocal => Ok(('a'..='z').map(|c| c.to_string()).collect()),
+        };
+        let names = match result {
+            Ok(names) => names,
+            e @ Err(_) => {
+                let Some(workspace) = vim.workspace(window) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    e.notify_err(workspace, cx);
+                });
+                return;
+            }
+        };
+        vim.update_editor(window, cx, |vim, editor, window, cx| {
+            for name in names {
+                vim.delete_mark(name, editor.buffer(), window, cx);
+            }
+        });
+    });
+
+    Vim::action(editor, cx, |vim, _: &PrintWorkingDirectory, window, cx| {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let Some(directory) = working_directory(workspace, cx)
+                .ok_or_else(|| anyhow!("E: This window has no working directory"))
+                .notify_err(workspace, cx)
+            else {
+                return;
+            };
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<WorkingDirectoryToast>(),
+                    directory.to_string_lossy().into_owned(),
+                ),
+                cx,
+            );
+        });
+    });
+
+    Vim::action(editor, cx, |vim, action: &ChangeDirectory, window, cx| {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        let current = working_directory(workspace.read(cx), cx);
+        let previous = Vim::globals(cx).previous_directory.clone();
+        let target = if workspace.read(cx).project().read(cx).is_local() {
+            action.target(current.as_deref(), previous.as_deref())
+        } else {
+            Err(anyhow!("E: :cd is only supported in local projects"))
+        };
+        let fs = workspace.read(cx).app_state().fs.clone();
+        let workspace = workspace.downgrade();
+        cx.spawn_in(window, async move |_, cx| {
+            let result = async {
+                let target = target?;
+                if !fs.is_dir(&target).await {
+                    anyhow::bail!("E344: Can't find directory \"{}\"", target.display());
+                }
+                cx.update(|_, cx| Vim::globals(cx).previous_directory = current)?;
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_workspace_for_paths(true, vec![target], window, cx)
+                    })?
+                    .await
+            }
+            .await;
+            if let e @ Err(_) = result {
+                workspace
+                    .update(cx, |workspace, cx| e.notify_err(workspace, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    });
+
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
         let Some(workspace) = vim.workspace(window) else {
             return;
@@ -254,6 +439,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             let target = snapshot
                 .buffer_snapshot
                 .clip_point(Point::new(buffer_row.0, current.head().column), Bias::Left);
+            editor.create_nav_history_entry(cx);
             editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                 s.select_ranges([target..target]);
             });
@@ -271,6 +457,14 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         }
     });
 
+    Vim::action(editor, cx, |vim, action: &CopyLines, window, cx| {
+        vim.transfer_lines(&action.range, &action.destination, false, window, cx)
+    });
+
+    Vim::action(editor, cx, |vim, action: &MoveLines, window, cx| {
+        vim.transfer_lines(&action.range, &action.destination, true, window, cx)
+    });
+
     Vim::action(editor, cx, |vim, action: &YankCommand, window, cx| {
         vim.update_editor(window, cx, |vim, editor, window, cx| {
             let snapshot = editor.snapshot(window, cx);
@@ -578,21 +772,33 @@ impl Position {
         cx: &mut App,
     ) -> Result<MultiBufferRow> {
         let snapshot = editor.snapshot(window, cx);
-        let target = match self {
+        let target = self.signed_row(vim, editor, window, cx)?.max(0) as u32;
+        Ok(MultiBufferRow(target).min(snapshot.buffer_snapshot.max_row()))
+    }
+
+    /// The row this position refers to before it is clamped to the buffer,
+    /// so that line 0 (row -1) can be told apart from line 1.
+    fn signed_row(
+        &self,
+        vim: &Vim,
+        editor: &mut Editor,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<i64> {
+        let snapshot = editor.snapshot(window, cx);
+        let (row, offset) = match self {
+            Position::Line { row: 0, offset } => (-1, offset),
             Position::Line { row, offset } => {
                 if let Some(anchor) = editor.active_excerpt(cx).and_then(|(_, buffer, _)| {
                     editor.buffer().read(cx).buffer_point_to_anchor(
                         &buffer,
-                        Point::new(row.saturating_sub(1), 0),
+                        Point::new(row - 1, 0),
                         cx,
                     )
                 }) {
-                    anchor
-                        .to_point(&snapshot.buffer_snapshot)
-                        .row
-                        .saturating_add_signed(*offset)
+                    (anchor.to_point(&snapshot.buffer_snapshot).row as i64, offset)
                 } else {
-                    row.saturating_add_signed(offset.saturating_sub(1))
+                    (*row as i64 - 1, offset)
                 }
             }
             Position::Mark { name, offset } => {
@@ -604,25 +810,21 @@ impl Position {
                 let Some(mark) = anchors.last() else {
                     return Err(anyhow!("mark {} contains empty anchors", name));
                 };
-                mark.to_point(&snapshot.buffer_snapshot)
-                    .row
-                    .saturating_add_signed(*offset)
+                (mark.to_point(&snapshot.buffer_snapshot).row as i64, offset)
             }
-            Position::LastLine { offset } => snapshot
-                .buffer_snapshot
-                .max_row()
-                .0
-                .saturating_add_signed(*offset),
-            Position::CurrentLine { offset } => editor
-                .selections
-                .newest_anchor()
-                .head()
-                .to_point(&snapshot.buffer_snapshot)
-                .row
-                .saturating_add_signed(*offset),
+            Position::LastLine { offset } => (snapshot.buffer_snapshot.max_row().0 as i64, offset),
+            Position::CurrentLine { offset } => (
+                editor
+                    .selections
+                    .newest_anchor()
+                    .head()
+                    .to_point(&snapshot.buffer_snapshot)
+                    .row as i64,
+                offset,
+            ),
         };
 
-        Ok(MultiBufferRow(target).min(snapshot.buffer_snapshot.max_row()))
+        Ok(row + *offset as i64)
     }
 }
 
@@ -633,6 +835,22 @@ pub(crate) struct CommandRange {
 }
 
 impl CommandRange {
+    pub(crate) fn current_lines(count: u32) -> Self {
+        CommandRange {
+            start: Position::CurrentLine { offset: 0 },
+            end: Some(Position::CurrentLine {
+                offset: count.saturating_sub(1) as i32,
+            }),
+        }
+    }
+
+    pub(crate) fn whole_file() -> Self {
+        CommandRange {
+            start: Position::Line { row: 1, offset: 0 },
+            end: Some(Position::LastLine { offset: 0 }),
+        }
+    }
+
     fn head(&self) -> &Position {
         self.end.as_ref().unwrap_or(&self.start)
     }
@@ -889,6 +1107,7 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
         VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
         VimCommand::new(("h", "elp"), OpenDocs),
+        VimCommand::new(("pw", "d"), PrintWorkingDirectory),
     ]
 }
 
@@ -973,6 +1192,14 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
             }
         }
         return commands;
+    } else if query == "&" || query == "&&" {
+        Some(
+            RepeatReplaceCommand {
+                range: range.clone().unwrap_or(CommandRange::current_lines(1)),
+                keep_flags: query == "&&",
+            }
+            .boxed_clone(),
+        )
     } else if query.starts_with('s') {
         let mut substitute = "substitute".chars().peekable();
         let mut query = query.chars().peekable();
@@ -1017,6 +1244,19 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
         } else {
             None
         }
+    } else if query.starts_with("delm") {
+        DeleteMarks::parse(query)
+    } else if let Some(action) = ChangeDirectory::parse(query) {
+        Some(action)
+    } else if let Some(names) = query.strip_prefix("marks ") {
+        Some(
+            ShowMarks {
+                names: names.trim().to_string(),
+            }
+            .boxed_clone(),
+        )
+    } else if let Some(action) = parse_transfer_lines(query, &range) {
+        Some(action)
     } else if query.contains('!') {
         ShellExec::parse(query, range.clone())
     } else {
@@ -1381,6 +1621,7 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Vim>,
     ) {
@@ -1392,7 +1633,7 @@ impl Vim {
             let snapshot = editor.snapshot(window, cx);
             let start = editor.selections.newest_display(cx);
             let range = object
-                .range(&snapshot, start.clone(), around)
+                .range(&snapshot, start.clone(), around, times)
                 .unwrap_or(start.range());
             if range.start != start.start {
                 editor.change_selections(None, window, cx, |s| {
@@ -1608,9 +1849,227 @@ impl ShellExec {
     }
 }
 
+/// Strips a command name that may be abbreviated down to `prefix`, returning its arguments.
+fn strip_command<'a>(query: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let mut query = query.strip_prefix(prefix)?;
+    for char in suffix.chars() {
+        match query.strip_prefix(char) {
+            Some(rest) => query = rest,
+            None => break,
+        }
+    }
+    Some(query)
+}
+
+fn parse_transfer_lines(query: &str, range: &Option<CommandRange>) -> Option<Box<dyn Action>> {
+    let (arguments, is_move) = if let Some(arguments) = strip_command(query, "co", "py") {
+        (arguments, false)
+    } else if let Some(arguments) = query.strip_prefix('t') {
+        (arguments, false)
+    } else {
+        (strip_command(query, "m", "ove")?, true)
+    };
+    let mut chars = arguments.trim().chars().peekable();
+    let destination = VimCommand::parse_position(&mut chars)?;
+    if chars.next().is_some() {
+        return None;
+    }
+    let range = range.clone().unwrap_or(CommandRange {
+        start: Position::CurrentLine { offset: 0 },
+        end: None,
+    });
+    if is_move {
+        Some(MoveLines { range, destination }.boxed_clone())
+    } else {
+        Some(CopyLines { range, destination }.boxed_clone())
+    }
+}
+
+impl Vim {
+    fn transfer_lines(
+        &mut self,
+        range: &CommandRange,
+        destination: &Position,
+        is_move: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.switch_mode(Mode::Normal, false, window, cx);
+        let result = self.update_editor(window, cx, |vim, editor, window, cx| {
+            let source = range.buffer_range(vim, editor, window, cx)?;
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            // The lines go below row `after`, which is -1 for the top of the buffer.
+            let after = destination
+                .signed_row(vim, editor, window, cx)?
+                .clamp(-1, snapshot.max_row().0 as i64);
+            let (start, end) = (source.start.0 as i64, source.end.0 as i64);
+            if is_move && after >= start && after < end {
+                return Err(anyhow!("E134: Cannot move a range of lines into itself"));
+            }
+
+            let text = snapshot
+                .text_for_range(
+                    Point::new(source.start.0, 0)
+                        ..Point::new(source.end.0, snapshot.line_len(source.end)),
+                )
+                .collect::<String>();
+            let mut edits = Vec::new();
+            let is_noop = is_move && (after == end || after == start - 1);
+            if !is_noop {
+                if after < 0 {
+                    edits.push((Point::zero()..Point::zero(), text + "\n"));
+                } else {
+                    let row = MultiBufferRow(after as u32);
+                    let point = Point::new(row.0, snapshot.line_len(row));
+                    edits.push((point..point, "\n".to_string() + &text));
+                }
+            }
+            if is_move && !is_noop {
+                let deletion = if source.end < snapshot.max_row() {
+                    Point::new(source.start.0, 0)..Point::new(source.end.0 + 1, 0)
+                } else {
+                    let previous = MultiBufferRow(source.start.0 - 1);
+                    Point::new(previous.0, snapshot.line_len(previous))
+                        ..Point::new(source.end.0, snapshot.line_len(source.end))
+                };
+                edits.push((deletion, String::new()));
+            }
+
+            // Like Vim, leave the cursor on the last line that was copied or moved.
+            let last_row = if is_move && after >= end {
+                after
+            } else {
+                after + end - start + 1
+            };
+            let column = editor.selections.newest::<Point>(cx).head().column;
+            editor.transact(window, cx, |editor, window, cx| {
+                editor.edit(edits, cx);
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point(Point::new(last_row as u32, column), Bias::Left);
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.select_ranges([point..point]);
+                });
+            });
+            anyhow::Ok(())
+        });
+        if let Some(e @ Err(_)) = result {
+            let Some(workspace) = self.workspace(window) else {
+                return;
+            };
+            workspace.update(cx, |workspace, cx| {
+                e.notify_err(workspace, cx);
+            });
+        }
+    }
+
+    /// Like Vim's buffer-local option, `:set tabstop` only changes how hard tabs are displayed
+    /// in this editor, and is never written to the settings file.
+    fn set_tab_stop(
+        &mut self,
+        size: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let tab_size = NonZeroU32::new(size)
+            .ok_or_else(|| anyhow!("E487: Argument must be positive: tabstop={size}"))?;
+        self.update_editor(window, cx, |_, editor, _, cx| {
+            editor.set_tab_size_override(Some(tab_size), cx);
+        });
+        Ok(())
+    }
+}
+
+/// The root of the window's first folder, which Zed treats like Vim's current directory.
+fn working_directory(workspace: &Workspace, cx: &App) -> Option<PathBuf> {
+    workspace
+        .visible_worktrees(cx)
+        .next()
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+}
+
+impl ChangeDirectory {
+    fn parse(query: &str) -> Option<Box<dyn Action>> {
+        let arguments = strip_command(query, "cd", "")
+            .or_else(|| strip_command(query, "chd", "ir"))?;
+        if !arguments.is_empty() && !arguments.starts_with(' ') {
+            return None;
+        }
+        Some(
+            ChangeDirectory {
+                path: arguments.trim().to_string(),
+            }
+            .boxed_clone(),
+        )
+    }
+
+    /// Resolves the argument the way Vim does: no argument or `~` means the home directory, `-`
+    /// means the previous directory, and relative paths are relative to the current one.
+    fn target(&self, current: Option<&Path>, previous: Option<&Path>) -> Result<PathBuf> {
+        let home = util::paths::home_dir();
+        let path = match self.path.as_str() {
+            "" | "~" => return Ok(home.clone()),
+            "-" => {
+                return previous
+                    .map(Path::to_path_buf)
+                    .ok_or_else(|| anyhow!("E186: No previous directory"));
+            }
+            path => path,
+        };
+        if let Some(rest) = path.strip_prefix("~/") {
+            return Ok(home.join(rest));
+        }
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return Ok(path.to_path_buf());
+        }
+        let current = current
+            .ok_or_else(|| anyhow!("E344: Can't find directory \"{}\"", path.display()))?;
+        Ok(current.join(path))
+    }
+}
+
+impl DeleteMarks {
+    fn parse(query: &str) -> Option<Box<dyn Action>> {
+        let query = strip_command(query, "delm", "arks")?;
+        if let Some(rest) = query.strip_prefix('!') {
+            return rest
+                .trim()
+                .is_empty()
+                .then(|| DeleteMarks::AllLocal.boxed_clone());
+        }
+        if !query.starts_with(' ') || query.trim().is_empty() {
+            return None;
+        }
+        Some(DeleteMarks::Marks(query.trim().to_string()).boxed_clone())
+    }
+
+    fn mark_names(marks: &str) -> Result<Vec<String>> {
+        let invalid = || anyhow!("E475: Invalid argument: {marks}");
+        let mut names = Vec::new();
+        let mut chars = marks.chars().filter(|c| !c.is_whitespace()).peekable();
+        while let Some(start) = chars.next() {
+            if chars.next_if_eq(&'-').is_some() {
+                let end = chars.next().ok_or_else(invalid)?;
+                let same_kind = (start.is_ascii_lowercase() && end.is_ascii_lowercase())
+                    || (start.is_ascii_uppercase() && end.is_ascii_uppercase())
+                    || (start.is_ascii_digit() && end.is_ascii_digit());
+                if !same_kind || end < start {
+                    return Err(invalid());
+                }
+                names.extend((start..=end).map(|c| c.to_string()));
+            } else if start.is_ascii_alphanumeric() || "\"^.[]<>".contains(start) {
+                names.push(start.to_string());
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(names)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use std::path::Path;
+    use std::{num::NonZeroU32, path::Path};
 
     use crate::{
         state::Mode,
@@ -1619,6 +2078,7 @@ mod test {
     use editor::Editor;
     use gpui::{Context, TestAppContext};
     use indoc::indoc;
+    use language::language_settings::language_settings;
     use util::path;
     use workspace::Workspace;
 
@@ -1658,6 +2118,235 @@ mod test {
             ˇc"});
     }
 
+    #[gpui::test]
+    async fn test_command_goto_jumplist(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d
+            e"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 3 enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            ˇc
+            d
+            e"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("` `");
+        cx.assert_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d
+            e"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": $ enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            c
+            d
+            ˇe"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": . enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            c
+            d
+            ˇe"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g g : % enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            c
+            d
+            ˇe"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("` `");
+        cx.assert_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d
+            e"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_delmarks(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("m a j m b j m c j m shift-a");
+        cx.simulate_keystrokes(": d e l m space a enter");
+        cx.simulate_keystrokes("' a");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            c
+            ˇd"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("' b");
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇb
+            c
+            d"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": d e l m a r k s space b - c space shift-a enter");
+        cx.simulate_keystrokes("' c ' shift-a");
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇb
+            c
+            d"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("m a j m b");
+        cx.simulate_keystrokes(": d e l m ! enter");
+        cx.simulate_keystrokes("' a ' b");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            ˇc
+            d"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_set_options(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": s e t space s o = 3 enter");
+        cx.update_editor(|editor, _, _| assert_eq!(editor.vertical_scroll_margin(), 3));
+
+        cx.set_state("ˇFoo foo Foo", Mode::Normal);
+        cx.simulate_keystrokes("/ F o o enter");
+        cx.assert_state("Foo ˇfoo Foo", Mode::Normal);
+
+        cx.set_state("ˇFoo foo Foo", Mode::Normal);
+        cx.simulate_keystrokes(": s e t space n o i c enter");
+        cx.simulate_keystrokes("/ F o o enter");
+        cx.assert_state("Foo foo ˇFoo", Mode::Normal);
+
+        cx.set_state("\tˇa", Mode::Normal);
+        cx.simulate_keystrokes(": s e t space t s = 2 enter");
+        cx.update_editor(|editor, _, cx| {
+            assert_eq!(editor.selections.newest_display(cx).head().column(), 2);
+            assert_eq!(
+                language_settings(None, None, cx).tab_size,
+                NonZeroU32::new(4).unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn test_change_directory_target() {
+        use super::ChangeDirectory;
+        use std::path::PathBuf;
+
+        let cd = |path: &str| ChangeDirectory {
+            path: path.to_string(),
+        };
+        let current = Path::new(path!("/root/project"));
+        let previous = Path::new(path!("/root/other"));
+        let home = util::paths::home_dir();
+
+        assert_eq!(cd("src").target(Some(current), None).unwrap(), current.join("src"));
+        assert_eq!(
+            cd(path!("/tmp")).target(Some(current), None).unwrap(),
+            PathBuf::from(path!("/tmp"))
+        );
+        assert_eq!(cd("").target(Some(current), None).unwrap(), *home);
+        assert_eq!(cd("~").target(Some(current), None).unwrap(), *home);
+        assert_eq!(cd("~/code").target(Some(current), None).unwrap(), home.join("code"));
+        assert_eq!(cd("-").target(Some(current), Some(previous)).unwrap(), previous);
+        assert!(cd("-").target(Some(current), None).is_err());
+        assert!(cd("src").target(None, None).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_command_copy_move(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        let initial = indoc! {"
+            one
+            tˇwo
+            three
+            four
+            five"};
+        for (keystrokes, expected) in [
+            (": m $ enter", "one\nthree\nfour\nfive\ntˇwo"),
+            (": t . enter", "one\ntwo\ntˇwo\nthree\nfour\nfive"),
+            (": m 0 enter", "tˇwo\none\nthree\nfour\nfive"),
+            (": m - 2 enter", "tˇwo\none\nthree\nfour\nfive"),
+            (": 1 , 2 m 4 enter", "three\nfour\none\ntˇwo\nfive"),
+            (": 4 , 5 m 2 enter", "one\ntwo\nfour\nfˇive\nthree"),
+            (": 4 , 5 t 0 enter", "four\nfˇive\none\ntwo\nthree\nfour\nfive"),
+            (": 2 , 3 c o $ enter", "one\ntwo\nthree\nfour\nfive\ntwo\ntˇhree"),
+            (": m . enter", "one\ntˇwo\nthree\nfour\nfive"),
+            (": m 1 enter", "one\ntˇwo\nthree\nfour\nfive"),
+        ] {
+            cx.set_shared_state(initial).await;
+            cx.simulate_shared_keystrokes(keystrokes).await;
+            cx.shared_state().await.assert_eq(expected);
+        }
+    }
+
+    #[gpui::test]
+    async fn test_command_copy_move_clamps_destination(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("one\ntˇwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes(": t 9 9 enter");
+        cx.assert_state("one\ntwo\nthree\ntˇwo", Mode::Normal);
+
+        cx.set_state("one\ntˇwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes(": m 9 9 enter");
+        cx.assert_state("one\nthree\ntˇwo", Mode::Normal);
+
+        cx.set_state("one\ntˇwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes(": 2 , 3 m 2 enter");
+        cx.assert_state("one\ntˇwo\nthree", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_command_replace(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
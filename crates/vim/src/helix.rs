@@ -1,16 +1,17 @@
-use editor::{DisplayPoint, Editor, movement, scroll::Autoscroll};
+use editor::{DisplayPoint, Editor, display_map::ToDisplayPoint, movement, scroll::Autoscroll};
 use gpui::{Action, actions};
 use gpui::{Context, Window};
-use language::{CharClassifier, CharKind};
+use language::{CharClassifier, CharKind, Point, SelectionGoal};
 
 use crate::motion::MotionKind;
 use crate::{Vim, motion::Motion, state::Mode};
 
-actions!(vim, [HelixNormalAfter, HelixDelete]);
+actions!(vim, [HelixNormalAfter, HelixDelete, HelixSelectLine]);
 
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_normal_after);
     Vim::action(editor, cx, Vim::helix_delete);
+    Vim::action(editor, cx, Vim::helix_select_line);
 }
 
 impl Vim {
@@ -186,7 +187,10 @@ impl Vim {
         cx: &mut Context<Self>,
     ) {
         match motion {
-            Motion::NextWordStart { ignore_punctuation } => {
+            Motion::NextWordStart {
+                ignore_punctuation,
+                ..
+            } => {
                 self.helix_find_range_forward(times, window, cx, |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
@@ -198,7 +202,10 @@ impl Vim {
                     found
                 })
             }
-            Motion::NextWordEnd { ignore_punctuation } => {
+            Motion::NextWordEnd {
+                ignore_punctuation,
+                ..
+            } => {
                 self.helix_find_range_forward(times, window, cx, |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
@@ -210,7 +217,10 @@ impl Vim {
                     found
                 })
             }
-            Motion::PreviousWordStart { ignore_punctuation } => {
+            Motion::PreviousWordStart {
+                ignore_punctuation,
+                ..
+            } => {
                 self.helix_find_range_backward(times, window, cx, |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
@@ -222,7 +232,10 @@ impl Vim {
                     found
                 })
             }
-            Motion::PreviousWordEnd { ignore_punctuation } => {
+            Motion::PreviousWordEnd {
+                ignore_punctuation,
+                ..
+            } => {
                 self.helix_find_range_backward(times, window, cx, |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
@@ -235,10 +248,105 @@ impl Vim {
                     found
                 })
             }
+            Motion::StartOfDocument | Motion::EndOfDocument => {
+                self.helix_move_and_extend(motion, times, window, cx)
+            }
             _ => self.helix_move_and_collapse(motion, times, window, cx),
         }
     }
 
+    /// Moves the head of each selection with `motion`, keeping the character under
+    /// the anchor selected like Helix does when a selection changes direction.
+    pub fn helix_move_and_extend(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let text_layout_details = editor.text_layout_details(window);
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    // treat a collapsed cursor as a one character selection
+                    if selection.is_empty() {
+                        selection.end = movement::right(map, selection.end);
+                        selection.reversed = false;
+                    }
+                    let cursor = if selection.reversed {
+                        selection.head()
+                    } else {
+                        movement::left(map, selection.head())
+                    };
+
+                    let Some((point, goal)) =
+                        motion.move_point(map, cursor, selection.goal, times, &text_layout_details)
+                    else {
+                        return;
+                    };
+
+                    let mut anchor = selection.tail();
+                    if !selection.reversed && point < anchor {
+                        anchor = movement::right(map, anchor);
+                    } else if selection.reversed && point >= anchor {
+                        anchor = movement::left(map, anchor);
+                    }
+                    if point >= anchor {
+                        selection.start = anchor;
+                        selection.end = movement::right(map, point);
+                        selection.reversed = false;
+                    } else {
+                        selection.start = point;
+                        selection.end = anchor;
+                        selection.reversed = true;
+                    }
+                    selection.goal = goal;
+                })
+            });
+        });
+    }
+
+    /// Selects the whole line under each cursor, newline included. Selections that
+    /// already cover whole lines are extended down by one line instead.
+    pub fn helix_select_line(
+        &mut self,
+        _: &HelixSelectLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let times = Vim::take_count(cx).unwrap_or(1) as u32;
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let start = selection.start.to_point(map);
+                    let end = selection.end.to_point(map);
+                    let ends_at_line_start = end.column == 0 && end.row > start.row;
+                    let last_row = if ends_at_line_start {
+                        end.row - 1
+                    } else {
+                        end.row
+                    };
+                    let target_row = if start.column == 0 && ends_at_line_start {
+                        last_row + times
+                    } else {
+                        last_row + times - 1
+                    };
+
+                    let max_point = map.buffer_snapshot.max_point();
+                    let new_end = if target_row >= max_point.row {
+                        max_point
+                    } else {
+                        Point::new(target_row + 1, 0)
+                    };
+                    selection.start = Point::new(start.row, 0).to_display_point(map);
+                    selection.end = new_end.to_display_point(map);
+                    selection.reversed = false;
+                    selection.goal = SelectionGoal::None;
+                });
+            });
+        });
+    }
+
     pub fn helix_delete(&mut self, _: &HelixDelete, window: &mut Window, cx: &mut Context<Self>) {
         self.store_visual_marks(window, cx);
         self.update_editor(window, cx, |vim, editor, window, cx| {
@@ -302,6 +410,118 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_select_line(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+            The quˇick brown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+            «The quick brown
+            ˇ»fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+            «The quick brown
+            fox jumps over
+            ˇ»the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        // the last line has no newline to include
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+            «The quick brown
+            fox jumps over
+            the lazy dog.ˇ»"},
+            Mode::HelixNormal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            The quick brown
+            fox «jumpsˇ» over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("2 x");
+        cx.assert_state(
+            indoc! {"
+            The quick brown
+            «fox jumps over
+            the lazy dog.ˇ»"},
+            Mode::HelixNormal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_document_motions_extend(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+            The quˇick brown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("shift-g");
+        cx.assert_state(
+            indoc! {"
+            The qu«ick brown
+            fox jumps over
+            tˇ»he lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        // the anchor character stays selected when the selection flips
+        cx.simulate_keystrokes("g g");
+        cx.assert_state(
+            indoc! {"
+            «ˇThe qui»ck brown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("shift-g");
+        cx.assert_state(
+            indoc! {"
+            The qu«ick brown
+            fox jumps over
+            tˇ»he lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            The quick brown
+            fox «jumpsˇ» over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("g g");
+        cx.assert_state(
+            indoc! {"
+            «ˇThe quick brown
+            fox j»umps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+    }
+
     // #[gpui::test]
     // async fn test_delete(cx: &mut gpui::TestAppContext) {
     //     let mut cx = VimTestContext::new(cx, true).await;
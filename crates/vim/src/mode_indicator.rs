@@ -70,10 +70,15 @@ impl ModeIndicator {
         recording
             .chain(
                 cx.global::<VimGlobals>()
-                    .pre_count
+                    .register_count
                     .map(|count| format!("{}", count)),
             )
             .chain(vim.selected_register.map(|reg| format!("\"{reg}")))
+            .chain(
+                cx.global::<VimGlobals>()
+                    .pre_count
+                    .map(|count| format!("{}", count)),
+            )
             .chain(
                 vim.operator_stack
                     .iter()
@@ -1,5 +1,7 @@
+use anyhow::{Context as _, Result};
 use editor::{
     Anchor, Bias, DisplayPoint, Editor, RowExt, ToOffset, ToPoint,
+    actions::{GoToDefinition, GoToDefinitionSplit},
     display_map::{DisplayRow, DisplaySnapshot, FoldPoint, ToDisplayPoint},
     movement::{
         self, FindRange, TextLayoutDetails, find_boundary, find_preceding_boundary_display_point,
@@ -7,15 +9,22 @@ use editor::{
     scroll::Autoscroll,
 };
 use gpui::{Context, Window, action_with_deprecated_aliases, actions, impl_actions, px};
-use language::{CharKind, Point, Selection, SelectionGoal};
+use language::{
+    BufferSnapshot, CharClassifier, CharKind, Point, Selection, SelectionGoal, WordChars,
+};
 use multi_buffer::MultiBufferRow;
 use schemars::JsonSchema;
 use serde::Deserialize;
-use std::ops::Range;
+use settings::Settings;
+use std::{
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
+use unicode_width::UnicodeWidthChar;
 use workspace::searchable::Direction;
 
 use crate::{
-    Vim,
+    Vim, VimSettings,
     normal::mark,
     state::{Mode, Operator},
     surrounds::SurroundsType,
@@ -55,15 +64,19 @@ pub enum Motion {
     WrappingRight,
     NextWordStart {
         ignore_punctuation: bool,
+        keyword: Option<Arc<Keyword>>,
     },
     NextWordEnd {
         ignore_punctuation: bool,
+        keyword: Option<Arc<Keyword>>,
     },
     PreviousWordStart {
         ignore_punctuation: bool,
+        keyword: Option<Arc<Keyword>>,
     },
     PreviousWordEnd {
         ignore_punctuation: bool,
+        keyword: Option<Arc<Keyword>>,
     },
     NextSubwordStart {
         ignore_punctuation: bool,
@@ -87,19 +100,27 @@ pub enum Motion {
     EndOfLine {
         display_lines: bool,
     },
-    SentenceBackward,
-    SentenceForward,
+    SentenceBackward {
+        skip_abbreviations: bool,
+    },
+    SentenceForward {
+        skip_abbreviations: bool,
+    },
     StartOfParagraph,
     EndOfParagraph,
     StartOfDocument,
     EndOfDocument,
-    Matching,
+    Matching {
+        match_pairs: Vec<(char, char)>,
+    },
     GoToPercentage,
     UnmatchedForward {
         char: char,
+        match_pairs: Vec<(char, char)>,
     },
     UnmatchedBackward {
         char: char,
+        match_pairs: Vec<(char, char)>,
     },
     FindForward {
         before: bool,
@@ -132,7 +153,9 @@ pub enum Motion {
     NextLineStart,
     PreviousLineStart,
     StartOfLineDownward,
-    EndOfLineDownward,
+    EndOfLineDownward {
+        display_lines: bool,
+    },
     GoToColumn,
     WindowTop,
     WindowMiddle,
@@ -147,6 +170,14 @@ pub enum Motion {
     PreviousMethodEnd,
     NextComment,
     PreviousComment,
+    NextUrl,
+    PreviousUrl,
+    NextSibling,
+    PreviousSibling,
+    ParentStart,
+    ParentEnd,
+    NextExcerpt,
+    PreviousExcerpt,
     PreviousLesserIndent,
     PreviousGreaterIndent,
     PreviousSameIndent,
@@ -167,7 +198,7 @@ pub enum Motion {
     },
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 enum IndentType {
     Lesser,
     Greater,
@@ -258,6 +289,13 @@ struct EndOfLine {
     display_lines: bool,
 }
 
+#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct EndOfLineDownward {
+    #[serde(default)]
+    display_lines: bool,
+}
+
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct StartOfLine {
@@ -284,6 +322,7 @@ impl_actions!(
     [
         StartOfLine,
         EndOfLine,
+        EndOfLineDownward,
         FirstNonWhitespace,
         Down,
         Up,
@@ -319,7 +358,6 @@ actions!(
         NextLineStart,
         PreviousLineStart,
         StartOfLineDownward,
-        EndOfLineDownward,
         GoToColumn,
         RepeatFind,
         RepeatFindReversed,
@@ -336,12 +374,22 @@ actions!(
         PreviousMethodEnd,
         NextComment,
         PreviousComment,
+        NextUrl,
+        PreviousUrl,
+        NextSibling,
+        PreviousSibling,
+        ParentStart,
+        ParentEnd,
+        NextExcerpt,
+        PreviousExcerpt,
         PreviousLesserIndent,
         PreviousGreaterIndent,
         PreviousSameIndent,
         NextLesserIndent,
         NextGreaterIndent,
         NextSameIndent,
+        GotoTagDefinition,
+        GotoTagDefinitionSplit,
     ]
 );
 
@@ -429,10 +477,12 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, _: &SentenceForward, window, cx| {
-        vim.motion(Motion::SentenceForward, window, cx)
+        let skip_abbreviations = VimSettings::get_global(cx).smart_sentence_detection;
+        vim.motion(Motion::SentenceForward { skip_abbreviations }, window, cx)
     });
     Vim::action(editor, cx, |vim, _: &SentenceBackward, window, cx| {
-        vim.motion(Motion::SentenceBackward, window, cx)
+        let skip_abbreviations = VimSettings::get_global(cx).smart_sentence_detection;
+        vim.motion(Motion::SentenceBackward { skip_abbreviations }, window, cx)
     });
     Vim::action(editor, cx, |vim, _: &StartOfDocument, window, cx| {
         vim.motion(Motion::StartOfDocument, window, cx)
@@ -441,7 +491,8 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         vim.motion(Motion::EndOfDocument, window, cx)
     });
     Vim::action(editor, cx, |vim, _: &Matching, window, cx| {
-        vim.motion(Motion::Matching, window, cx)
+        let match_pairs = vim.match_pairs(cx);
+        vim.motion(Motion::Matching { match_pairs }, window, cx)
     });
     Vim::action(editor, cx, |vim, _: &GoToPercentage, window, cx| {
         vim.motion(Motion::GoToPercentage, window, cx)
@@ -450,42 +501,64 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         editor,
         cx,
         |vim, &UnmatchedForward { char }: &UnmatchedForward, window, cx| {
-            vim.motion(Motion::UnmatchedForward { char }, window, cx)
+            let match_pairs = vim.match_pairs(cx);
+            vim.motion(Motion::UnmatchedForward { char, match_pairs }, window, cx)
         },
     );
     Vim::action(
         editor,
         cx,
         |vim, &UnmatchedBackward { char }: &UnmatchedBackward, window, cx| {
-            vim.motion(Motion::UnmatchedBackward { char }, window, cx)
+            let match_pairs = vim.match_pairs(cx);
+            vim.motion(Motion::UnmatchedBackward { char, match_pairs }, window, cx)
         },
     );
     Vim::action(
         editor,
         cx,
         |vim, &NextWordStart { ignore_punctuation }: &NextWordStart, window, cx| {
-            vim.motion(Motion::NextWordStart { ignore_punctuation }, window, cx)
+            let keyword = VimSettings::get_global(cx).keyword.clone();
+            let motion = Motion::NextWordStart {
+                ignore_punctuation,
+                keyword,
+            };
+            vim.motion(motion, window, cx)
         },
     );
     Vim::action(
         editor,
         cx,
         |vim, &NextWordEnd { ignore_punctuation }: &NextWordEnd, window, cx| {
-            vim.motion(Motion::NextWordEnd { ignore_punctuation }, window, cx)
+            let keyword = VimSettings::get_global(cx).keyword.clone();
+            let motion = Motion::NextWordEnd {
+                ignore_punctuation,
+                keyword,
+            };
+            vim.motion(motion, window, cx)
         },
     );
     Vim::action(
         editor,
         cx,
         |vim, &PreviousWordStart { ignore_punctuation }: &PreviousWordStart, window, cx| {
-            vim.motion(Motion::PreviousWordStart { ignore_punctuation }, window, cx)
+            let keyword = VimSettings::get_global(cx).keyword.clone();
+            let motion = Motion::PreviousWordStart {
+                ignore_punctuation,
+                keyword,
+            };
+            vim.motion(motion, window, cx)
         },
     );
     Vim::action(
         editor,
         cx,
         |vim, &PreviousWordEnd { ignore_punctuation }, window, cx| {
-            vim.motion(Motion::PreviousWordEnd { ignore_punctuation }, window, cx)
+            let keyword = VimSettings::get_global(cx).keyword.clone();
+            let motion = Motion::PreviousWordEnd {
+                ignore_punctuation,
+                keyword,
+            };
+            vim.motion(motion, window, cx)
         },
     );
     Vim::action(
@@ -533,8 +606,14 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, &StartOfLineDownward, window, cx| {
         vim.motion(Motion::StartOfLineDownward, window, cx)
     });
-    Vim::action(editor, cx, |vim, &EndOfLineDownward, window, cx| {
-        vim.motion(Motion::EndOfLineDownward, window, cx)
+    Vim::action(editor, cx, |vim, action: &EndOfLineDownward, window, cx| {
+        vim.motion(
+            Motion::EndOfLineDownward {
+                display_lines: action.display_lines,
+            },
+            window,
+            cx,
+        )
     });
     Vim::action(editor, cx, |vim, &GoToColumn, window, cx| {
         vim.motion(Motion::GoToColumn, window, cx)
@@ -591,6 +670,30 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, &PreviousComment, window, cx| {
         vim.motion(Motion::PreviousComment, window, cx)
     });
+    Vim::action(editor, cx, |vim, &NextUrl, window, cx| {
+        vim.motion(Motion::NextUrl, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousUrl, window, cx| {
+        vim.motion(Motion::PreviousUrl, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextSibling, window, cx| {
+        vim.motion(Motion::NextSibling, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousSibling, window, cx| {
+        vim.motion(Motion::PreviousSibling, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &ParentStart, window, cx| {
+        vim.motion(Motion::ParentStart, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &ParentEnd, window, cx| {
+        vim.motion(Motion::ParentEnd, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextExcerpt, window, cx| {
+        vim.motion(Motion::NextExcerpt, window, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousExcerpt, window, cx| {
+        vim.motion(Motion::PreviousExcerpt, window, cx)
+    });
     Vim::action(editor, cx, |vim, &PreviousLesserIndent, window, cx| {
         vim.motion(Motion::PreviousLesserIndent, window, cx)
     });
@@ -609,9 +712,49 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, &NextSameIndent, window, cx| {
         vim.motion(Motion::NextSameIndent, window, cx)
     });
+    Vim::action(editor, cx, |vim, _: &GotoTagDefinition, window, cx| {
+        vim.goto_tag_definition(false, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &GotoTagDefinitionSplit, window, cx| {
+        vim.goto_tag_definition(true, window, cx)
+    });
 }
 
 impl Vim {
+    /// Vim's tag jumps, backed by the language server's definitions rather than a tags file.
+    fn goto_tag_definition(&mut self, split: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if self.mode != Mode::Normal && !self.mode.is_visual() {
+            return;
+        }
+        self.clear_operator(window, cx);
+        if self.mode.is_visual() {
+            self.switch_mode(Mode::Normal, false, window, cx);
+        }
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let navigate = if split {
+                editor.go_to_definition_split(&GoToDefinitionSplit, window, cx)
+            } else {
+                editor.go_to_definition(&GoToDefinition, window, cx)
+            };
+            navigate.detach_and_log_err(cx);
+        });
+    }
+
+    /// The `matchpairs` that apply to the language under the newest cursor.
+    fn match_pairs(&self, cx: &mut Context<Self>) -> Vec<(char, char)> {
+        let language = self.editor().and_then(|editor| {
+            let editor = editor.read(cx);
+            let head = editor.selections.newest_anchor().head();
+            editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .language_at(head)
+                .map(|language| language.name())
+        });
+        VimSettings::get_global(cx).match_pairs(language.as_ref())
+    }
+
     pub(crate) fn search_motion(&mut self, m: Motion, window: &mut Window, cx: &mut Context<Self>) {
         if let Motion::ZedSearchResult {
             prior_selections, ..
@@ -629,6 +772,7 @@ impl Vim {
                 }
                 Mode::Normal | Mode::Replace | Mode::Insert => {
                     if self.active_operator().is_none() {
+                        self.center_on_jump(window, cx);
                         return;
                     }
                 }
@@ -711,6 +855,8 @@ impl Motion {
             | PreviousMethodEnd
             | NextComment
             | PreviousComment
+            | NextExcerpt
+            | PreviousExcerpt
             | PreviousLesserIndent
             | PreviousGreaterIndent
             | PreviousSameIndent
@@ -720,8 +866,8 @@ impl Motion {
             | GoToPercentage
             | Jump { line: true, .. } => MotionKind::Linewise,
             EndOfLine { .. }
-            | EndOfLineDownward
-            | Matching
+            | EndOfLineDownward { .. }
+            | Matching { .. }
             | FindForward { .. }
             | NextWordEnd { .. }
             | PreviousWordEnd { .. }
@@ -734,8 +880,8 @@ impl Motion {
             | StartOfLine { .. }
             | StartOfParagraph
             | EndOfParagraph
-            | SentenceBackward
-            | SentenceForward
+            | SentenceBackward { .. }
+            | SentenceForward { .. }
             | GoToColumn
             | UnmatchedForward { .. }
             | UnmatchedBackward { .. }
@@ -747,6 +893,12 @@ impl Motion {
             | FindBackward { .. }
             | Sneak { .. }
             | SneakBackward { .. }
+            | NextUrl
+            | PreviousUrl
+            | NextSibling
+            | PreviousSibling
+            | ParentStart
+            | ParentEnd
             | Jump { .. }
             | ZedSearchResult { .. } => MotionKind::Exclusive,
             RepeatFind { last_find: motion } | RepeatFindReversed { last_find: motion } => {
@@ -769,7 +921,7 @@ impl Motion {
             Down { .. }
             | Up { .. }
             | EndOfLine { .. }
-            | Matching
+            | Matching { .. }
             | UnmatchedForward { .. }
             | UnmatchedBackward { .. }
             | FindForward { .. }
@@ -781,10 +933,10 @@ impl Motion {
             | StartOfLine { .. }
             | StartOfParagraph
             | EndOfParagraph
-            | SentenceBackward
-            | SentenceForward
+            | SentenceBackward { .. }
+            | SentenceForward { .. }
             | StartOfLineDownward
-            | EndOfLineDownward
+            | EndOfLineDownward { .. }
             | GoToColumn
             | GoToPercentage
             | NextWordStart { .. }
@@ -816,6 +968,14 @@ impl Motion {
             | PreviousMethodEnd
             | NextComment
             | PreviousComment
+            | NextUrl
+            | PreviousUrl
+            | NextSibling
+            | PreviousSibling
+            | ParentStart
+            | ParentEnd
+            | NextExcerpt
+            | PreviousExcerpt
             | PreviousLesserIndent
             | PreviousGreaterIndent
             | PreviousSameIndent
@@ -826,6 +986,82 @@ impl Motion {
         }
     }
 
+    /// Motions that can move the cursor far enough to leave the screen, which
+    /// `vim.center_on_jump` recenters on.
+    pub(crate) fn is_jump(&self) -> bool {
+        use Motion::*;
+        match self {
+            StartOfDocument
+            | EndOfDocument
+            | GoToPercentage
+            | Matching { .. }
+            | StartOfParagraph
+            | EndOfParagraph
+            | SentenceBackward { .. }
+            | SentenceForward { .. }
+            | WindowTop
+            | WindowMiddle
+            | WindowBottom
+            | ZedSearchResult { .. }
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | Jump { .. } => true,
+            Down { .. }
+            | Up { .. }
+            | CurrentLine
+            | EndOfLine { .. }
+            | UnmatchedForward { .. }
+            | UnmatchedBackward { .. }
+            | FindForward { .. }
+            | RepeatFind { .. }
+            | Left
+            | WrappingLeft
+            | Right
+            | WrappingRight
+            | StartOfLine { .. }
+            | StartOfLineDownward
+            | EndOfLineDownward { .. }
+            | GoToColumn
+            | NextWordStart { .. }
+            | NextWordEnd { .. }
+            | PreviousWordStart { .. }
+            | PreviousWordEnd { .. }
+            | NextSubwordStart { .. }
+            | NextSubwordEnd { .. }
+            | PreviousSubwordStart { .. }
+            | PreviousSubwordEnd { .. }
+            | FirstNonWhitespace { .. }
+            | FindBackward { .. }
+            | Sneak { .. }
+            | SneakBackward { .. }
+            | RepeatFindReversed { .. }
+            | NextLineStart
+            | PreviousLineStart
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextComment
+            | PreviousComment
+            | NextUrl
+            | PreviousUrl
+            | NextSibling
+            | PreviousSibling
+            | ParentStart
+            | ParentEnd
+            | NextExcerpt
+            | PreviousExcerpt
+            | PreviousLesserIndent
+            | PreviousGreaterIndent
+            | PreviousSameIndent
+            | NextLesserIndent
+            | NextGreaterIndent
+            | NextSameIndent => false,
+        }
+    }
+
     pub fn move_point(
         &self,
         map: &DisplaySnapshot,
@@ -854,20 +1090,32 @@ impl Motion {
             } => up_display(map, point, goal, times, text_layout_details),
             Right => (right(map, point, times), SelectionGoal::None),
             WrappingRight => (wrapping_right(map, point, times), SelectionGoal::None),
-            NextWordStart { ignore_punctuation } => (
-                next_word_start(map, point, *ignore_punctuation, times),
+            NextWordStart {
+                ignore_punctuation,
+                keyword,
+            } => (
+                next_word_start(map, point, *ignore_punctuation, keyword.as_ref(), times),
                 SelectionGoal::None,
             ),
-            NextWordEnd { ignore_punctuation } => (
-                next_word_end(map, point, *ignore_punctuation, times, true),
+            NextWordEnd {
+                ignore_punctuation,
+                keyword,
+            } => (
+                next_word_end(map, point, *ignore_punctuation, keyword.as_ref(), times, true),
                 SelectionGoal::None,
             ),
-            PreviousWordStart { ignore_punctuation } => (
-                previous_word_start(map, point, *ignore_punctuation, times),
+            PreviousWordStart {
+                ignore_punctuation,
+                keyword,
+            } => (
+                previous_word_start(map, point, *ignore_punctuation, keyword.as_ref(), times),
                 SelectionGoal::None,
             ),
-            PreviousWordEnd { ignore_punctuation } => (
-                previous_word_end(map, point, *ignore_punctuation, times),
+            PreviousWordEnd {
+                ignore_punctuation,
+                keyword,
+            } => (
+                previous_word_end(map, point, *ignore_punctuation, keyword.as_ref(), times),
                 SelectionGoal::None,
             ),
             NextSubwordStart { ignore_punctuation } => (
@@ -898,8 +1146,14 @@ impl Motion {
                 end_of_line(map, *display_lines, point, times),
                 SelectionGoal::None,
             ),
-            SentenceBackward => (sentence_backwards(map, point, times), SelectionGoal::None),
-            SentenceForward => (sentence_forwards(map, point, times), SelectionGoal::None),
+            SentenceBackward { skip_abbreviations } => (
+                sentence_backwards(map, point, times, *skip_abbreviations),
+                SelectionGoal::None,
+            ),
+            SentenceForward { skip_abbreviations } => (
+                sentence_forwards(map, point, times, *skip_abbreviations),
+                SelectionGoal::None,
+            ),
             StartOfParagraph => (
                 movement::start_of_paragraph(map, point, times),
                 SelectionGoal::None,
@@ -910,21 +1164,21 @@ impl Motion {
             ),
             CurrentLine => (next_line_end(map, point, times), SelectionGoal::None),
             StartOfDocument => (
-                start_of_document(map, point, maybe_times),
+                first_non_whitespace(map, false, start_of_document(map, point, maybe_times)),
                 SelectionGoal::None,
             ),
             EndOfDocument => (
                 end_of_document(map, point, maybe_times),
                 SelectionGoal::None,
             ),
-            Matching => (matching(map, point), SelectionGoal::None),
+            Matching { match_pairs } => (matching(map, point, match_pairs), SelectionGoal::None),
             GoToPercentage => (go_to_percentage(map, point, times), SelectionGoal::None),
-            UnmatchedForward { char } => (
-                unmatched_forward(map, point, *char, times),
+            UnmatchedForward { char, match_pairs } => (
+                unmatched_forward(map, point, *char, match_pairs, times),
                 SelectionGoal::None,
             ),
-            UnmatchedBackward { char } => (
-                unmatched_backward(map, point, *char, times),
+            UnmatchedBackward { char, match_pairs } => (
+                unmatched_backward(map, point, *char, match_pairs, times),
                 SelectionGoal::None,
             ),
             // t f
@@ -1107,9 +1361,12 @@ impl Motion {
             NextLineStart => (next_line_start(map, point, times), SelectionGoal::None),
             PreviousLineStart => (previous_line_start(map, point, times), SelectionGoal::None),
             StartOfLineDownward => (next_line_start(map, point, times - 1), SelectionGoal::None),
-            EndOfLineDownward => (last_non_whitespace(map, point, times), SelectionGoal::None),
+            EndOfLineDownward { display_lines } => (
+                last_non_whitespace(map, *display_lines, point, times),
+                SelectionGoal::None,
+            ),
             GoToColumn => (go_to_column(map, point, times), SelectionGoal::None),
-            WindowTop => window_top(map, point, text_layout_details, times - 1),
+            WindowTop => window_top(map, text_layout_details, times - 1),
             WindowMiddle => window_middle(map, point, text_layout_details),
             WindowBottom => window_bottom(map, point, text_layout_details, times - 1),
             Jump { line, anchor } => mark::jump_motion(map, *anchor, *line),
@@ -1166,6 +1423,32 @@ impl Motion {
                 comment_motion(map, point, times, Direction::Prev),
                 SelectionGoal::None,
             ),
+            NextUrl => (
+                url_motion(map, point, times, Direction::Next),
+                SelectionGoal::None,
+            ),
+            PreviousUrl => (
+                url_motion(map, point, times, Direction::Prev),
+                SelectionGoal::None,
+            ),
+            NextSibling => (
+                sibling_motion(map, point, times, Direction::Next),
+                SelectionGoal::None,
+            ),
+            PreviousSibling => (
+                sibling_motion(map, point, times, Direction::Prev),
+                SelectionGoal::None,
+            ),
+            ParentStart => (parent_motion(map, point, times, true), SelectionGoal::None),
+            ParentEnd => (parent_motion(map, point, times, false), SelectionGoal::None),
+            NextExcerpt => (
+                excerpt_motion(map, point, times, Direction::Next),
+                SelectionGoal::None,
+            ),
+            PreviousExcerpt => (
+                excerpt_motion(map, point, times, Direction::Prev),
+                SelectionGoal::None,
+            ),
             PreviousLesserIndent => (
                 indent_motion(map, point, times, Direction::Prev, IndentType::Lesser),
                 SelectionGoal::None,
@@ -1253,10 +1536,7 @@ impl Motion {
             (kind, false) => kind,
         };
 
-        if let Motion::NextWordStart {
-            ignore_punctuation: _,
-        } = self
-        {
+        if let Motion::NextWordStart { .. } = self {
             // Another special case: When using the "w" motion in combination with an
             // operator and the last word moved over is at the end of a line, the end of
             // that word becomes the end of the operated text, not the first word in the
@@ -1275,6 +1555,14 @@ impl Motion {
                     return Some((selection.start..selection.end, MotionKind::Linewise));
                 }
             }
+        } else if *self == Motion::NextExcerpt {
+            // `y]b` and friends should stop at the end of the current excerpt rather
+            // than include the first line of the next one.
+            let start = selection.start.to_point(map);
+            let end = selection.end.to_point(map);
+            if end.row > start.row {
+                selection.end = Point::new(end.row - 1, 0).to_display_point(map);
+            }
         } else if kind == MotionKind::Exclusive && !self.skip_exclusive_special_case() {
             let start_point = selection.start.to_point(map);
             let mut end_point = selection.end.to_point(map);
@@ -1553,17 +1841,103 @@ pub(crate) fn next_char(
     map.clip_ignoring_line_ends(new_point, Bias::Right)
 }
 
+/// Word characters in Vim's `iskeyword` syntax, e.g. `"@,48-57,_,192-255"`: a comma
+/// separated list of characters, decimal character codes and ranges of either, where `@`
+/// stands for all letters and a leading `^` excludes the part. Later parts override earlier
+/// ones. As in Vim, characters above 255 keep their usual classification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keyword {
+    parts: Vec<(KeywordPart, bool)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum KeywordPart {
+    Letters,
+    Chars(RangeInclusive<char>),
+}
+
+impl Keyword {
+    pub(crate) fn parse(spec: &str) -> Result<Self> {
+        let parts = spec
+            .split(',')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (part, included) = match part.strip_prefix('^') {
+                    Some(part) if !part.is_empty() => (part, false),
+                    _ => (part, true),
+                };
+                if part == "@" {
+                    return Some((KeywordPart::Letters, included));
+                }
+                // Skips the first character so that "-" on its own means the dash itself.
+                let range = match part.char_indices().skip(1).find(|&(_, c)| c == '-') {
+                    Some((ix, _)) => {
+                        parse_keyword_char(&part[..ix])?..=parse_keyword_char(&part[ix + 1..])?
+                    }
+                    None => {
+                        let c = parse_keyword_char(part)?;
+                        c..=c
+                    }
+                };
+                Some((KeywordPart::Chars(range), included))
+            })
+            .collect::<Option<_>>()
+            .with_context(|| format!("invalid `iskeyword` value {spec:?}"))?;
+        Ok(Self { parts })
+    }
+}
+
+fn parse_keyword_char(part: &str) -> Option<char> {
+    if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+        return char::from_u32(part.parse().ok()?);
+    }
+    let mut chars = part.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+impl WordChars for Keyword {
+    fn is_word(&self, c: char) -> bool {
+        if c as u32 > 255 {
+            return c.is_alphanumeric() || c == '_';
+        }
+        self.parts
+            .iter()
+            .rev()
+            .find(|(part, _)| match part {
+                KeywordPart::Letters => c.is_alphabetic(),
+                KeywordPart::Chars(range) => range.contains(&c),
+            })
+            .is_some_and(|(_, included)| *included)
+    }
+}
+
+/// Classifies characters for the word motions, with `iskeyword` replacing the language's
+/// word characters when it's set. The language scope at `point` can differ from the next
+/// word's (e.g. inside an injected language), so motions classify from each start point.
+fn word_classifier(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    ignore_punctuation: bool,
+    keyword: Option<&Arc<Keyword>>,
+) -> CharClassifier {
+    map.buffer_snapshot
+        .char_classifier_at(point.to_point(map))
+        .ignore_punctuation(ignore_punctuation)
+        .word_chars(keyword.map(|keyword| keyword.clone() as Arc<dyn WordChars>))
+}
+
 pub(crate) fn next_word_start(
     map: &DisplaySnapshot,
     mut point: DisplayPoint,
     ignore_punctuation: bool,
+    keyword: Option<&Arc<Keyword>>,
     times: usize,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
     for _ in 0..times {
+        let classifier = word_classifier(map, point, ignore_punctuation, keyword);
         let mut crossed_newline = false;
         let new_point = movement::find_boundary(map, point, FindRange::MultiLine, |left, right| {
             let left_kind = classifier.kind(left);
@@ -1585,17 +1959,28 @@ pub(crate) fn next_word_start(
     point
 }
 
+/// Counts from which `next_word_end` finds its word ends in a single pass over the buffer.
+const FAST_PATH_THRESHOLD: usize = 10;
+
 pub(crate) fn next_word_end(
     map: &DisplaySnapshot,
     mut point: DisplayPoint,
     ignore_punctuation: bool,
-    times: usize,
+    keyword: Option<&Arc<Keyword>>,
+    mut times: usize,
     allow_cross_newline: bool,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
+    let classifier = word_classifier(map, point, ignore_punctuation, keyword);
+    if allow_cross_newline
+        && times >= FAST_PATH_THRESHOLD
+        && map.fold_snapshot.max_point().row() == map.buffer_snapshot.max_point().row
+    {
+        let word_ends = word_ends_after(map, point, &classifier, times);
+        if let Some(&offset) = word_ends.last() {
+            point = map.clip_point(offset.to_display_point(map), Bias::Left);
+            times -= word_ends.len();
+        }
+    }
     for _ in 0..times {
         let new_point = next_char(map, point, allow_cross_newline);
         let mut need_next_char = false;
@@ -1630,16 +2015,40 @@ pub(crate) fn next_word_end(
     point
 }
 
+/// Collects the offsets of up to `count` word ends after `point`, the same ends that repeated
+/// single steps of `next_word_end` would land on. Running out of buffer yields fewer.
+fn word_ends_after(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    classifier: &CharClassifier,
+    count: usize,
+) -> Vec<usize> {
+    let start = point.to_offset(map, Bias::Right);
+    let mut word_ends = Vec::new();
+    let mut chars = map.buffer_chars_at(start).peekable();
+    while let Some((ch, offset)) = chars.next() {
+        let Some(&(next_ch, _)) = chars.peek() else {
+            break;
+        };
+        let kind = classifier.kind(ch);
+        if offset > start && kind != CharKind::Whitespace && kind != classifier.kind(next_ch) {
+            word_ends.push(offset);
+            if word_ends.len() == count {
+                break;
+            }
+        }
+    }
+    word_ends
+}
+
 fn previous_word_start(
     map: &DisplaySnapshot,
     mut point: DisplayPoint,
     ignore_punctuation: bool,
+    keyword: Option<&Arc<Keyword>>,
     times: usize,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
+    let classifier = word_classifier(map, point, ignore_punctuation, keyword);
     for _ in 0..times {
         // This works even though find_preceding_boundary is called for every character in the line containing
         // cursor because the newline is checked only once.
@@ -1666,12 +2075,10 @@ fn previous_word_end(
     map: &DisplaySnapshot,
     point: DisplayPoint,
     ignore_punctuation: bool,
+    keyword: Option<&Arc<Keyword>>,
     times: usize,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
+    let classifier = word_classifier(map, point, ignore_punctuation, keyword);
     let mut point = point.to_point(map);
 
     if point.column < map.buffer_snapshot.line_len(MultiBufferRow(point.row)) {
@@ -1709,11 +2116,8 @@ fn next_subword_start(
     ignore_punctuation: bool,
     times: usize,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
     for _ in 0..times {
+        let classifier = word_classifier(map, point, ignore_punctuation, None);
         let mut crossed_newline = false;
         let new_point = movement::find_boundary(map, point, FindRange::MultiLine, |left, right| {
             let left_kind = classifier.kind(left);
@@ -1797,11 +2201,8 @@ fn previous_subword_start(
     ignore_punctuation: bool,
     times: usize,
 ) -> DisplayPoint {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(point.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
     for _ in 0..times {
+        let classifier = word_classifier(map, point, ignore_punctuation, None);
         let mut crossed_newline = false;
         // This works even though find_preceding_boundary is called for every character in the line containing
         // cursor because the newline is checked only once.
@@ -1906,10 +2307,13 @@ pub(crate) fn first_non_whitespace(
 
 pub(crate) fn last_non_whitespace(
     map: &DisplaySnapshot,
+    display_lines: bool,
     from: DisplayPoint,
     count: usize,
 ) -> DisplayPoint {
-    let mut end_of_line = end_of_line(map, false, from, count).to_offset(map, Bias::Left);
+    let line_end = end_of_line(map, display_lines, from, count);
+    let line_start = start_of_line(map, display_lines, line_end).to_offset(map, Bias::Left);
+    let mut end_of_line = line_end.to_offset(map, Bias::Left);
     let classifier = map.buffer_snapshot.char_classifier_at(from.to_point(map));
 
     // NOTE: depending on clip_at_line_end we may already be one char back from the end.
@@ -1920,7 +2324,7 @@ pub(crate) fn last_non_whitespace(
     }
 
     for (ch, offset) in map.reverse_buffer_chars_at(end_of_line) {
-        if ch == '\n' {
+        if ch == '\n' || offset < line_start {
             break;
         }
         end_of_line = offset;
@@ -1951,7 +2355,12 @@ pub(crate) fn end_of_line(
     times: usize,
 ) -> DisplayPoint {
     if times > 1 {
-        point = start_of_relative_buffer_row(map, point, times as isize - 1);
+        point = if display_lines {
+            let row = (point.row().0 + times as u32 - 1).min(map.max_point().row().0);
+            map.clip_point(DisplayPoint::new(DisplayRow(row), 0), Bias::Right)
+        } else {
+            start_of_relative_buffer_row(map, point, times as isize - 1)
+        };
     }
     if display_lines {
         map.clip_point(
@@ -1967,6 +2376,7 @@ pub(crate) fn sentence_backwards(
     map: &DisplaySnapshot,
     point: DisplayPoint,
     mut times: usize,
+    skip_abbreviations: bool,
 ) -> DisplayPoint {
     let mut start = point.to_point(map).to_offset(&map.buffer_snapshot);
     let mut chars = map.reverse_buffer_chars_at(start).peekable();
@@ -1981,7 +2391,9 @@ pub(crate) fn sentence_backwards(
             Some(offset + ch.len_utf8())
         } else if ch == '\n' && chars.peek().is_some_and(|(c, _)| *c == '\n') {
             Some(next_non_blank(map, offset + ch.len_utf8()))
-        } else if ch == '.' || ch == '?' || ch == '!' {
+        } else if (ch == '.' || ch == '?' || ch == '!')
+            && (!skip_abbreviations || !ends_abbreviation(map, ch, offset))
+        {
             start_of_next_sentence(map, offset + ch.len_utf8())
         } else {
             None
@@ -2013,6 +2425,7 @@ pub(crate) fn sentence_forwards(
     map: &DisplaySnapshot,
     point: DisplayPoint,
     mut times: usize,
+    skip_abbreviations: bool,
 ) -> DisplayPoint {
     let start = point.to_point(map).to_offset(&map.buffer_snapshot);
     let mut chars = map.buffer_chars_at(start).peekable();
@@ -2031,13 +2444,20 @@ pub(crate) fn sentence_forwards(
             Some(next_non_blank(map, offset))
         } else if ch == '\n' && chars.peek().is_some_and(|(c, _)| *c == '\n') {
             Some(next_non_blank(map, offset + ch.len_utf8()))
-        } else if ch == '.' || ch == '?' || ch == '!' {
+        } else if (ch == '.' || ch == '?' || ch == '!')
+            && (!skip_abbreviations || !ends_abbreviation(map, ch, offset))
+        {
             start_of_next_sentence(map, offset + ch.len_utf8())
         } else {
             None
         };
 
         if let Some(start_of_next_sentence) = start_of_next_sentence {
+            // A sentence that runs into the end of the buffer has no successor to
+            // land on, so it only ever counts once and we stop at the end.
+            if start_of_next_sentence >= map.buffer_snapshot.len() {
+                break;
+            }
             times = times.saturating_sub(1);
             if times == 0 {
                 return map.clip_point(
@@ -2052,7 +2472,47 @@ pub(crate) fn sentence_forwards(
         was_newline = ch == '\n' && chars.peek().is_some_and(|(c, _)| *c == '\n');
     }
 
-    map.max_point()
+    map.clip_point(map.buffer_snapshot.len().to_display_point(map), Bias::Left)
+}
+
+// Enough characters before a period to see the longest abbreviation and the character
+// separating it from the previous word.
+const SENTENCE_ABBREVIATION_CONTEXT: usize = 4;
+const SENTENCE_ABBREVIATIONS: &[&str] = &["Mr", "Mrs", "Dr", "Jr", "Sr", "vs", "etc", "e.g", "i.e"];
+
+/// Whether the `.`, `!` or `?` at `terminator_offset` in `left_context` ends a sentence,
+/// rather than an abbreviation like "Mr." or "e.g.". Like vim, this is only a heuristic.
+pub(crate) fn is_sentence_boundary(left_context: &str, terminator_offset: usize) -> bool {
+    let (Some(before), Some(terminator)) = (
+        left_context.get(..terminator_offset),
+        left_context.get(terminator_offset..),
+    ) else {
+        return true;
+    };
+    if !terminator.starts_with('.') {
+        return true;
+    }
+    let word = before
+        .rsplit(|ch: char| !ch.is_alphanumeric() && ch != '.')
+        .next()
+        .unwrap_or_default();
+    !SENTENCE_ABBREVIATIONS
+        .iter()
+        .any(|abbreviation| word.eq_ignore_ascii_case(abbreviation))
+}
+
+fn ends_abbreviation(map: &DisplaySnapshot, terminator: char, offset: usize) -> bool {
+    let mut left_context = map
+        .reverse_buffer_chars_at(offset)
+        .take(SENTENCE_ABBREVIATION_CONTEXT)
+        .map(|(ch, _)| ch)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<String>();
+    let terminator_offset = left_context.len();
+    left_context.push(terminator);
+    !is_sentence_boundary(&left_context, terminator_offset)
 }
 
 fn next_non_blank(map: &DisplaySnapshot, start: usize) -> usize {
@@ -2148,15 +2608,8 @@ fn start_of_document(
         return go_to_line(map, display_point, times);
     }
 
-    let point = map.display_point_to_point(display_point, Bias::Left);
-    let mut first_point = Point::zero();
-    first_point.column = point.column;
-
     map.clip_point(
-        map.point_to_display_point(
-            map.buffer_snapshot.clip_point(first_point, Bias::Left),
-            Bias::Left,
-        ),
+        map.point_to_display_point(Point::zero(), Bias::Left),
         Bias::Left,
     )
 }
@@ -2167,18 +2620,16 @@ fn end_of_document(
     maybe_times: Option<usize>,
 ) -> DisplayPoint {
     if let Some(times) = maybe_times {
-        return go_to_line(map, display_point, times);
+        return first_non_whitespace(map, false, go_to_line(map, display_point, times));
     };
-    let point = map.display_point_to_point(display_point, Bias::Left);
-    let mut last_point = map.buffer_snapshot.max_point();
-    last_point.column = point.column;
-
-    map.clip_point(
-        map.point_to_display_point(
-            map.buffer_snapshot.clip_point(last_point, Bias::Left),
+    let last_line_start = Point::new(map.buffer_snapshot.max_point().row, 0);
+    first_non_whitespace(
+        map,
+        false,
+        map.clip_point(
+            map.point_to_display_point(last_line_start, Bias::Left),
             Bias::Left,
         ),
-        Bias::Left,
     )
 }
 
@@ -2207,12 +2658,77 @@ fn matching_tag(map: &DisplaySnapshot, head: DisplayPoint) -> Option<DisplayPoin
     return None;
 }
 
-fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint {
+/// Cycles through the keywords of the construct under the cursor (e.g. `if` → `elif` →
+/// `else` → `fi` → `if`), as configured by the language's `match_words`.
+fn matching_keyword(map: &DisplaySnapshot, offset: usize) -> Option<usize> {
+    let mut excerpt = map.buffer_snapshot.excerpt_containing(offset..offset)?;
+    let buffer = excerpt.buffer();
+    let buffer_offset = excerpt.map_offset_to_buffer(offset);
+    if buffer_offset >= buffer.len() {
+        return None;
+    }
+    let language = buffer.language_at(buffer_offset)?;
+    let token = buffer.syntax_ancestor(buffer_offset..buffer_offset + 1)?;
+    if token.child_count() != 0 {
+        return None;
+    }
+    let words = language
+        .config()
+        .match_words
+        .iter()
+        .find(|words| words.contains(token.kind()))?;
+    let opens_construct = |node: language::Node| {
+        node.child(0)
+            .is_some_and(|child| words.start.iter().any(|word| word == child.kind()))
+    };
+
+    let mut construct = token.parent()?;
+    while !opens_construct(construct) {
+        construct = construct.parent()?;
+    }
+
+    // Collect the construct's own keywords, skipping over nested constructs of the same kind.
+    let mut keywords = Vec::new();
+    let mut cursor = construct.walk();
+    'outer: loop {
+        let node = cursor.node();
+        if node.child_count() == 0 && words.contains(node.kind()) {
+            keywords.push(node.byte_range());
+        }
+        if (node == construct || !opens_construct(node)) && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'outer;
+            }
+        }
+    }
+
+    let ix = keywords
+        .iter()
+        .position(|range| *range == token.byte_range())?;
+    let destination = keywords.get((ix + 1) % keywords.len())?;
+    if !excerpt.contains_buffer_range(destination.clone()) {
+        return None;
+    }
+    Some(excerpt.map_offset_from_buffer(destination.start))
+}
+
+fn matching(
+    map: &DisplaySnapshot,
+    display_point: DisplayPoint,
+    match_pairs: &[(char, char)],
+) -> DisplayPoint {
     // https://github.com/vim/vim/blob/1d87e11a1ef201b26ed87585fba70182ad0c468a/runtime/doc/motion.txt#L1200
     let display_point = map.clip_at_line_end(display_point);
     let point = display_point.to_point(map);
     let offset = point.to_offset(&map.buffer_snapshot);
 
+    if let Some(destination) = matching_keyword(map, offset) {
+        return destination.to_display_point(map);
+    }
+
     // Ensure the range is contained by the current line.
     let mut line_end = map.next_line_boundary(point).0;
     if line_end == point {
@@ -2220,11 +2736,16 @@ fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint
     }
 
     let line_range = map.prev_line_boundary(point).0..line_end;
+    let line_end_offset = line_end.to_offset(&map.buffer_snapshot);
+    let contexts = BracketContexts::new(map, offset);
+    let context = contexts.at(offset);
     let visible_line_range =
         line_range.start..Point::new(line_range.end.row, line_range.end.column.saturating_sub(1));
+    // Tree-sitter only reports brackets that are part of the code.
     let ranges = map
         .buffer_snapshot
-        .bracket_ranges(visible_line_range.clone());
+        .bracket_ranges(visible_line_range.clone())
+        .filter(|_| context == BracketContext::Code);
     if let Some(ranges) = ranges {
         let line_range = line_range.start.to_offset(&map.buffer_snapshot)
             ..line_range.end.to_offset(&map.buffer_snapshot);
@@ -2273,11 +2794,181 @@ fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint
             continue;
         }
 
-        closest_pair_destination
-            .map(|destination| destination.to_display_point(map))
-            .unwrap_or(display_point)
-    } else {
-        display_point
+        if let Some(destination) = closest_pair_destination {
+            return destination.to_display_point(map);
+        }
+    }
+
+    match_pair_destination(&contexts, offset, line_end_offset, match_pairs, context)
+        .map(|destination| destination.to_display_point(map))
+        .unwrap_or(display_point)
+}
+
+// Bounds the plain-text scan for `matchpairs` so that a stray bracket in a large buffer
+// doesn't walk the whole file.
+const MATCH_PAIR_SCAN_LIMIT: usize = 10_000;
+
+/// Finds the partner of the first `matchpairs` character at or after `offset` on the
+/// current line, for pairs that tree-sitter doesn't report as brackets.
+fn match_pair_destination(
+    contexts: &BracketContexts,
+    offset: usize,
+    line_end: usize,
+    match_pairs: &[(char, char)],
+    context: BracketContext,
+) -> Option<usize> {
+    let map = contexts.map;
+    let mut start = offset;
+    let mut found = None;
+    for ch in map.buffer_snapshot.chars_at(offset) {
+        if start >= line_end {
+            break;
+        }
+        if let Some(pair) = match_pairs
+            .iter()
+            .find(|(open, close)| *open == ch || *close == ch)
+        {
+            if contexts.at(start) == context {
+                found = Some((ch, *pair));
+                break;
+            }
+        }
+        start += ch.len_utf8();
+    }
+
+    let (ch, (open, close)) = found?;
+    let scope = bracket_scan_scope(map, start);
+    if ch == open {
+        let from = start + open.len_utf8();
+        let to = scope.end.min(from + MATCH_PAIR_SCAN_LIMIT);
+        unmatched_close_offset(contexts, from..to, open, close, context, 1)
+    } else {
+        let from = scope.start.max(start.saturating_sub(MATCH_PAIR_SCAN_LIMIT));
+        unmatched_open_offset(contexts, from..start, open, close, context, 1)
+    }
+}
+
+/// Scans `range` for the `times`-th closing bracket that isn't matched within it,
+/// counting only brackets in `context`. Returns the furthest one found.
+fn unmatched_close_offset(
+    contexts: &BracketContexts,
+    range: Range<usize>,
+    open: char,
+    close: char,
+    context: BracketContext,
+    mut times: usize,
+) -> Option<usize> {
+    let mut depth = 0;
+    let mut offset = range.start;
+    let mut found = None;
+    for ch in contexts.map.buffer_snapshot.chars_at(range.start) {
+        if offset >= range.end {
+            break;
+        }
+        let len = ch.len_utf8();
+        if (ch == open || ch == close) && contexts.at(offset) == context {
+            if ch == open {
+                depth += 1;
+            } else if depth > 0 {
+                depth -= 1;
+            } else {
+                found = Some(offset);
+                times = times.saturating_sub(1);
+                if times == 0 {
+                    break;
+                }
+            }
+        }
+        offset += len;
+    }
+    found
+}
+
+/// Scans `range` backwards for the `times`-th opening bracket that isn't matched
+/// within it, counting only brackets in `context`. Returns the furthest one found.
+fn unmatched_open_offset(
+    contexts: &BracketContexts,
+    range: Range<usize>,
+    open: char,
+    close: char,
+    context: BracketContext,
+    mut times: usize,
+) -> Option<usize> {
+    let mut depth = 0;
+    let mut offset = range.end;
+    let mut found = None;
+    for ch in contexts.map.buffer_snapshot.reversed_chars_at(range.end) {
+        if offset <= range.start {
+            break;
+        }
+        offset -= ch.len_utf8();
+        if (ch == open || ch == close) && contexts.at(offset) == context {
+            if ch == close {
+                depth += 1;
+            } else if depth > 0 {
+                depth -= 1;
+            } else {
+                found = Some(offset);
+                times = times.saturating_sub(1);
+                if times == 0 {
+                    break;
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Whether a character is in code, a string or a comment. Bracket motions only pair
+/// brackets within the same kind of context.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BracketContext {
+    Code,
+    String,
+    Comment,
+}
+
+/// Resolves the [`BracketContext`] of characters for a single motion. Whether the buffer
+/// has a grammar is looked up once, and without one every character is code.
+struct BracketContexts<'a> {
+    map: &'a DisplaySnapshot,
+    has_syntax: bool,
+}
+
+impl<'a> BracketContexts<'a> {
+    fn new(map: &'a DisplaySnapshot, offset: usize) -> Self {
+        let has_syntax = map
+            .buffer_snapshot
+            .language_at(offset)
+            .is_some_and(|language| language.grammar().is_some());
+        Self { map, has_syntax }
+    }
+
+    fn at(&self, offset: usize) -> BracketContext {
+        if !self.has_syntax {
+            return BracketContext::Code;
+        }
+        let context_at = |offset: usize| {
+            let scope = self.map.buffer_snapshot.language_scope_at(offset)?;
+            match scope.override_name()? {
+                "string" => Some(BracketContext::String),
+                "comment" => Some(BracketContext::Comment),
+                _ => None,
+            }
+        };
+        // Check the positions on both sides of the character, so that the quotes delimiting a
+        // string count as code.
+        let next_offset = offset
+            + self
+                .map
+                .buffer_snapshot
+                .chars_at(offset)
+                .next()
+                .map_or(0, |ch| ch.len_utf8());
+        match (context_at(offset), context_at(next_offset)) {
+            (Some(context), Some(next_context)) if context == next_context => context,
+            _ => BracketContext::Code,
+        }
     }
 }
 
@@ -2299,88 +2990,127 @@ fn go_to_percentage(map: &DisplaySnapshot, point: DisplayPoint, count: usize) ->
 
 fn unmatched_forward(
     map: &DisplaySnapshot,
-    mut display_point: DisplayPoint,
+    display_point: DisplayPoint,
     char: char,
+    match_pairs: &[(char, char)],
     times: usize,
 ) -> DisplayPoint {
-    for _ in 0..times {
-        // https://github.com/vim/vim/blob/1d87e11a1ef201b26ed87585fba70182ad0c468a/runtime/doc/motion.txt#L1245
-        let point = display_point.to_point(map);
-        let offset = point.to_offset(&map.buffer_snapshot);
-
-        let ranges = map.buffer_snapshot.enclosing_bracket_ranges(point..point);
-        let Some(ranges) = ranges else { break };
-        let mut closest_closing_destination = None;
-        let mut closest_distance = usize::MAX;
-
-        for (_, close_range) in ranges {
-            if close_range.start > offset {
-                let mut chars = map.buffer_snapshot.chars_at(close_range.start);
-                if Some(char) == chars.next() {
-                    let distance = close_range.start - offset;
-                    if distance < closest_distance {
-                        closest_closing_destination = Some(close_range.start);
-                        closest_distance = distance;
-                        continue;
-                    }
-                }
-            }
-        }
-
-        let new_point = closest_closing_destination
-            .map(|destination| destination.to_display_point(map))
-            .unwrap_or(display_point);
-        if new_point == display_point {
-            break;
+    // https://github.com/vim/vim/blob/1d87e11a1ef201b26ed87585fba70182ad0c468a/runtime/doc/motion.txt#L1245
+    let Some((open, close)) = unmatched_pair(char, match_pairs) else {
+        return display_point;
+    };
+    let offset = display_point.to_point(map).to_offset(&map.buffer_snapshot);
+    let contexts = BracketContexts::new(map, offset);
+    let context = contexts.at(offset);
+    let mut pairs = enclosing_pairs(map, offset, open, close);
+    pairs.retain(|(_, close_range)| close_range.start > offset);
+    let destination = match pairs.get(times.saturating_sub(1)).or(pairs.last()) {
+        Some((_, close_range)) if context == BracketContext::Code => Some(close_range.start),
+        _ => {
+            let next_offset = offset
+                + map
+                    .buffer_snapshot
+                    .chars_at(offset)
+                    .next()
+                    .map_or(0, |ch| ch.len_utf8());
+            let scope = bracket_scan_scope(map, offset);
+            unmatched_close_offset(&contexts, next_offset..scope.end, open, close, context, times)
         }
-        display_point = new_point;
-    }
-    return display_point;
+    };
+    destination
+        .map(|destination| destination.to_display_point(map))
+        .unwrap_or(display_point)
 }
 
 fn unmatched_backward(
     map: &DisplaySnapshot,
-    mut display_point: DisplayPoint,
+    display_point: DisplayPoint,
     char: char,
+    match_pairs: &[(char, char)],
     times: usize,
 ) -> DisplayPoint {
-    for _ in 0..times {
-        // https://github.com/vim/vim/blob/1d87e11a1ef201b26ed87585fba70182ad0c468a/runtime/doc/motion.txt#L1239
-        let point = display_point.to_point(map);
-        let offset = point.to_offset(&map.buffer_snapshot);
-
-        let ranges = map.buffer_snapshot.enclosing_bracket_ranges(point..point);
-        let Some(ranges) = ranges else {
-            break;
-        };
+    // https://github.com/vim/vim/blob/1d87e11a1ef201b26ed87585fba70182ad0c468a/runtime/doc/motion.txt#L1239
+    let Some((open, close)) = unmatched_pair(char, match_pairs) else {
+        return display_point;
+    };
+    let offset = display_point.to_point(map).to_offset(&map.buffer_snapshot);
+    let contexts = BracketContexts::new(map, offset);
+    let context = contexts.at(offset);
+    let mut pairs = enclosing_pairs(map, offset, open, close);
+    pairs.retain(|(open_range, _)| open_range.start < offset);
+    let destination = match pairs.get(times.saturating_sub(1)).or(pairs.last()) {
+        Some((open_range, _)) if context == BracketContext::Code => Some(open_range.start),
+        _ => {
+            let scope = bracket_scan_scope(map, offset);
+            unmatched_open_offset(&contexts, scope.start..offset, open, close, context, times)
+        }
+    };
+    destination
+        .map(|destination| destination.to_display_point(map))
+        .unwrap_or(display_point)
+}
 
-        let mut closest_starting_destination = None;
-        let mut closest_distance = usize::MAX;
+/// The tree-sitter bracket pairs delimited by `open` and `close` that enclose `offset`,
+/// innermost first. Tree-sitter already leaves out brackets in strings and comments.
+fn enclosing_pairs(
+    map: &DisplaySnapshot,
+    offset: usize,
+    open: char,
+    close: char,
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let Some(pairs) = map.buffer_snapshot.enclosing_bracket_ranges(offset..offset) else {
+        return Vec::new();
+    };
+    let mut pairs = pairs
+        .filter(|(open_range, close_range)| {
+            open_range.start <= offset
+                && offset <= close_range.start
+                && map.buffer_snapshot.chars_at(open_range.start).next() == Some(open)
+                && map.buffer_snapshot.chars_at(close_range.start).next() == Some(close)
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_by(|(a, _), (b, _)| b.start.cmp(&a.start));
+    pairs.dedup();
+    pairs
+}
 
-        for (start_range, _) in ranges {
-            if start_range.start < offset {
-                let mut chars = map.buffer_snapshot.chars_at(start_range.start);
-                if Some(char) == chars.next() {
-                    let distance = offset - start_range.start;
-                    if distance < closest_distance {
-                        closest_starting_destination = Some(start_range.start);
-                        closest_distance = distance;
-                        continue;
-                    }
-                }
-            }
-        }
+/// `])` and `]}` always pair the usual brackets, other characters are looked up in
+/// `matchpairs`.
+fn unmatched_pair(char: char, match_pairs: &[(char, char)]) -> Option<(char, char)> {
+    match char {
+        '(' | ')' => Some(('(', ')')),
+        '{' | '}' => Some(('{', '}')),
+        _ => match_pairs
+            .iter()
+            .copied()
+            .find(|&(open, close)| open == char || close == char),
+    }
+}
 
-        let new_point = closest_starting_destination
-            .map(|destination| destination.to_display_point(map))
-            .unwrap_or(display_point);
-        if new_point == display_point {
-            break;
-        } else {
-            display_point = new_point;
-        }
+/// The range to scan for brackets around `offset`: the outermost tree-sitter bracket pair
+/// enclosing it, or else the excerpt containing it. Brackets in other excerpts belong to
+/// unrelated code, so scans never cross it.
+fn bracket_scan_scope(map: &DisplaySnapshot, offset: usize) -> Range<usize> {
+    let outermost_pair = map
+        .buffer_snapshot
+        .enclosing_bracket_ranges(offset..offset)
+        .and_then(|pairs| {
+            pairs
+                .filter(|(open_range, close_range)| {
+                    open_range.end <= offset && offset < close_range.start
+                })
+                .map(|(open_range, close_range)| open_range.start..close_range.end)
+                .max_by_key(|range| range.len())
+        });
+    if let Some(range) = outermost_pair {
+        return range;
     }
-    display_point
+    let Some(mut excerpt) = map.buffer_snapshot.excerpt_containing(offset..offset) else {
+        return 0..map.buffer_snapshot.len();
+    };
+    let buffer_range = excerpt.buffer_range();
+    excerpt.map_offset_from_buffer(buffer_range.start)
+        ..excerpt.map_offset_from_buffer(buffer_range.end)
 }
 
 fn find_forward(
@@ -2549,7 +3279,21 @@ fn previous_line_start(map: &DisplaySnapshot, point: DisplayPoint, times: usize)
 
 fn go_to_column(map: &DisplaySnapshot, point: DisplayPoint, times: usize) -> DisplayPoint {
     let correct_line = start_of_relative_buffer_row(map, point, 0);
-    right(map, correct_line, times.saturating_sub(1))
+    let row = correct_line.row();
+    // The count is a screen column, so full-width characters take up two of them. Tabs are
+    // already expanded in the display line.
+    let target = times.saturating_sub(1);
+    let mut width = 0;
+    let mut column = 0;
+    for ch in map.line(row).chars() {
+        width += ch.width().unwrap_or(0);
+        // Columns in the middle of a wide character select that character.
+        if width > target {
+            break;
+        }
+        column += ch.len_utf8();
+    }
+    map.clip_point(DisplayPoint::new(row, column as u32), Bias::Left)
 }
 
 pub(crate) fn next_line_end(
@@ -2565,7 +3309,6 @@ pub(crate) fn next_line_end(
 
 fn window_top(
     map: &DisplaySnapshot,
-    point: DisplayPoint,
     text_layout_details: &TextLayoutDetails,
     mut times: usize,
 ) -> (DisplayPoint, SelectionGoal) {
@@ -2574,10 +3317,9 @@ fn window_top(
         .anchor
         .to_display_point(map);
 
-    if first_visible_line.row() != DisplayRow(0)
-        && text_layout_details.vertical_scroll_margin as usize > times
-    {
-        times = text_layout_details.vertical_scroll_margin.ceil() as usize;
+    // The margin applies to explicit counts too, so `1H` lands where `H` does.
+    if first_visible_line.row() != DisplayRow(0) {
+        times = times.max(text_layout_details.vertical_scroll_margin.ceil() as usize);
     }
 
     if let Some(visible_rows) = text_layout_details.visible_rows {
@@ -2585,17 +3327,13 @@ fn window_top(
         let new_row = (first_visible_line.row().0 + (times as u32))
             .min(bottom_row)
             .min(map.max_point().row().0);
-        let new_col = point.column().min(map.line_len(first_visible_line.row()));
-
-        let new_point = DisplayPoint::new(DisplayRow(new_row), new_col);
-        (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
+        let new_point = DisplayPoint::new(DisplayRow(new_row), 0);
+        (first_non_whitespace(map, false, new_point), SelectionGoal::None)
     } else {
         let new_row =
             DisplayRow((first_visible_line.row().0 + (times as u32)).min(map.max_point().row().0));
-        let new_col = point.column().min(map.line_len(first_visible_line.row()));
-
-        let new_point = DisplayPoint::new(new_row, new_col);
-        (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
+        let new_point = DisplayPoint::new(new_row, 0);
+        (first_non_whitespace(map, false, new_point), SelectionGoal::None)
     }
 }
 
@@ -2604,24 +3342,13 @@ fn window_middle(
     point: DisplayPoint,
     text_layout_details: &TextLayoutDetails,
 ) -> (DisplayPoint, SelectionGoal) {
-    if let Some(visible_rows) = text_layout_details.visible_rows {
-        let first_visible_line = text_layout_details
-            .scroll_anchor
-            .anchor
-            .to_display_point(map);
-
-        let max_visible_rows =
-            (visible_rows as u32).min(map.max_point().row().0 - first_visible_line.row().0);
+    let Some((first_row, last_row)) = visible_display_rows(map, text_layout_details) else {
+        return (point, SelectionGoal::None);
+    };
 
-        let new_row =
-            (first_visible_line.row().0 + (max_visible_rows / 2)).min(map.max_point().row().0);
-        let new_row = DisplayRow(new_row);
-        let new_col = point.column().min(map.line_len(new_row));
-        let new_point = DisplayPoint::new(new_row, new_col);
-        (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
-    } else {
-        (point, SelectionGoal::None)
-    }
+    let new_row = DisplayRow(first_row.0 + (last_row.0 - first_row.0) / 2);
+    let new_point = DisplayPoint::new(new_row, 0);
+    (first_non_whitespace(map, false, new_point), SelectionGoal::None)
 }
 
 fn window_bottom(
@@ -2630,31 +3357,36 @@ fn window_bottom(
     text_layout_details: &TextLayoutDetails,
     mut times: usize,
 ) -> (DisplayPoint, SelectionGoal) {
-    if let Some(visible_rows) = text_layout_details.visible_rows {
-        let first_visible_line = text_layout_details
-            .scroll_anchor
-            .anchor
-            .to_display_point(map);
-        let bottom_row = first_visible_line.row().0
-            + (visible_rows + text_layout_details.scroll_anchor.offset.y - 1.).floor() as u32;
-        if bottom_row < map.max_point().row().0
-            && text_layout_details.vertical_scroll_margin as usize > times
-        {
-            times = text_layout_details.vertical_scroll_margin.ceil() as usize;
-        }
-        let bottom_row_capped = bottom_row.min(map.max_point().row().0);
-        let new_row = if bottom_row_capped.saturating_sub(times as u32) < first_visible_line.row().0
-        {
-            first_visible_line.row()
-        } else {
-            DisplayRow(bottom_row_capped.saturating_sub(times as u32))
-        };
-        let new_col = point.column().min(map.line_len(new_row));
-        let new_point = DisplayPoint::new(new_row, new_col);
-        (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
-    } else {
-        (point, SelectionGoal::None)
+    let Some((first_row, last_row)) = visible_display_rows(map, text_layout_details) else {
+        return (point, SelectionGoal::None);
+    };
+
+    if last_row < map.max_point().row() {
+        times = times.max(text_layout_details.vertical_scroll_margin.ceil() as usize);
     }
+    let new_row = DisplayRow(last_row.0.saturating_sub(times as u32).max(first_row.0));
+    let new_point = DisplayPoint::new(new_row, 0);
+    (first_non_whitespace(map, false, new_point), SelectionGoal::None)
+}
+
+/// Returns the first and last display rows in the window, ignoring a partially visible last
+/// row. Rows are display rows, so a fold counts once, and the range stops at the end of the
+/// buffer when it is shorter than the window.
+fn visible_display_rows(
+    map: &DisplaySnapshot,
+    text_layout_details: &TextLayoutDetails,
+) -> Option<(DisplayRow, DisplayRow)> {
+    let visible_rows = text_layout_details.visible_rows?;
+    let first_row = text_layout_details
+        .scroll_anchor
+        .anchor
+        .to_display_point(map)
+        .row();
+    let last_row = first_row.0
+        + (visible_rows + text_layout_details.scroll_anchor.offset.y - 1.)
+            .floor()
+            .max(0.) as u32;
+    Some((first_row, DisplayRow(last_row.min(map.max_point().row().0))))
 }
 
 fn method_motion(
@@ -2684,10 +3416,15 @@ fn method_motion(
                     return None;
                 }
 
-                let relevant = if is_start { range.start } else { range.end };
+                // `range.end` is past the closing brace, which the cursor should land on.
+                let relevant = if is_start {
+                    range.start
+                } else {
+                    buffer.clip_offset(range.end.saturating_sub(1), Bias::Left)
+                };
                 if direction == Direction::Prev && relevant < offset {
                     Some(relevant)
-                } else if direction == Direction::Next && relevant > offset + 1 {
+                } else if direction == Direction::Next && relevant > offset {
                     Some(relevant)
                 } else {
                     None
@@ -2714,55 +3451,212 @@ fn comment_motion(
     times: usize,
     direction: Direction,
 ) -> DisplayPoint {
-    let Some((_, _, buffer)) = map.buffer_snapshot.as_singleton() else {
-        return display_point;
-    };
+    let comments = comment_blocks(map);
 
     for _ in 0..times {
         let point = map.display_point_to_point(display_point, Bias::Left);
         let offset = point.to_offset(&map.buffer_snapshot);
-        let range = if direction == Direction::Prev {
-            0..offset
+        let dest = if direction == Direction::Prev {
+            comments
+                .iter()
+                .rev()
+                .map(|range| range.start)
+                .find(|start| *start < offset)
         } else {
-            offset..buffer.len()
+            comments
+                .iter()
+                .map(|range| range.end)
+                .find(|end| *end > offset + 1)
+        };
+        let Some(dest) = dest else {
+            break;
         };
+        let new_point = map.clip_point(dest.to_display_point(map), Bias::Left);
+        if new_point == display_point {
+            break;
+        }
+        display_point = new_point;
+    }
 
-        let possibilities = buffer
-            .text_object_ranges(range, language::TreeSitterOptions::max_start_depth(6))
-            .filter_map(|(range, object)| {
-                if !matches!(object, language::TextObject::AroundComment) {
-                    return None;
-                }
+    display_point
+}
 
-                let relevant = if direction == Direction::Prev {
-                    range.start
-                } else {
-                    range.end
-                };
-                if direction == Direction::Prev && relevant < offset {
-                    Some(relevant)
-                } else if direction == Direction::Next && relevant > offset + 1 {
-                    Some(relevant)
-                } else {
-                    None
+/// Returns the comments in every excerpt in order. Line comments on consecutive lines are
+/// merged into a single block, so that the motions don't crawl through them line by line.
+fn comment_blocks(map: &DisplaySnapshot) -> Vec<Range<usize>> {
+    let mut comments = Vec::new();
+    for (excerpt_id, _, excerpt_range) in map.buffer_snapshot.excerpts() {
+        let Some(excerpt_start) = map
+            .buffer_snapshot
+            .anchor_in_excerpt(excerpt_id, excerpt_range.context.start)
+        else {
+            continue;
+        };
+        let Some(mut excerpt) = map
+            .buffer_snapshot
+            .excerpt_containing(excerpt_start..excerpt_start)
+        else {
+            continue;
+        };
+        let buffer = excerpt.buffer();
+        let mut ranges = buffer
+            .text_object_ranges(
+                excerpt.buffer_range(),
+                language::TreeSitterOptions::max_start_depth(6),
+            )
+            .filter(|(range, object)| {
+                *object == language::TextObject::AroundComment
+                    && excerpt.contains_buffer_range(range.clone())
+            })
+            .map(|(range, _)| {
+                // Some grammars include the trailing newline in a line comment.
+                let trailing_whitespace: usize = buffer
+                    .reversed_chars_at(range.end)
+                    .take_while(|ch| ch.is_whitespace())
+                    .map(|ch| ch.len_utf8())
+                    .sum();
+                range.start..range.end.saturating_sub(trailing_whitespace)
+            })
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut blocks: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            if let Some(block) = blocks.last_mut() {
+                if range.start < block.end
+                    || is_line_comment(buffer, block.start)
+                        && is_line_comment(buffer, range.start)
+                        && is_next_line(buffer, block.end, range.start)
+                {
+                    block.end = block.end.max(range.end);
+                    continue;
                 }
-            });
+            }
+            blocks.push(range);
+        }
+        comments.extend(blocks.into_iter().map(|block| {
+            excerpt.map_offset_from_buffer(block.start)..excerpt.map_offset_from_buffer(block.end)
+        }));
+    }
+    comments
+}
 
-        let dest = if direction == Direction::Prev {
-            possibilities.max().unwrap_or(offset)
+fn is_line_comment(buffer: &BufferSnapshot, offset: usize) -> bool {
+    buffer.language_scope_at(offset).is_some_and(|scope| {
+        scope
+            .line_comment_prefixes()
+            .iter()
+            .any(|prefix| buffer.contains_str_at(offset, prefix.trim_end()))
+    })
+}
+
+/// Whether `start` is on the line following `end`, with only whitespace between them.
+fn is_next_line(buffer: &BufferSnapshot, end: usize, start: usize) -> bool {
+    buffer.chars_for_range(end..start).all(char::is_whitespace)
+        && buffer.offset_to_point(start).row == buffer.offset_to_point(end).row + 1
+}
+
+// Whitespace-separated tokens longer than this are never treated as URLs, which keeps
+// the scan cheap on minified files. This matches the limit `editor::OpenUrl` uses.
+const URL_TOKEN_LIMIT: usize = 2048;
+
+fn url_motion(
+    map: &DisplaySnapshot,
+    mut display_point: DisplayPoint,
+    times: usize,
+    direction: Direction,
+) -> DisplayPoint {
+    for _ in 0..times {
+        let offset = map
+            .display_point_to_point(display_point, Bias::Left)
+            .to_offset(&map.buffer_snapshot);
+        let destination = if direction == Direction::Prev {
+            previous_url_start(map, offset)
         } else {
-            possibilities.min().unwrap_or(offset)
+            next_url_start(map, offset)
         };
-        let new_point = map.clip_point(dest.to_display_point(&map), Bias::Left);
-        if new_point == display_point {
+        let Some(destination) = destination else {
             break;
-        }
-        display_point = new_point;
+        };
+        display_point = map.clip_point(destination.to_display_point(map), Bias::Left);
     }
 
     display_point
 }
 
+fn next_url_start(map: &DisplaySnapshot, offset: usize) -> Option<usize> {
+    // Start from the beginning of the token under the cursor, so that a URL the
+    // cursor is already inside of is recognized (and skipped).
+    let mut token_start = offset;
+    for (ch, ch_offset) in map.reverse_buffer_chars_at(offset).take(URL_TOKEN_LIMIT) {
+        if ch.is_whitespace() {
+            break;
+        }
+        token_start = ch_offset;
+    }
+
+    let mut token = String::new();
+    for (ch, ch_offset) in map.buffer_chars_at(token_start) {
+        if !ch.is_whitespace() {
+            if token.is_empty() {
+                token_start = ch_offset;
+            }
+            if token.len() <= URL_TOKEN_LIMIT {
+                token.push(ch);
+            }
+            continue;
+        }
+        if let Some(url_start) = url_starts(&token, token_start).find(|start| *start > offset) {
+            return Some(url_start);
+        }
+        token.clear();
+    }
+    url_starts(&token, token_start).find(|start| *start > offset)
+}
+
+fn previous_url_start(map: &DisplaySnapshot, offset: usize) -> Option<usize> {
+    let mut token_end = offset;
+    for (ch, ch_offset) in map.buffer_chars_at(offset).take(URL_TOKEN_LIMIT) {
+        if ch.is_whitespace() {
+            break;
+        }
+        token_end = ch_offset + ch.len_utf8();
+    }
+
+    let mut reversed_token = String::new();
+    let mut token_start = token_end;
+    for (ch, ch_offset) in map.reverse_buffer_chars_at(token_end) {
+        if !ch.is_whitespace() {
+            token_start = ch_offset;
+            if reversed_token.len() <= URL_TOKEN_LIMIT {
+                reversed_token.push(ch);
+            }
+            continue;
+        }
+        let token = reversed_token.chars().rev().collect::<String>();
+        if let Some(url_start) = url_starts(&token, token_start)
+            .rev()
+            .find(|start| *start < offset)
+        {
+            return Some(url_start);
+        }
+        reversed_token.clear();
+    }
+    let token = reversed_token.chars().rev().collect::<String>();
+    url_starts(&token, token_start)
+        .rev()
+        .find(|start| *start < offset)
+}
+
+fn url_starts(token: &str, token_start: usize) -> impl DoubleEndedIterator<Item = usize> {
+    let ranges = if token.is_empty() || token.len() > URL_TOKEN_LIMIT {
+        Vec::new()
+    } else {
+        editor::url_ranges(token)
+    };
+    ranges.into_iter().map(move |range| token_start + range.start)
+}
+
 fn section_motion(
     map: &DisplaySnapshot,
     mut display_point: DisplayPoint,
@@ -2804,10 +3698,16 @@ fn section_motion(
                 }
                 prev_end = Some(range.end);
 
-                let relevant = if is_start { range.start } else { range.end };
+                // `range.end` is past the closing brace, which the cursor should land on.
+                let relevant = if is_start {
+                    range.start
+                } else {
+                    map.buffer_snapshot
+                        .clip_offset(range.end.saturating_sub(1), Bias::Left)
+                };
                 if direction == Direction::Prev && relevant < offset {
                     Some(relevant)
-                } else if direction == Direction::Next && relevant > offset + 1 {
+                } else if direction == Direction::Next && relevant > offset {
                     Some(relevant)
                 } else {
                     None
@@ -2844,38 +3744,206 @@ fn section_motion(
     display_point
 }
 
-fn matches_indent_type(
-    target_indent: &text::LineIndent,
-    current_indent: &text::LineIndent,
-    indent_type: IndentType,
-) -> bool {
-    match indent_type {
-        IndentType::Lesser => {
-            target_indent.spaces < current_indent.spaces || target_indent.tabs < current_indent.tabs
-        }
-        IndentType::Greater => {
-            target_indent.spaces > current_indent.spaces || target_indent.tabs > current_indent.tabs
+/// Returns the named syntax node that sibling and parent motions are relative to: the
+/// largest named node starting at `offset`, or the smallest one containing it. When `offset`
+/// is between nodes the nearest following node is returned, along with `true`.
+fn syntax_node_at(buffer: &BufferSnapshot, offset: usize) -> Option<(language::Node, bool)> {
+    let root = buffer.syntax_layer_at(offset)?.node();
+    let mut node = root;
+    let mut between_nodes = false;
+    loop {
+        let mut cursor = node.walk();
+        let Some(child) = node
+            .named_children(&mut cursor)
+            .find(|child| child.end_byte() > offset)
+        else {
+            break;
+        };
+        if child.start_byte() > offset {
+            let mut cursor = node.walk();
+            let on_token = node
+                .children(&mut cursor)
+                .any(|child| child.byte_range().contains(&offset));
+            if !on_token {
+                node = child;
+                between_nodes = true;
+            }
+            break;
         }
-        IndentType::Same => {
-            target_indent.spaces == current_indent.spaces
-                && target_indent.tabs == current_indent.tabs
+        node = child;
+    }
+
+    while let Some(parent) = node.parent() {
+        if parent == root || parent.start_byte() != node.start_byte() {
+            break;
         }
+        node = parent;
     }
+    (node != root).then_some((node, between_nodes))
 }
 
-fn indent_motion(
+fn sibling_motion(
     map: &DisplaySnapshot,
-    mut display_point: DisplayPoint,
+    display_point: DisplayPoint,
     times: usize,
     direction: Direction,
-    indent_type: IndentType,
 ) -> DisplayPoint {
-    let buffer_point = map.display_point_to_point(display_point, Bias::Left);
-    let current_row = MultiBufferRow(buffer_point.row);
-    let current_indent = map.line_indent_for_buffer_row(current_row);
-    if current_indent.is_line_empty() {
+    let offset = map
+        .display_point_to_point(display_point, Bias::Left)
+        .to_offset(&map.buffer_snapshot);
+    let Some(mut excerpt) = map.buffer_snapshot.excerpt_containing(offset..offset) else {
         return display_point;
-    }
+    };
+    let buffer = excerpt.buffer();
+    let Some((mut node, between_nodes)) =
+        syntax_node_at(buffer, excerpt.map_offset_to_buffer(offset))
+    else {
+        return display_point;
+    };
+
+    // Between nodes, the following node is the first step forward.
+    let mut moved = between_nodes && direction == Direction::Next;
+    let times = if moved { times.saturating_sub(1) } else { times };
+    for _ in 0..times {
+        let sibling = match direction {
+            Direction::Prev => node.prev_named_sibling(),
+            Direction::Next => node.next_named_sibling(),
+        };
+        let Some(sibling) = sibling else {
+            break;
+        };
+        if !excerpt.contains_buffer_range(sibling.start_byte()..sibling.start_byte()) {
+            break;
+        }
+        node = sibling;
+        moved = true;
+    }
+    if !moved || !excerpt.contains_buffer_range(node.start_byte()..node.start_byte()) {
+        return display_point;
+    }
+
+    let destination = excerpt.map_offset_from_buffer(node.start_byte());
+    map.clip_point(destination.to_display_point(map), Bias::Left)
+}
+
+fn parent_motion(
+    map: &DisplaySnapshot,
+    display_point: DisplayPoint,
+    times: usize,
+    is_start: bool,
+) -> DisplayPoint {
+    let offset = map
+        .display_point_to_point(display_point, Bias::Left)
+        .to_offset(&map.buffer_snapshot);
+    let Some(mut excerpt) = map.buffer_snapshot.excerpt_containing(offset..offset) else {
+        return display_point;
+    };
+    let buffer = excerpt.buffer();
+    let Some((mut node, _)) = syntax_node_at(buffer, excerpt.map_offset_to_buffer(offset)) else {
+        return display_point;
+    };
+
+    let boundary = |node: language::Node| {
+        if is_start {
+            node.start_byte()
+        } else {
+            let end = node.end_byte();
+            end - buffer
+                .reversed_chars_at(end)
+                .next()
+                .map_or(0, |ch| ch.len_utf8())
+        }
+    };
+    let mut destination = None;
+    for _ in 0..times {
+        // Skip ancestors sharing the current boundary so that each step moves the cursor.
+        let current = boundary(node);
+        let mut parent = node.parent();
+        while let Some(ancestor) = parent {
+            if boundary(ancestor) != current {
+                break;
+            }
+            parent = ancestor.parent();
+        }
+        // The root node spans the whole layer, so it isn't a useful destination.
+        let Some(parent) = parent.filter(|parent| parent.parent().is_some()) else {
+            break;
+        };
+        if !excerpt.contains_buffer_range(parent.byte_range()) {
+            break;
+        }
+        node = parent;
+        destination = Some(boundary(parent));
+    }
+
+    let Some(destination) = destination else {
+        return display_point;
+    };
+    let destination = excerpt.map_offset_from_buffer(destination);
+    map.clip_point(destination.to_display_point(map), Bias::Left)
+}
+
+fn excerpt_motion(
+    map: &DisplaySnapshot,
+    mut display_point: DisplayPoint,
+    times: usize,
+    direction: Direction,
+) -> DisplayPoint {
+    let excerpt_id = |display_point: DisplayPoint| {
+        let point = map.display_point_to_point(display_point, Bias::Left);
+        map.buffer_snapshot
+            .excerpt_containing(point..point)
+            .map(|excerpt| excerpt.id())
+    };
+
+    for _ in 0..times {
+        let next_point = movement::start_of_excerpt(map, display_point, direction);
+        // Past the last excerpt there is nowhere to go, but `start_of_excerpt`
+        // clamps to the end of the buffer instead of staying put.
+        if next_point == display_point
+            || (direction == Direction::Next
+                && excerpt_id(next_point) == excerpt_id(display_point))
+        {
+            break;
+        }
+        display_point = next_point;
+    }
+
+    display_point
+}
+
+fn matches_indent_type(
+    target_indent: &text::LineIndent,
+    current_indent: &text::LineIndent,
+    indent_type: IndentType,
+) -> bool {
+    match indent_type {
+        IndentType::Lesser => {
+            target_indent.spaces < current_indent.spaces || target_indent.tabs < current_indent.tabs
+        }
+        IndentType::Greater => {
+            target_indent.spaces > current_indent.spaces || target_indent.tabs > current_indent.tabs
+        }
+        IndentType::Same => {
+            target_indent.spaces == current_indent.spaces
+                && target_indent.tabs == current_indent.tabs
+        }
+    }
+}
+
+fn indent_motion(
+    map: &DisplaySnapshot,
+    mut display_point: DisplayPoint,
+    times: usize,
+    direction: Direction,
+    indent_type: IndentType,
+) -> DisplayPoint {
+    let buffer_point = map.display_point_to_point(display_point, Bias::Left);
+    let current_row = MultiBufferRow(buffer_point.row);
+    let mut current_indent = map.line_indent_for_buffer_row(current_row);
+    if current_indent.is_line_empty() {
+        return display_point;
+    }
     let max_row = map.max_point().to_point(map).row;
 
     for _ in 0..times {
@@ -2901,6 +3969,11 @@ fn indent_motion(
             break;
         }
         display_point = new_point;
+        // Each step out of a scope compares against the scope just reached, so that a
+        // count climbs that many levels instead of stopping at lines of the same level.
+        if indent_type == IndentType::Lesser {
+            current_indent = map.line_indent_for_buffer_row(MultiBufferRow(target_row));
+        }
     }
     display_point
 }
@@ -2909,13 +3982,17 @@ fn indent_motion(
 mod test {
 
     use crate::{
+        VimSettings,
+        motion::Keyword,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
-    use editor::display_map::Inlay;
+    use editor::{EditorSettings, display_map::Inlay};
+    use gpui::{point, px, size};
     use indoc::indoc;
     use language::Point;
     use multi_buffer::MultiBufferRow;
+    use settings::SettingsStore;
 
     #[gpui::test]
     async fn test_start_end_of_paragraph(cx: &mut gpui::TestAppContext) {
@@ -2990,6 +4067,46 @@ mod test {
                 final"});
     }
 
+    #[gpui::test]
+    async fn test_start_end_of_paragraph_edges(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        // on a blank line, the surrounding blank lines belong to no paragraph
+        cx.set_shared_state("abc\n\nˇ\n\ndef\nghi").await;
+        cx.simulate_shared_keystrokes("{").await;
+        cx.shared_state().await.assert_eq("ˇabc\n\n\n\ndef\nghi");
+        cx.set_shared_state("abc\nˇ\n\ndef\nghi\n\njkl").await;
+        cx.simulate_shared_keystrokes("}").await;
+        cx.shared_state().await.assert_eq("abc\n\n\ndef\nghi\nˇ\njkl");
+
+        // on the first line of a paragraph
+        cx.set_shared_state("abc\n\nˇdef\nghi").await;
+        cx.simulate_shared_keystrokes("{").await;
+        cx.shared_state().await.assert_eq("abc\nˇ\ndef\nghi");
+
+        // on the last line of the document
+        cx.set_shared_state("abc\n\ndef\ngˇhi").await;
+        cx.simulate_shared_keystrokes("}").await;
+        cx.shared_state().await.assert_eq("abc\n\ndef\nghˇi");
+        cx.simulate_shared_keystrokes("{").await;
+        cx.shared_state().await.assert_eq("abc\nˇ\ndef\nghi");
+
+        // on the only line
+        cx.set_shared_state("aˇbc").await;
+        cx.simulate_shared_keystrokes("}").await;
+        cx.shared_state().await.assert_eq("abˇc");
+        cx.simulate_shared_keystrokes("{").await;
+        cx.shared_state().await.assert_eq("ˇabc");
+
+        // in leading and trailing blank lines
+        cx.set_shared_state("\n\nˇ\nabc").await;
+        cx.simulate_shared_keystrokes("{").await;
+        cx.shared_state().await.assert_eq("ˇ\n\n\nabc");
+        cx.set_shared_state("abc\ndef\nˇ\n\n").await;
+        cx.simulate_shared_keystrokes("}").await;
+        cx.shared_state().await.assert_eq("abc\ndef\n\n\nˇ");
+    }
+
     #[gpui::test]
     async fn test_matching(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -3150,36 +4267,852 @@ mod test {
             .await
             .assert_eq(indoc! {r"<ˇbody></body>"});
 
-        // test self-closing tags
-        cx.set_shared_state(indoc! {r"<a><bˇr/></a>"}).await;
-        cx.simulate_shared_keystrokes("%").await;
-        cx.shared_state().await.assert_eq(indoc! {r"<a><bˇr/></a>"});
+        // test self-closing tags
+        cx.set_shared_state(indoc! {r"<a><bˇr/></a>"}).await;
+        cx.simulate_shared_keystrokes("%").await;
+        cx.shared_state().await.assert_eq(indoc! {r"<a><bˇr/></a>"});
+
+        // test tag with attributes
+        cx.set_shared_state(indoc! {r"<div class='test' ˇid='main'>
+            </div>
+            "})
+            .await;
+        cx.simulate_shared_keystrokes("%").await;
+        cx.shared_state()
+            .await
+            .assert_eq(indoc! {r"<div class='test' id='main'>
+            <ˇ/div>
+            "});
+
+        // test multi-line self-closing tag
+        cx.set_shared_state(indoc! {r#"<a>
+            <br
+                test = "test"
+            /ˇ>
+        </a>"#})
+            .await;
+        cx.simulate_shared_keystrokes("%").await;
+        cx.shared_state().await.assert_eq(indoc! {r#"<a>
+            ˇ<br
+                test = "test"
+            />
+        </a>"#});
+    }
+
+    #[gpui::test]
+    async fn test_next_method_end(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig::default(),
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        ))
+        .with_text_object_query(
+            r#"(function_item body: (_ "{" (_)* @function.inside "}")) @function.around"#,
+        )
+        .unwrap();
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_shared_state(indoc! {"
+            ˇfn a() {
+                1
+            }
+
+            fn b() {
+                2
+            }
+            "})
+            .await;
+        cx.simulate_shared_keystrokes("] shift-m").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            ˇ}
+
+            fn b() {
+                2
+            }
+            "});
+        cx.simulate_shared_keystrokes("] shift-m").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                2
+            ˇ}
+            "});
+        cx.simulate_shared_keystrokes("[ shift-m").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            ˇ}
+
+            fn b() {
+                2
+            }
+            "});
+
+        cx.set_shared_state(indoc! {"
+            ˇfn a() {
+                1
+            }
+
+            fn b() {
+                2
+            }
+            "})
+            .await;
+        cx.simulate_shared_keystrokes("v ] shift-m").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            «fn a() {
+                1
+            }ˇ»
+
+            fn b() {
+                2
+            }
+            "});
+    }
+
+    #[gpui::test]
+    async fn test_section_motion_in_last_section(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig::default(),
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        ))
+        .with_text_object_query(
+            r#"(function_item body: (_ "{" (_)* @function.inside "}")) @function.around"#,
+        )
+        .unwrap();
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_shared_state(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                ˇ2
+            }
+            "})
+            .await;
+        cx.simulate_shared_keystrokes("] ]").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                2
+            }
+            ˇ"});
+
+        cx.set_shared_state(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                ˇ2
+            }
+            "})
+            .await;
+        cx.simulate_shared_keystrokes("] [").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                2
+            ˇ}
+            "});
+        cx.simulate_shared_keystrokes("] [").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            fn a() {
+                1
+            }
+
+            fn b() {
+                2
+            }
+            ˇ"});
+    }
+
+    #[gpui::test]
+    async fn test_matching_keywords(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig {
+                match_words: vec![language::MatchWordsConfig {
+                    start: vec!["if".into()],
+                    middle: Vec::new(),
+                    end: vec!["else".into()],
+                }],
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        ));
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                ˇif x {
+                    if y { 1 } else { 2 }
+                } else {
+                    3
+                }
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                if x {
+                    if y { 1 } else { 2 }
+                } ˇelse {
+                    3
+                }
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                ˇif x {
+                    if y { 1 } else { 2 }
+                } else {
+                    3
+                }
+            }"},
+            Mode::Normal,
+        );
+
+        // nested constructs are matched on their own
+        cx.simulate_keystrokes("j w %");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                if x {
+                    if y { 1 } ˇelse { 2 }
+                } else {
+                    3
+                }
+            }"},
+            Mode::Normal,
+        );
+
+        // brackets still work
+        cx.simulate_keystrokes("k f { %");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                if x {
+                    if y { 1 } else { 2 }
+                ˇ} else {
+                    3
+                }
+            }"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_matching_skips_strings_and_comments(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let language = std::sync::Arc::new(
+            language::Language::new(
+                language::LanguageConfig::default(),
+                Some(language::tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_brackets_query(r#"("(" @open ")" @close) ("\"" @open "\"" @close)"#)
+            .unwrap()
+            .with_override_query(
+                "(string_literal) @string\n(line_comment) @comment.inclusive",
+            )
+            .unwrap(),
+        );
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        // a bracket in a string is not paired with the code around it
+        cx.set_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                call("ˇ(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                call("ˇ(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                callˇ("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                call("(a", bˇ);
+            }"#},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {r#"
+            fn a() {
+                // wrapˇ(x, y) here
+                call("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, yˇ) here
+                call("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, ˇy) here
+                call("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] )");
+        cx.assert_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, yˇ) here
+                call("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                call("(a", ˇb);
+            }"#},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ (");
+        cx.assert_state(
+            indoc! {r#"
+            fn a() {
+                // wrap(x, y) here
+                callˇ("(a", b);
+            }"#},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_unmatched_skips_strings_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let language = std::sync::Arc::new(
+            language::Language::new(
+                language::LanguageConfig::default(),
+                Some(language::tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_override_query("(string_literal) @string")
+            .unwrap(),
+        );
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state(r#"call(ˇx, ")", (y), z);"#, Mode::Normal);
+        cx.simulate_keystrokes("] )");
+        cx.assert_state(r#"call(x, ")", (y), zˇ);"#, Mode::Normal);
+
+        cx.set_state(r#"f(g(ˇa, ")"), b);"#, Mode::Normal);
+        cx.simulate_keystrokes("2 ] )");
+        cx.assert_state(r#"f(g(a, ")"), bˇ);"#, Mode::Normal);
+
+        cx.set_state(r#"f(g("(", ˇa));"#, Mode::Normal);
+        cx.simulate_keystrokes("2 [ (");
+        cx.assert_state(r#"fˇ(g("(", a));"#, Mode::Normal);
+
+        // a count past the outermost bracket stops there
+        cx.set_state(r#"f(g(ˇa));"#, Mode::Normal);
+        cx.simulate_keystrokes("5 ] )");
+        cx.assert_state(r#"f(g(a)ˇ);"#, Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_unmatched_distant_brackets(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_buffer(|buffer, cx| buffer.set_language(None, cx));
+        let padding = "x".repeat(20_000);
+
+        cx.set_state(&format!("(ˇa{padding})"), Mode::Normal);
+        cx.simulate_keystrokes("] )");
+        cx.assert_state(&format!("(a{padding}ˇ)"), Mode::Normal);
+
+        cx.set_state(&format!("({padding}ˇa)"), Mode::Normal);
+        cx.simulate_keystrokes("[ (");
+        cx.assert_state(&format!("ˇ({padding}a)"), Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_matchpairs(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_buffer(|buffer, cx| buffer.set_language(None, cx));
+
+        // the default pairs are matched without tree-sitter brackets
+        cx.set_state("(a (b) ˇc)", Mode::Normal);
+        cx.simulate_keystrokes("[ (");
+        cx.assert_state("ˇ(a (b) c)", Mode::Normal);
+        cx.simulate_keystrokes("%");
+        cx.assert_state("(a (b) cˇ)", Mode::Normal);
+
+        cx.set_state("ˇa <b <c> d>", Mode::Normal);
+        cx.simulate_keystrokes("%");
+        cx.assert_state("ˇa <b <c> d>", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.matchpairs = Some(vec!["<:>".into()]);
+            });
+        });
+
+        cx.simulate_keystrokes("%");
+        cx.assert_state("a <b <c> dˇ>", Mode::Normal);
+        cx.simulate_keystrokes("%");
+        cx.assert_state("a ˇ<b <c> d>", Mode::Normal);
+        cx.simulate_keystrokes("f c %");
+        cx.assert_state("a <b ˇ<c> d>", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_iskeyword(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇfoo-bar baz.qux", Mode::Normal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("fooˇ-bar baz.qux", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.iskeyword = Some("@,48-57,_,-".into());
+            });
+        });
+
+        cx.set_state("ˇfoo-bar baz.qux", Mode::Normal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("foo-bar ˇbaz.qux", Mode::Normal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("foo-bar bazˇ.qux", Mode::Normal);
+        cx.simulate_keystrokes("b b");
+        cx.assert_state("ˇfoo-bar baz.qux", Mode::Normal);
+        cx.simulate_keystrokes("e");
+        cx.assert_state("foo-baˇr baz.qux", Mode::Normal);
+        cx.simulate_keystrokes("w g e");
+        cx.assert_state("foo-baˇr baz.qux", Mode::Normal);
+
+        cx.set_state("ˇfoo-bar baz", Mode::Normal);
+        cx.simulate_keystrokes("c w");
+        cx.assert_state("ˇ baz", Mode::Insert);
+
+        // A leading `^` excludes characters that an earlier part included.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.iskeyword = Some("@,48-57,_,^_".into());
+            });
+        });
+
+        cx.set_state("ˇsnake_case", Mode::Normal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("snakeˇ_case", Mode::Normal);
+
+        assert!(Keyword::parse("@,48-").is_err());
+        assert!(Keyword::parse("@,ab").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_comment_block_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig {
+                line_comments: vec!["// ".into(), "/// ".into()],
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        ))
+        .with_text_object_query(
+            "(line_comment) @comment.around\n(block_comment) @comment.around",
+        )
+        .unwrap();
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state(
+            indoc! {"
+            ˇfn a() {}
+            // one
+            // two
+            //   three
+
+            /// doc one
+            /// doc two
+            fn b() {
+                /* block */
+                // trailing
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] /");
+        cx.assert_state(
+            indoc! {"
+            fn a() {}
+            // one
+            // two
+            //   threˇe
+
+            /// doc one
+            /// doc two
+            fn b() {
+                /* block */
+                // trailing
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] /");
+        cx.assert_state(
+            indoc! {"
+            fn a() {}
+            // one
+            // two
+            //   three
+
+            /// doc one
+            /// doc twˇo
+            fn b() {
+                /* block */
+                // trailing
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 ] /");
+        cx.assert_state(
+            indoc! {"
+            fn a() {}
+            // one
+            // two
+            //   three
+
+            /// doc one
+            /// doc two
+            fn b() {
+                /* block */
+                // trailinˇg
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ /");
+        cx.assert_state(
+            indoc! {"
+            fn a() {}
+            // one
+            // two
+            //   three
+
+            /// doc one
+            /// doc two
+            fn b() {
+                /* block */
+                ˇ// trailing
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("3 [ /");
+        cx.assert_state(
+            indoc! {"
+            fn a() {}
+            ˇ// one
+            // two
+            //   three
+
+            /// doc one
+            /// doc two
+            fn b() {
+                /* block */
+                // trailing
+            }"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_url_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇSee https://zed.dev/docs and
+            the (http://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] u");
+        cx.assert_state(
+            indoc! {"
+            See ˇhttps://zed.dev/docs and
+            the (http://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] u");
+        cx.assert_state(
+            indoc! {"
+            See https://zed.dev/docs and
+            the (ˇhttp://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] u");
+        cx.assert_state(
+            indoc! {"
+            See https://zed.dev/docs and
+            the (ˇhttp://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+
+        // inside a url, [u goes to its start and ]u skips past it
+        cx.simulate_keystrokes("f x [ u");
+        cx.assert_state(
+            indoc! {"
+            See https://zed.dev/docs and
+            the (ˇhttp://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("k f d ] u");
+        cx.assert_state(
+            indoc! {"
+            See https://zed.dev/docs and
+            the (ˇhttp://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("shift-g 2 [ u");
+        cx.assert_state(
+            indoc! {"
+            See ˇhttps://zed.dev/docs and
+            the (http://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("0 d ] u");
+        cx.assert_state(
+            indoc! {"
+            ˇhttps://zed.dev/docs and
+            the (http://example.com/a).
+            Not a url: zed.dev"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_syntax_node_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig::default(),
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        ));
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                ˇlet y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2);
+                ˇlet z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 [ n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                ˇlet x = 1;
+                let y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                ˇlet x = 1;
+                let y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ shift-n");
+        cx.assert_state(
+            indoc! {"
+            fn a() ˇ{
+                let x = 1;
+                let y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ shift-n");
+        cx.assert_state(
+            indoc! {"
+            ˇfn a() {
+                let x = 1;
+                let y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        // the root node is not a destination
+        cx.simulate_keystrokes("[ shift-n");
+        cx.assert_state(
+            indoc! {"
+            ˇfn a() {
+                let x = 1;
+                let y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(ˇx, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] shift-n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2ˇ);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(ˇx, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 ] shift-n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2)ˇ;
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
 
-        // test tag with attributes
-        cx.set_shared_state(indoc! {r"<div class='test' ˇid='main'>
-            </div>
-            "})
-            .await;
-        cx.simulate_shared_keystrokes("%").await;
-        cx.shared_state()
-            .await
-            .assert_eq(indoc! {r"<div class='test' id='main'>
-            <ˇ/div>
-            "});
+        // between nodes, the following node is used
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2);
+            ˇ    let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2);
+                ˇlet z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                let y = foo(x, 2);
+            ˇ    let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                ˇlet y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
 
-        // test multi-line self-closing tag
-        cx.set_shared_state(indoc! {r#"<a>
-            <br
-                test = "test"
-            /ˇ>
-        </a>"#})
-            .await;
-        cx.simulate_shared_keystrokes("%").await;
-        cx.shared_state().await.assert_eq(indoc! {r#"<a>
-            ˇ<br
-                test = "test"
-            />
-        </a>"#});
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                ˇlet y = foo(x, 2);
+                let z = 3;
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d ] n");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                let x = 1;
+                ˇlet z = 3;
+            }"},
+            Mode::Normal,
+        );
     }
 
     #[gpui::test]
@@ -3217,6 +5150,26 @@ mod test {
         cx.shared_state().await.assert_eq("one two thˇree four");
     }
 
+    #[gpui::test]
+    async fn test_find_motions_at_line_edges(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        let test_case = indoc! {"
+            ˇ
+            ˇa
+            ˇaˇa
+            ˇaˇbˇa
+            ˇéˇaˇéˇa
+        "};
+
+        for count in ["", "2 "] {
+            for find in ["f", "t", "shift-f", "shift-t"] {
+                cx.simulate_at_each_offset(&format!("{count}{find} a"), test_case)
+                    .await
+                    .assert_matches();
+            }
+        }
+    }
+
     #[gpui::test]
     async fn test_next_word_end_newline_last_char(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -3226,6 +5179,49 @@ mod test {
         cx.shared_state().await.assert_eq("something(fooˇ)");
     }
 
+    #[gpui::test]
+    async fn test_next_word_end_large_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let initial_state = indoc! {"
+            ˇa.b c-d
+            ef gh.ij kl
+
+            mn op"};
+
+        cx.set_state(initial_state, Mode::Normal);
+        cx.simulate_keystrokes("1 0 e");
+        cx.assert_state(
+            indoc! {"
+                a.b c-d
+                ef gh.ij kˇl
+
+                mn op"},
+            Mode::Normal,
+        );
+
+        cx.set_state(initial_state, Mode::Normal);
+        cx.simulate_keystrokes("1 1 e");
+        cx.assert_state(
+            indoc! {"
+                a.b c-d
+                ef gh.ij kl
+
+                mˇn op"},
+            Mode::Normal,
+        );
+
+        cx.set_state(initial_state, Mode::Normal);
+        cx.simulate_keystrokes("2 0 e");
+        cx.assert_state(
+            indoc! {"
+                a.b c-d
+                ef gh.ij kl
+
+                mn oˇp"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_next_line_start(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -3251,6 +5247,7 @@ mod test {
     #[gpui::test]
     async fn test_window_top(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
         let initial_state = indoc! {r"abc
           def
           paragraph
@@ -3260,7 +5257,7 @@ mod test {
 
         cx.set_shared_state(initial_state).await;
         cx.simulate_shared_keystrokes("shift-h").await;
-        cx.shared_state().await.assert_eq(indoc! {r"abˇc
+        cx.shared_state().await.assert_eq(indoc! {r"ˇabc
           def
           paragraph
           the second
@@ -3276,7 +5273,7 @@ mod test {
             .await;
         cx.simulate_shared_keystrokes("shift-h").await;
         cx.shared_state().await.assert_eq(indoc! {"
-          1 2 ˇ3
+          ˇ1 2 3
           4 5 6
           7 8 9
           "});
@@ -3303,12 +5300,82 @@ mod test {
         cx.shared_state().await.assert_eq(indoc! {"
           1 2 3
           4 5 6
-          7 8 ˇ9"});
+          ˇ7 8 9"});
+    }
+
+    #[gpui::test]
+    async fn test_window_top_and_bottom_scroll_margin(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let (line_height, visible_line_count) = cx.editor(|editor, window, _cx| {
+            (
+                editor
+                    .style()
+                    .unwrap()
+                    .text
+                    .line_height_in_pixels(window.rem_size()),
+                editor.visible_line_count().unwrap(),
+            )
+        });
+        let window = cx.window;
+        let margin = cx
+            .update_window(window, |_, window, _cx| {
+                window.viewport_size().height - line_height * visible_line_count
+            })
+            .unwrap();
+        cx.simulate_window_resize(cx.window, size(px(1000.), margin + 10. * line_height));
+
+        let state = |cursor_row: usize| {
+            (0..30)
+                .map(|row| {
+                    let marker = if row == cursor_row { "ˇ" } else { "" };
+                    format!("{marker}line {row}")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        for (scroll_margin, top_row, bottom_row) in [(0, 10, 19), (2, 12, 17), (3, 13, 16)] {
+            cx.set_state(&state(15), Mode::Normal);
+            cx.update_editor(|editor, window, cx| {
+                editor.set_vertical_scroll_margin(scroll_margin, cx);
+                editor.set_scroll_position(point(0., 10.), window, cx);
+            });
+
+            for keystrokes in ["shift-h", "1 shift-h"] {
+                cx.simulate_keystrokes(keystrokes);
+                cx.assert_state(&state(top_row), Mode::Normal);
+            }
+            for keystrokes in ["shift-l", "1 shift-l"] {
+                cx.simulate_keystrokes(keystrokes);
+                cx.assert_state(&state(bottom_row), Mode::Normal);
+            }
+        }
+
+        // A fractional margin rounds up, so the cursor never lands inside it.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<EditorSettings>(cx, |s| {
+                s.vertical_scroll_margin = Some(2.5)
+            });
+        });
+        cx.set_state(&state(15), Mode::Normal);
+        cx.update_editor(|editor, window, cx| {
+            editor.set_scroll_position(point(0., 10.), window, cx);
+        });
+        for keystrokes in ["shift-h", "1 shift-h"] {
+            cx.simulate_keystrokes(keystrokes);
+            cx.assert_state(&state(13), Mode::Normal);
+        }
+        for keystrokes in ["shift-l", "1 shift-l"] {
+            cx.simulate_keystrokes(keystrokes);
+            cx.assert_state(&state(16), Mode::Normal);
+        }
     }
 
     #[gpui::test]
     async fn test_window_middle(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
         let initial_state = indoc! {r"abˇc
           def
           paragraph
@@ -3320,7 +5387,7 @@ mod test {
         cx.simulate_shared_keystrokes("shift-m").await;
         cx.shared_state().await.assert_eq(indoc! {r"abc
           def
-          paˇragraph
+          ˇparagraph
           the second
           third and
           final"});
@@ -3334,7 +5401,7 @@ mod test {
         cx.simulate_shared_keystrokes("shift-m").await;
         cx.shared_state().await.assert_eq(indoc! {"
           1 2 3
-          4 5 ˇ6
+          ˇ4 5 6
           7 8 9
           "});
         cx.set_shared_state(indoc! {r"
@@ -3382,7 +5449,7 @@ mod test {
         cx.simulate_shared_keystrokes("shift-m").await;
         cx.shared_state().await.assert_eq(indoc! {"
           1 2 3
-          4 5 ˇ6
+          ˇ4 5 6
           7 8 9
           "});
     }
@@ -3390,6 +5457,7 @@ mod test {
     #[gpui::test]
     async fn test_window_bottom(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
         let initial_state = indoc! {r"abc
           deˇf
           paragraph
@@ -3404,7 +5472,7 @@ mod test {
           paragraph
           the second
           third and
-          fiˇnal"});
+          ˇfinal"});
 
         cx.set_shared_state(indoc! {r"
           1 2 3
@@ -3466,12 +5534,97 @@ mod test {
             .await;
         cx.simulate_shared_keystrokes("9 shift-l").await;
         cx.shared_state().await.assert_eq(indoc! {"
-          1 2 ˇ3
+          ˇ1 2 3
           4 5 6
           7 8 9
           "});
     }
 
+    #[gpui::test]
+    async fn test_window_motions_first_non_blank(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
+
+        cx.set_shared_state("  one\n    two\n      thrˇee\n    four\n  five")
+            .await;
+        cx.simulate_shared_keystrokes("shift-h").await;
+        cx.shared_state()
+            .await
+            .assert_eq("  ˇone\n    two\n      three\n    four\n  five");
+        cx.simulate_shared_keystrokes("shift-l").await;
+        cx.shared_state()
+            .await
+            .assert_eq("  one\n    two\n      three\n    four\n  ˇfive");
+        cx.simulate_shared_keystrokes("shift-m").await;
+        cx.shared_state()
+            .await
+            .assert_eq("  one\n    two\n      ˇthree\n    four\n  five");
+        cx.simulate_shared_keystrokes("2 shift-h").await;
+        cx.shared_state()
+            .await
+            .assert_eq("  one\n    ˇtwo\n      three\n    four\n  five");
+        cx.simulate_shared_keystrokes("2 shift-l").await;
+        cx.shared_state()
+            .await
+            .assert_eq("  one\n    two\n      three\n    ˇfour\n  five");
+    }
+
+    #[gpui::test]
+    async fn test_window_middle_bottom_with_folds(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("foldmethod=manual").await;
+        cx.set_scroll_height(10).await;
+
+        let content = ('a'..='z')
+            .map(|ch| format!("{ch}{ch}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        cx.set_shared_state(&format!("ˇ{content}")).await;
+        // fold cc through ff, so gg is on the fourth display row
+        cx.simulate_shared_keystrokes("2 j shift-v 3 j z f escape g g")
+            .await;
+        cx.simulate_shared_keystrokes("shift-m").await;
+        cx.shared_state().await.assert_matches();
+        cx.simulate_shared_keystrokes("shift-l").await;
+        cx.shared_state().await.assert_matches();
+        cx.simulate_shared_keystrokes("g g 5 shift-l").await;
+        cx.shared_state().await.assert_matches();
+    }
+
+    #[gpui::test]
+    async fn test_window_middle_bottom_with_folds_in_short_file(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("foldmethod=manual").await;
+        cx.set_scroll_height(10).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇaa
+            bb
+            cc
+            dd
+            ee
+            ff"})
+            .await;
+        cx.simulate_shared_keystrokes("j shift-v j z f escape g g")
+            .await;
+        cx.simulate_shared_keystrokes("shift-m").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            aa
+            bb
+            cc
+            ˇdd
+            ee
+            ff"});
+        cx.simulate_shared_keystrokes("shift-l").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            aa
+            bb
+            cc
+            dd
+            ee
+            ˇff"});
+    }
+
     #[gpui::test]
     async fn test_previous_word_end(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -3577,6 +5730,26 @@ mod test {
         "});
     }
 
+    #[gpui::test]
+    async fn test_visual_matching(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("fn aˇ(b, c) {}").await;
+        cx.simulate_shared_keystrokes("v %").await;
+        cx.shared_state().await.assert_eq("fn a«(b, c)ˇ» {}");
+
+        // jumping before the anchor flips the selection, but keeps the anchor selected
+        cx.set_shared_state("fn a(b, cˇ) {}").await;
+        cx.simulate_shared_keystrokes("v %").await;
+        cx.shared_state().await.assert_eq("fn a«ˇ(b, c)» {}");
+
+        cx.set_shared_state("fn a(b, ˇc) {}").await;
+        cx.simulate_shared_keystrokes("v h h h %").await;
+        cx.shared_state().await.assert_eq("fn a«ˇ(b, c») {}");
+        cx.simulate_shared_keystrokes("%").await;
+        cx.shared_state().await.assert_eq("fn a(b, «c)ˇ» {}");
+    }
+
     #[gpui::test]
     async fn test_clipping_with_inlay_hints(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -3863,6 +6036,46 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_go_to_enclosing_indent(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+                class Foo:
+                    def bar(self):
+                        x = 1
+                        if x:
+                            ˇreturn x
+                        return 0
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 [ i");
+        cx.assert_state(
+            indoc! {"
+                class Foo:
+                    ˇdef bar(self):
+                        x = 1
+                        if x:
+                            return x
+                        return 0
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("j j j ] i");
+        cx.assert_state(
+            indoc! {"
+                class Foo:
+                    def bar(self):
+                        x = 1
+                        if x:
+                            return x
+                        ˇreturn 0
+            "},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_key_can_remove_last_character(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -3871,6 +6084,36 @@ mod test {
         cx.shared_state().await.assert_eq("aˇb");
     }
 
+    #[gpui::test]
+    async fn test_go_to_column_wide_characters(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇab𠮷𠀋cd", Mode::Normal);
+        cx.simulate_keystrokes("3 |");
+        cx.assert_state("abˇ𠮷𠀋cd", Mode::Normal);
+        cx.simulate_keystrokes("4 |");
+        cx.assert_state("abˇ𠮷𠀋cd", Mode::Normal);
+        cx.simulate_keystrokes("5 |");
+        cx.assert_state("ab𠮷ˇ𠀋cd", Mode::Normal);
+        cx.simulate_keystrokes("6 |");
+        cx.assert_state("ab𠮷ˇ𠀋cd", Mode::Normal);
+        cx.simulate_keystrokes("7 |");
+        cx.assert_state("ab𠮷𠀋ˇcd", Mode::Normal);
+        cx.simulate_keystrokes("2 0 |");
+        cx.assert_state("ab𠮷𠀋cˇd", Mode::Normal);
+
+        cx.simulate_keystrokes("d 6 |");
+        cx.assert_state("ab𠮷ˇd", Mode::Normal);
+        cx.simulate_keystrokes("0 d 3 |");
+        cx.assert_state("ˇ𠮷d", Mode::Normal);
+
+        cx.set_state("\taˇb", Mode::Normal);
+        cx.simulate_keystrokes("3 |");
+        cx.assert_state("ˇ\tab", Mode::Normal);
+        cx.simulate_keystrokes("5 |");
+        cx.assert_state("\tˇab", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_forced_motion_delete_to_start_of_line(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -4013,4 +6256,66 @@ mod test {
         the quick brown foˇd over the lazy dog"});
         assert_eq!(cx.cx.forced_motion(), false);
     }
+
+    #[cfg(target_os = "macos")]
+    #[gpui::test]
+    async fn test_end_of_display_line_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_wrap(12).await;
+        // The first line wraps into two display lines:
+        //  1: twelve char
+        //     twelve char
+        //  2: twelve char
+        cx.set_shared_state(indoc! {"
+            ˇtwelve char twelve char
+            twelve char
+        "})
+        .await;
+        cx.simulate_shared_keystrokes("2 g $").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            twelve char twelve chaˇr
+            twelve char
+        "});
+
+        cx.set_shared_state(indoc! {"
+            ˇtwelve char twelve char
+            twelve char
+        "})
+        .await;
+        cx.simulate_shared_keystrokes("3 g $").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            twelve char twelve char
+            twelve chaˇr
+        "});
+
+        cx.set_state(
+            indoc! {"
+                ˇtwelve char twelve char
+                twelve char
+            "},
+            Mode::Normal,
+        );
+        cx.dispatch_action(super::EndOfLineDownward {
+            display_lines: true,
+        });
+        cx.assert_state(
+            indoc! {"
+                twelve chaˇr twelve char
+                twelve char
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("0 2");
+        cx.dispatch_action(super::EndOfLineDownward {
+            display_lines: true,
+        });
+        cx.assert_state(
+            indoc! {"
+                twelve char twelve chaˇr
+                twelve char
+            "},
+            Mode::Normal,
+        );
+    }
 }
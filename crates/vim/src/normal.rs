@@ -1,6 +1,7 @@
 mod change;
 mod convert;
 mod delete;
+mod fold;
 mod increment;
 pub(crate) mod mark;
 mod paste;
@@ -15,7 +16,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::{
-    Vim,
+    Vim, VimSettings,
     indent::IndentDirection,
     motion::{self, Motion, first_non_whitespace, next_line_end, right},
     object::Object,
@@ -33,6 +34,7 @@ use gpui::{Context, Window, actions};
 use language::{Point, SelectionGoal, ToPoint};
 use log::error;
 use multi_buffer::MultiBufferRow;
+use settings::Settings;
 
 actions!(
     vim,
@@ -86,6 +88,7 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::toggle_comments);
     Vim::action(editor, cx, Vim::paste);
     Vim::action(editor, cx, Vim::show_location);
+    Vim::action(editor, cx, Vim::go_back);
 
     Vim::action(editor, cx, |vim, _: &DeleteLeft, window, cx| {
         vim.record_current_action(cx);
@@ -169,6 +172,7 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         });
     });
 
+    fold::register(editor, cx);
     repeat::register(editor, cx);
     scroll::register(editor, cx);
     search::register(editor, cx);
@@ -280,38 +284,44 @@ impl Vim {
 
     pub fn normal_object(&mut self, object: Object, window: &mut Window, cx: &mut Context<Self>) {
         let mut waiting_operator: Option<Operator> = None;
+        let count = Self::take_count(cx);
         match self.maybe_pop_operator() {
             Some(Operator::Object { around }) => match self.maybe_pop_operator() {
-                Some(Operator::Change) => self.change_object(object, around, window, cx),
-                Some(Operator::Delete) => self.delete_object(object, around, window, cx),
-                Some(Operator::Yank) => self.yank_object(object, around, window, cx),
+                Some(Operator::Change) => self.change_object(object, around, count, window, cx),
+                Some(Operator::Delete) => self.delete_object(object, around, count, window, cx),
+                Some(Operator::Yank) => self.yank_object(object, around, count, window, cx),
                 Some(Operator::Indent) => {
-                    self.indent_object(object, around, IndentDirection::In, window, cx)
+                    self.indent_object(object, around, count, IndentDirection::In, window, cx)
                 }
                 Some(Operator::Outdent) => {
-                    self.indent_object(object, around, IndentDirection::Out, window, cx)
+                    self.indent_object(object, around, count, IndentDirection::Out, window, cx)
                 }
                 Some(Operator::AutoIndent) => {
-                    self.indent_object(object, around, IndentDirection::Auto, window, cx)
+                    self.indent_object(object, around, count, IndentDirection::Auto, window, cx)
                 }
                 Some(Operator::ShellCommand) => {
-                    self.shell_command_object(object, around, window, cx);
+                    self.shell_command_object(object, around, count, window, cx);
                 }
-                Some(Operator::Rewrap) => self.rewrap_object(object, around, window, cx),
+                Some(Operator::Rewrap) => self.rewrap_object(object, around, count, window, cx),
                 Some(Operator::Lowercase) => {
-                    self.convert_object(object, around, ConvertTarget::LowerCase, window, cx)
+                    self.convert_object(object, around, count, ConvertTarget::LowerCase, window, cx)
                 }
                 Some(Operator::Uppercase) => {
-                    self.convert_object(object, around, ConvertTarget::UpperCase, window, cx)
-                }
-                Some(Operator::OppositeCase) => {
-                    self.convert_object(object, around, ConvertTarget::OppositeCase, window, cx)
+                    self.convert_object(object, around, count, ConvertTarget::UpperCase, window, cx)
                 }
+                Some(Operator::OppositeCase) => self.convert_object(
+                    object,
+                    around,
+                    count,
+                    ConvertTarget::OppositeCase,
+                    window,
+                    cx,
+                ),
                 Some(Operator::Rot13) => {
-                    self.convert_object(object, around, ConvertTarget::Rot13, window, cx)
+                    self.convert_object(object, around, count, ConvertTarget::Rot13, window, cx)
                 }
                 Some(Operator::Rot47) => {
-                    self.convert_object(object, around, ConvertTarget::Rot47, window, cx)
+                    self.convert_object(object, around, count, ConvertTarget::Rot47, window, cx)
                 }
                 Some(Operator::AddSurrounds { target: None }) => {
                     waiting_operator = Some(Operator::AddSurrounds {
@@ -319,12 +329,12 @@ impl Vim {
                     });
                 }
                 Some(Operator::ToggleComments) => {
-                    self.toggle_comments_object(object, around, window, cx)
+                    self.toggle_comments_object(object, around, count, window, cx)
                 }
                 Some(Operator::ReplaceWithRegister) => {
-                    self.replace_with_register_object(object, around, window, cx)
+                    self.replace_with_register_object(object, around, count, window, cx)
                 }
-                Some(Operator::Exchange) => self.exchange_object(object, around, window, cx),
+                Some(Operator::Exchange) => self.exchange_object(object, around, count, window, cx),
                 _ => {
                     // Can't do anything for namespace operators. Ignoring
                 }
@@ -333,7 +343,7 @@ impl Vim {
                 waiting_operator = Some(Operator::DeleteSurrounds);
             }
             Some(Operator::ChangeSurrounds { target: None }) => {
-                if self.check_and_move_to_valid_bracket_pair(object, window, cx) {
+                if self.check_and_move_to_valid_bracket_pair(&object, window, cx) {
                     waiting_operator = Some(Operator::ChangeSurrounds {
                         target: Some(object),
                     });
@@ -356,6 +366,7 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let is_jump = motion.is_jump();
         self.update_editor(window, cx, |_, editor, window, cx| {
             let text_layout_details = editor.text_layout_details(window);
             editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
@@ -364,7 +375,23 @@ impl Vim {
                         .move_point(map, cursor, goal, times, &text_layout_details)
                         .unwrap_or((cursor, goal))
                 })
-            })
+            });
+        });
+        if is_jump {
+            self.center_on_jump(window, cx);
+        }
+    }
+
+    /// Recenters on the newest cursor after a jump when `vim.center_on_jump` is
+    /// enabled and the cursor has left the viewport.
+    pub(crate) fn center_on_jump(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !VimSettings::get_global(cx).center_on_jump {
+            return;
+        }
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            if !newest_cursor_visible(editor, window, cx) {
+                editor.request_autoscroll(Autoscroll::center(), cx);
+            }
         });
     }
 
@@ -780,9 +807,25 @@ impl Vim {
         }
     }
 }
+
+// Autoscroll is applied during layout, so the scroll position still reflects
+// the viewport the cursor was moved from.
+fn newest_cursor_visible(
+    editor: &mut Editor,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) -> bool {
+    let Some(visible_line_count) = editor.visible_line_count() else {
+        return true;
+    };
+    let top = editor.snapshot(window, cx).scroll_position().y;
+    let row = editor.selections.newest_display(cx).head().row().0 as f32;
+    row >= top && row < top + visible_line_count
+}
+
 #[cfg(test)]
 mod test {
-    use gpui::{KeyBinding, TestAppContext, UpdateGlobal};
+    use gpui::{KeyBinding, TestAppContext, UpdateGlobal, point, px, size};
     use indoc::indoc;
     use language::language_settings::AllLanguageSettings;
     use settings::SettingsStore;
@@ -847,6 +890,36 @@ mod test {
         .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_j_k_through_empty_line(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            abcdˇe
+
+            fghij"
+        })
+        .await;
+        cx.simulate_shared_keystrokes("j").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            abcde
+            ˇ
+            fghij"
+        });
+        cx.simulate_shared_keystrokes("j").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            abcde
+
+            fghiˇj"
+        });
+        cx.simulate_shared_keystrokes("k k").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            abcdˇe
+
+            fghij"
+        });
+    }
+
     #[gpui::test]
     async fn test_enter(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -912,6 +985,7 @@ mod test {
     #[gpui::test]
     async fn test_jump_to_end(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
 
         cx.simulate_at_each_offset(
             "shift-g",
@@ -970,6 +1044,27 @@ mod test {
         .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_w_over_lone_punctuation(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        let marked_positions = indoc! {"
+            ˇ! a
+            foo ˇ!ˇ bar
+            ˇx ˇ- ˇ(y)ˇ;
+            ˇ.
+              ˇ?
+            ˇend"};
+        cx.simulate_at_each_offset("w", marked_positions)
+            .await
+            .assert_matches();
+        cx.simulate_at_each_offset("shift-w", marked_positions)
+            .await
+            .assert_matches();
+        cx.simulate_at_each_offset("2 w", marked_positions)
+            .await
+            .assert_matches();
+    }
+
     #[gpui::test]
     async fn test_end_of_word(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1027,6 +1122,7 @@ mod test {
     #[gpui::test]
     async fn test_gg(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
         cx.simulate_at_each_offset(
             "g g",
             indoc! {"
@@ -1059,9 +1155,19 @@ mod test {
         .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_gg_first_non_blank(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
+        cx.simulate_at_each_offset("g g", "    oˇne\ntwo thˇree\nfouˇr")
+            .await
+            .assert_matches();
+    }
+
     #[gpui::test]
     async fn test_end_of_document(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
         cx.simulate_at_each_offset(
             "shift-g",
             indoc! {"
@@ -1094,6 +1200,15 @@ mod test {
         .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_end_of_document_first_non_blank(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.set_neovim_option("startofline").await;
+        cx.simulate_at_each_offset("shift-g", "The qˇuick\nbrown ˇfox\n  teˇxt")
+            .await
+            .assert_matches();
+    }
+
     #[gpui::test]
     async fn test_a(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1516,6 +1631,22 @@ mod test {
             "},
             Mode::Normal,
         );
+
+        // an operator over a find that crosses lines stays characterwise
+        cx.assert_binding(
+            "d f l",
+            indoc! {"
+            ˇfunction print() {
+                console.log('ok')
+            }
+            "},
+            Mode::Normal,
+            indoc! {"
+            ˇe.log('ok')
+            }
+            "},
+            Mode::Normal,
+        );
     }
 
     #[gpui::test]
@@ -1689,6 +1820,14 @@ mod test {
             indoc! {"assert_bindinˇg"},
             indoc! {"asserˇt_binding"},
         );
+
+        // Screaming snake case, where the words are only separated by underscores
+        cx.assert_binding_normal("w", "ˇSCREAMING_SNAKE_CASE", "SCREAMING_ˇSNAKE_CASE");
+        cx.assert_binding_normal("2 w", "ˇSCREAMING_SNAKE_CASE", "SCREAMING_SNAKE_ˇCASE");
+        cx.assert_binding_normal("w", "ˇFOO__BAR", "FOO__ˇBAR");
+        cx.assert_binding_normal("e", "SCREAMINˇG_SNAKE_CASE", "SCREAMING_SNAKˇE_CASE");
+        cx.assert_binding_normal("b", "SCREAMING_SNAKE_ˇCASE", "SCREAMING_ˇSNAKE_CASE");
+        cx.assert_binding_normal("g e", "SCREAMING_SNAKE_CASˇE", "SCREAMING_SNAKˇE_CASE");
     }
 
     #[gpui::test]
@@ -1827,4 +1966,80 @@ mod test {
             fox jˇumps over
             the lazy dog"});
     }
+
+    #[gpui::test]
+    async fn test_center_on_jump(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.center_on_jump = Some(true);
+            });
+        });
+
+        let (line_height, visible_line_count) = cx.editor(|editor, window, _cx| {
+            (
+                editor
+                    .style()
+                    .unwrap()
+                    .text
+                    .line_height_in_pixels(window.rem_size()),
+                editor.visible_line_count().unwrap(),
+            )
+        });
+        let window = cx.window;
+        let margin = cx
+            .update_window(window, |_, window, _cx| {
+                window.viewport_size().height - line_height * visible_line_count
+            })
+            .unwrap();
+        cx.simulate_window_resize(
+            cx.window,
+            size(px(1000.), margin + 20. * line_height - px(1.0)),
+        );
+
+        let lines = (1..=100).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        cx.set_state(&format!("ˇ{}", lines.join("\n")), Mode::Normal);
+
+        // an off-screen target is centered
+        cx.simulate_keystrokes("5 0 shift-g");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 40.))
+        });
+
+        // small motions keep scrolling as usual
+        cx.simulate_keystrokes("k");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 40.))
+        });
+
+        // an on-screen target does not scroll
+        cx.simulate_keystrokes("g g");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 0.))
+        });
+        cx.simulate_keystrokes("1 0 shift-g");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 0.))
+        });
+
+        // search motions are jumps as well
+        let mut lines = lines;
+        for row in [0, 59, 89] {
+            lines[row] = "target".to_string();
+        }
+        cx.set_state(&format!("ˇ{}", lines.join("\n")), Mode::Normal);
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 50.))
+        });
+        cx.simulate_keystrokes("n");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 80.))
+        });
+        cx.simulate_keystrokes("shift-n");
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.snapshot(window, cx).scroll_position(), point(0., 50.))
+        });
+    }
 }
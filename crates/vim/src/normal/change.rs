@@ -1,6 +1,6 @@
 use crate::{
     Vim,
-    motion::{self, Motion, MotionKind},
+    motion::{self, Keyword, Motion, MotionKind},
     object::Object,
     state::Mode,
 };
@@ -12,6 +12,7 @@ use editor::{
 };
 use gpui::{Context, Window};
 use language::Selection;
+use std::sync::Arc;
 
 impl Vim {
     pub fn change_motion(
@@ -42,16 +43,28 @@ impl Vim {
                 editor.set_clip_at_line_ends(false, cx);
                 editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                     s.move_with(|map, selection| {
-                        let kind = match motion {
-                            Motion::NextWordStart { ignore_punctuation }
-                            | Motion::NextSubwordStart { ignore_punctuation } => {
+                        let kind = match &motion {
+                            Motion::NextWordStart {
+                                ignore_punctuation,
+                                keyword,
+                            } => expand_changed_word_selection(
+                                map,
+                                selection,
+                                times,
+                                *ignore_punctuation,
+                                keyword.as_ref(),
+                                &text_layout_details,
+                                false,
+                            ),
+                            Motion::NextSubwordStart { ignore_punctuation } => {
                                 expand_changed_word_selection(
                                     map,
                                     selection,
                                     times,
-                                    ignore_punctuation,
+                                    *ignore_punctuation,
+                                    None,
                                     &text_layout_details,
-                                    motion == Motion::NextSubwordStart { ignore_punctuation },
+                                    true,
                                 )
                             }
                             _ => {
@@ -106,6 +119,7 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -116,7 +130,7 @@ impl Vim {
             editor.transact(window, cx, |editor, window, cx| {
                 editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                     s.move_with(|map, selection| {
-                        objects_found |= object.expand_selection(map, selection, around);
+                        objects_found |= object.expand_selection(map, selection, around, times);
                     });
                 });
                 if objects_found {
@@ -133,6 +147,57 @@ impl Vim {
             self.switch_mode(Mode::Normal, false, window, cx);
         }
     }
+
+    /// `cX{char}`: changes the next occurrence of `char` at or after the cursor, wrapping
+    /// around the end of the buffer, so that `.` changes the occurrence after that.
+    pub fn change_to_match(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(target) = text.chars().next() else {
+            return;
+        };
+        Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        let mut match_found = false;
+        self.update_editor(window, cx, |vim, editor, window, cx| {
+            editor.set_clip_at_line_ends(false, cx);
+            editor.transact(window, cx, |editor, window, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.move_with(|map, selection| {
+                        let start = selection.head().to_offset(map, Bias::Left);
+                        let Some(offset) = map
+                            .buffer_chars_at(start)
+                            .chain(
+                                map.buffer_chars_at(0)
+                                    .take_while(|(_, offset)| *offset < start),
+                            )
+                            .find(|(ch, _)| *ch == target)
+                            .map(|(_, offset)| offset)
+                        else {
+                            return;
+                        };
+                        match_found = true;
+                        selection.start = offset.to_display_point(map);
+                        selection.end = (offset + target.len_utf8()).to_display_point(map);
+                    });
+                });
+                if match_found {
+                    vim.copy_selections_content(editor, MotionKind::Exclusive, window, cx);
+                    editor.insert("", window, cx);
+                    editor.refresh_inline_completion(true, false, window, cx);
+                }
+            });
+        });
+
+        if match_found {
+            self.switch_mode(Mode::Insert, false, window, cx);
+        } else {
+            self.switch_mode(Mode::Normal, false, window, cx);
+        }
+    }
 }
 
 // From the docs https://vimdoc.sourceforge.net/htmldoc/motion.html
@@ -146,6 +211,7 @@ fn expand_changed_word_selection(
     selection: &mut Selection<DisplayPoint>,
     times: Option<usize>,
     ignore_punctuation: bool,
+    keyword: Option<&Arc<Keyword>>,
     text_layout_details: &TextLayoutDetails,
     use_subword: bool,
 ) -> Option<MotionKind> {
@@ -173,8 +239,14 @@ fn expand_changed_word_selection(
                     selection.end =
                         motion::next_subword_end(map, selection.end, ignore_punctuation, 1, false);
                 } else {
-                    selection.end =
-                        motion::next_word_end(map, selection.end, ignore_punctuation, 1, false);
+                    selection.end = motion::next_word_end(
+                        map,
+                        selection.end,
+                        ignore_punctuation,
+                        keyword,
+                        1,
+                        false,
+                    );
                 }
                 selection.end = motion::next_char(map, selection.end, false);
             }
@@ -184,7 +256,10 @@ fn expand_changed_word_selection(
         let motion = if use_subword {
             Motion::NextSubwordStart { ignore_punctuation }
         } else {
-            Motion::NextWordStart { ignore_punctuation }
+            Motion::NextWordStart {
+                ignore_punctuation,
+                keyword: keyword.cloned(),
+            }
         };
         motion.expand_selection(map, selection, times, text_layout_details, false)
     }
@@ -194,7 +269,10 @@ fn expand_changed_word_selection(
 mod test {
     use indoc::indoc;
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_change_h(cx: &mut gpui::TestAppContext) {
@@ -688,4 +766,21 @@ mod test {
             .assert_matches();
         }
     }
+
+    #[gpui::test]
+    async fn test_change_to_match(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇfoo(a, b)\nbar(c, d)", Mode::Normal);
+        cx.simulate_keystrokes("c shift-x , ; escape");
+        cx.assert_state("foo(aˇ; b)\nbar(c, d)", Mode::Normal);
+        cx.simulate_keystrokes("l .");
+        cx.assert_state("foo(a; b)\nbar(cˇ; d)", Mode::Normal);
+        cx.simulate_keystrokes("g g .");
+        cx.assert_state("ˇfoo(a; b)\nbar(c; d)", Mode::Normal);
+
+        cx.set_state("one two\nthreˇe", Mode::Normal);
+        cx.simulate_keystrokes("c shift-x o 0 escape");
+        cx.assert_state("ˇ0ne two\nthree", Mode::Normal);
+    }
 }
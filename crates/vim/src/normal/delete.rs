@@ -1,6 +1,6 @@
 use crate::{
     Vim,
-    motion::{Motion, MotionKind},
+    motion::{self, Motion, MotionKind},
     object::Object,
 };
 use collections::{HashMap, HashSet};
@@ -92,6 +92,7 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -102,9 +103,16 @@ impl Vim {
                 // Emulates behavior in vim where if we expanded backwards to include a newline
                 // the cursor gets set back to the start of the line
                 let mut should_move_to_start: HashSet<_> = Default::default();
+                let mut deleted_lines: HashSet<_> = Default::default();
                 editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                     s.move_with(|map, selection| {
-                        object.expand_selection(map, selection, around);
+                        object.expand_selection(map, selection, around, times);
+                        if matches!(object, Object::Word { .. }) {
+                            if expand_to_whole_lines(map, selection) {
+                                deleted_lines.insert(selection.id);
+                            }
+                            return;
+                        }
                         let offset_range = selection.map(|p| p.to_offset(map, Bias::Left)).range();
                         let mut move_selection_start_to_previous_line =
                             |map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>| {
@@ -165,6 +173,9 @@ impl Vim {
                         if should_move_to_start.contains(&selection.id) {
                             *cursor.column_mut() = 0;
                         }
+                        if deleted_lines.contains(&selection.id) {
+                            cursor = motion::first_non_whitespace(map, false, cursor);
+                        }
                         cursor = map.clip_point(cursor, Bias::Left);
                         selection.collapse_to(cursor, selection.goal)
                     });
@@ -175,6 +186,42 @@ impl Vim {
     }
 }
 
+/// Vim deletes whole lines when a deletion spans several lines, starts in the
+/// indentation and ends at the end of a line, so that `2daw` over the last words of a
+/// paragraph doesn't leave blank lines behind.
+fn expand_to_whole_lines(map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>) -> bool {
+    let start = selection.start.to_point(map);
+    let end = selection.end.to_point(map);
+    if end.row == start.row {
+        return false;
+    }
+    let line_start = Point::new(start.row, 0);
+    let line_end = Point::new(end.row, map.buffer_snapshot.line_len(MultiBufferRow(end.row)));
+    let only_whitespace = |range: std::ops::Range<Point>| {
+        map.buffer_snapshot
+            .text_for_range(range)
+            .flat_map(|chunk| chunk.chars())
+            .all(|ch| ch == ' ' || ch == '\t')
+    };
+    if !only_whitespace(line_start..start) || !only_whitespace(end..line_end) {
+        return false;
+    }
+
+    if end.row < map.buffer_snapshot.max_point().row {
+        selection.start = line_start.to_display_point(map);
+        selection.end = Point::new(end.row + 1, 0).to_display_point(map);
+    } else if start.row > 0 {
+        let row = start.row - 1;
+        let row_end = Point::new(row, map.buffer_snapshot.line_len(MultiBufferRow(row)));
+        selection.start = row_end.to_display_point(map);
+        selection.end = line_end.to_display_point(map);
+    } else {
+        selection.start = line_start.to_display_point(map);
+        selection.end = line_end.to_display_point(map);
+    }
+    true
+}
+
 fn move_selection_end_to_next_line(map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>) {
     let end = selection.end.to_offset(map, Bias::Left);
     selection.end = (end + '\n'.len_utf8()).to_display_point(map);
@@ -0,0 +1,121 @@
+use crate::Vim;
+use editor::Editor;
+use gpui::{Context, Window, actions};
+
+actions!(
+    vim,
+    [Fold, Unfold, ToggleFold, FoldRecursive, UnfoldRecursive]
+);
+
+pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, _: &Fold, window, cx| {
+        vim.fold(window, cx, |editor, count, window, cx| match count {
+            Some(count) => editor.fold_levels(count, window, cx),
+            None => editor.fold(&editor::actions::Fold, window, cx),
+        })
+    });
+    Vim::action(editor, cx, |vim, _: &Unfold, window, cx| {
+        vim.fold(window, cx, |editor, count, window, cx| match count {
+            Some(count) => editor.unfold_levels(count, window, cx),
+            None => editor.unfold_lines(&editor::actions::UnfoldLines, window, cx),
+        })
+    });
+    Vim::action(editor, cx, |vim, _: &ToggleFold, window, cx| {
+        vim.fold(window, cx, |editor, count, window, cx| match count {
+            Some(count) => editor.toggle_fold_levels(count, window, cx),
+            None => editor.toggle_fold(&editor::actions::ToggleFold, window, cx),
+        })
+    });
+    Vim::action(editor, cx, |vim, _: &FoldRecursive, window, cx| {
+        vim.fold(window, cx, |editor, count, window, cx| match count {
+            Some(count) => editor.fold_levels(count, window, cx),
+            None => editor.fold_recursive(&editor::actions::FoldRecursive, window, cx),
+        })
+    });
+    Vim::action(editor, cx, |vim, _: &UnfoldRecursive, window, cx| {
+        vim.fold(window, cx, |editor, count, window, cx| match count {
+            Some(count) => editor.unfold_levels(count, window, cx),
+            None => editor.unfold_recursive(&editor::actions::UnfoldRecursive, window, cx),
+        })
+    });
+}
+
+impl Vim {
+    /// Runs a fold command with the pending count, which limits how many levels of nested
+    /// folds it opens or closes.
+    fn fold(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        fold: fn(&mut Editor, Option<usize>, &mut Window, &mut Context<Editor>),
+    ) {
+        let count = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            fold(editor, count, window, cx)
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_fold_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let text = indoc! {"
+            fn a() {
+                if b {
+                    c();
+                    d();
+                }
+            }
+            fn e() {}"
+        };
+        let all_folded = indoc! {"
+            fn a() {⋯
+            }
+            fn e() {}"
+        };
+        let inner_folded = indoc! {"
+            fn a() {
+                if b {⋯
+                }
+            }
+            fn e() {}"
+        };
+        cx.set_state(
+            indoc! {"
+                fn a() {
+                    if b {
+                        c();
+                        ˇd();
+                    }
+                }
+                fn e() {}"
+            },
+            Mode::Normal,
+        );
+        let display_text = |cx: &mut VimTestContext| {
+            cx.update_editor(|editor, _, cx| editor.display_text(cx))
+        };
+
+        cx.simulate_keystrokes("2 z c");
+        assert_eq!(display_text(&mut cx), all_folded);
+
+        cx.simulate_keystrokes("1 z o");
+        assert_eq!(display_text(&mut cx), inner_folded);
+
+        cx.simulate_keystrokes("3 z o");
+        assert_eq!(display_text(&mut cx), text);
+
+        cx.simulate_keystrokes("2 z a");
+        assert_eq!(display_text(&mut cx), all_folded);
+
+        cx.simulate_keystrokes("z shift-o");
+        assert_eq!(display_text(&mut cx), text);
+    }
+}
@@ -1,5 +1,7 @@
 use std::{ops::Range, path::Path, sync::Arc};
 
+use anyhow::anyhow;
+
 use editor::{
     Anchor, Bias, DisplayPoint, Editor, MultiBuffer,
     display_map::{DisplaySnapshot, ToDisplayPoint},
@@ -8,12 +10,13 @@ use editor::{
 };
 use gpui::{Context, Entity, EntityId, UpdateGlobal, Window};
 use language::SelectionGoal;
+use settings::Settings;
 use text::Point;
 use ui::App;
-use workspace::OpenOptions;
+use workspace::{OpenOptions, pane::GoBack};
 
 use crate::{
-    Vim,
+    Vim, VimSettings,
     motion::{self, Motion},
     state::{Mark, Mode, VimGlobals},
 };
@@ -127,6 +130,7 @@ impl Vim {
     fn open_path_mark(
         &mut self,
         line: bool,
+        name: String,
         path: Arc<Path>,
         points: Vec<Point>,
         window: &mut Window,
@@ -135,19 +139,31 @@ impl Vim {
         let Some(workspace) = self.workspace(window) else {
             return;
         };
-        let task = workspace.update(cx, |workspace, cx| {
-            workspace.open_abs_path(
-                path.to_path_buf(),
-                OpenOptions {
-                    visible: Some(workspace::OpenVisible::All),
-                    focus: Some(true),
-                    ..Default::default()
-                },
-                window,
-                cx,
-            )
-        });
+        let fs = workspace.read(cx).app_state().fs.clone();
         cx.spawn_in(window, async move |this, cx| {
+            // Marks are restored from previous sessions, so the file may have gone away since.
+            if !fs.is_file(&path).await {
+                this.update_in(cx, |vim, window, cx| {
+                    vim.update_editor(window, cx, |vim, editor, window, cx| {
+                        vim.delete_mark(name.clone(), editor.buffer(), window, cx)
+                    });
+                })?;
+                return Err(anyhow!("mark {name} points to {path:?}, which no longer exists"));
+            }
+            let task = this.update_in(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.open_abs_path(
+                        path.to_path_buf(),
+                        OpenOptions {
+                            visible: Some(workspace::OpenVisible::All),
+                            focus: Some(true),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })
+            })?;
             let editor = task.await?;
             this.update_in(cx, |_, window, cx| {
                 if let Some(editor) = editor.act_as::<Editor>(cx) {
@@ -203,7 +219,7 @@ impl Vim {
                 return;
             }
             Some(Mark::Path(path, points)) => {
-                self.open_path_mark(line, path, points, window, cx);
+                self.open_path_mark(line, text.to_string(), path, points, window, cx);
                 return;
             }
         };
@@ -265,6 +281,91 @@ impl Vim {
         }
     }
 
+    pub(crate) fn record_jump(
+        &mut self,
+        anchor: Anchor,
+        buffer_entity: &Entity<MultiBuffer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let entity_id = workspace.entity_id();
+        Vim::update_globals(cx, |vim_globals, cx| {
+            let Some(marks_state) = vim_globals.marks.get(&entity_id) else {
+                return;
+            };
+            marks_state.update(cx, |ms, cx| ms.record_jump(buffer_entity, anchor, cx));
+        });
+    }
+
+    /// The panes' navigation history starts empty in every session, so once it runs out,
+    /// ctrl-o goes on through the jumps persisted from earlier sessions.
+    pub(crate) fn go_back(&mut self, _: &GoBack, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            cx.propagate();
+            return;
+        };
+        let pane = workspace.read(cx).active_pane().clone();
+        let marks_state = Vim::globals(cx).marks.get(&workspace.entity_id()).cloned();
+        let Some(marks_state) = marks_state.filter(|_| !pane.read(cx).can_navigate_backward())
+        else {
+            cx.propagate();
+            return;
+        };
+        let fs = workspace.read(cx).app_state().fs.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let (path, point) = loop {
+                let Some((path, point)) =
+                    marks_state.update(cx, |ms, _| ms.take_restored_jump())?
+                else {
+                    return Ok(());
+                };
+                if fs.is_file(&path).await {
+                    break (path, point);
+                }
+                marks_state.update(cx, |ms, cx| ms.forget_jumps_to(&path, cx))?;
+            };
+
+            // Going back must not add to the pane's history, or the next ctrl-o would return
+            // to where this one started.
+            pane.update(cx, |pane, _| pane.disable_history())?;
+            let task = this.update_in(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.open_abs_path(
+                        path.to_path_buf(),
+                        OpenOptions {
+                            visible: Some(workspace::OpenVisible::All),
+                            focus: Some(true),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })
+            });
+            let item = match task {
+                Ok(task) => task.await,
+                Err(error) => Err(error),
+            };
+            let result = item.and_then(|item| {
+                this.update_in(cx, |_, window, cx| {
+                    if let Some(editor) = item.act_as::<Editor>(cx) {
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                                s.select_ranges([point..point])
+                            })
+                        })
+                    }
+                })
+            });
+            pane.update(cx, |pane, _| pane.enable_history())?;
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn set_mark(
         &mut self,
         mut name: String,
@@ -290,6 +391,30 @@ impl Vim {
         });
     }
 
+    pub fn delete_mark(
+        &mut self,
+        mut name: String,
+        buffer_entity: &Entity<MultiBuffer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        if name == "`" {
+            name = "'".to_string();
+        }
+        let entity_id = workspace.entity_id();
+        Vim::update_globals(cx, |vim_globals, cx| {
+            let Some(marks_state) = vim_globals.marks.get(&entity_id) else {
+                return;
+            };
+            marks_state.update(cx, |ms, cx| {
+                ms.delete_mark(name, buffer_entity, cx);
+            });
+        });
+    }
+
     pub fn get_mark(
         &self,
         mut name: &str,
@@ -301,6 +426,7 @@ impl Vim {
             name = "'";
         }
         if matches!(name, "{" | "}" | "(" | ")") {
+            let skip_abbreviations = VimSettings::get_global(cx).smart_sentence_detection;
             let (map, selections) = editor.selections.all_display(cx);
             let anchors = selections
                 .into_iter()
@@ -308,8 +434,15 @@ impl Vim {
                     let point = match name {
                         "{" => movement::start_of_paragraph(&map, selection.head(), 1),
                         "}" => movement::end_of_paragraph(&map, selection.head(), 1),
-                        "(" => motion::sentence_backwards(&map, selection.head(), 1),
-                        ")" => motion::sentence_forwards(&map, selection.head(), 1),
+                        "(" => motion::sentence_backwards(
+                            &map,
+                            selection.head(),
+                            1,
+                            skip_abbreviations,
+                        ),
+                        ")" => {
+                            motion::sentence_forwards(&map, selection.head(), 1, skip_abbreviations)
+                        }
                         _ => unreachable!(),
                     };
                     map.buffer_snapshot
@@ -3,10 +3,11 @@ use gpui::{Context, Window, impl_actions};
 use language::{Bias, SelectionGoal};
 use schemars::JsonSchema;
 use serde::Deserialize;
+use settings::Settings;
 use std::cmp;
 
 use crate::{
-    Vim,
+    Vim, VimSettings,
     motion::{Motion, MotionKind},
     object::Object,
     state::{Mode, Register},
@@ -29,6 +30,8 @@ impl Vim {
         self.store_visual_marks(window, cx);
         let count = Vim::take_count(cx).unwrap_or(1);
         Vim::take_forced_motion(cx);
+        let preserve_clipboard =
+            action.preserve_clipboard || VimSettings::get_global(cx).visual_paste_keeps_register;
 
         self.update_editor(window, cx, |vim, editor, window, cx| {
             let text_layout_details = editor.text_layout_details(window);
@@ -50,7 +53,7 @@ impl Vim {
                 let clipboard_selections = clipboard_selections
                     .filter(|sel| sel.len() > 1 && vim.mode != Mode::VisualLine);
 
-                if !action.preserve_clipboard && vim.mode.is_visual() {
+                if !preserve_clipboard && vim.mode.is_visual() {
                     vim.copy_selections_content(editor, MotionKind::for_mode(vim.mode), window, cx);
                 }
 
@@ -212,6 +215,7 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -222,7 +226,7 @@ impl Vim {
                 editor.set_clip_at_line_ends(false, cx);
                 editor.change_selections(None, window, cx, |s| {
                     s.move_with(|map, selection| {
-                        object.expand_selection(map, selection, around);
+                        object.expand_selection(map, selection, around, times);
                     });
                 });
 
@@ -555,6 +559,37 @@ mod test {
         cx.shared_clipboard().await.assert_eq("The quick brown\n");
     }
 
+    #[gpui::test]
+    async fn test_visual_paste_keeps_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Never);
+                s.visual_paste_keeps_register = Some(true);
+            });
+        });
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y i w w v i w p");
+        cx.assert_state("one onˇe three", Mode::Normal);
+        cx.simulate_keystrokes("w v i w p");
+        cx.assert_state("one one onˇe", Mode::Normal);
+
+        // without the setting `p` yanks the replaced text, but `P` doesn't
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.visual_paste_keeps_register = Some(false);
+            });
+        });
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y i w w v i w p w v i w p");
+        cx.assert_state("one one twˇo", Mode::Normal);
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y i w w v i w shift-p w v i w shift-p");
+        cx.assert_state("one one onˇe", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_paste_visual_block(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -814,6 +849,45 @@ mod test {
         cx.shared_register('a').await.assert_eq(" over");
     }
 
+    #[gpui::test]
+    async fn test_count_around_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Never)
+            });
+        });
+
+        cx.set_shared_state("ˇ1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12")
+            .await;
+        cx.simulate_shared_keystrokes("\" a 3 y y").await;
+        cx.shared_register('a').await.assert_eq("1\n2\n3\n");
+        cx.simulate_shared_keystrokes("3 \" b y y").await;
+        cx.shared_register('b').await.assert_eq("1\n2\n3\n");
+        cx.simulate_shared_keystrokes("2 \" c 3 y y").await;
+        cx.shared_register('c').await.assert_eq("1\n2\n3\n4\n5\n6\n");
+
+        cx.simulate_shared_keystrokes("j \" a 2 d d").await;
+        cx.shared_state()
+            .await
+            .assert_eq("1\nˇ4\n5\n6\n7\n8\n9\n10\n11\n12");
+        cx.shared_register('a').await.assert_eq("2\n3\n");
+        cx.simulate_shared_keystrokes(".").await;
+        cx.shared_state()
+            .await
+            .assert_eq("1\nˇ6\n7\n8\n9\n10\n11\n12");
+        cx.shared_register('a').await.assert_eq("4\n5\n");
+
+        cx.simulate_shared_keystrokes("2 \" b 2 d d").await;
+        cx.shared_state().await.assert_eq("1\nˇ10\n11\n12");
+        cx.shared_register('b').await.assert_eq("6\n7\n8\n9\n");
+        cx.simulate_shared_keystrokes("\" b 2 p").await;
+        cx.shared_state()
+            .await
+            .assert_eq("1\n10\nˇ6\n7\n8\n9\n6\n7\n8\n9\n11\n12");
+    }
+
     #[gpui::test]
     async fn test_special_registers(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
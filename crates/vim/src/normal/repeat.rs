@@ -7,6 +7,7 @@ use crate::{
     normal::InsertBefore,
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
 };
+use anyhow::anyhow;
 use editor::Editor;
 use gpui::{Action, App, Context, Window, actions};
 use workspace::Workspace;
@@ -64,7 +65,19 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, _: &ReplayLastRecording, window, cx| {
-        let Some(register) = Vim::globals(cx).last_recorded_register else {
+        let globals = Vim::globals(cx);
+        // finish an in-progress recording so that it is the one replayed
+        if let Some(char) = globals.recording_register.take() {
+            globals.last_recorded_register = Some(char)
+        }
+        let Some(register) = globals.last_recorded_register else {
+            Vim::take_count(cx);
+            vim.clear_operator(window, cx);
+            if let Some(workspace) = vim.workspace(window) {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.show_error(&anyhow!("E748: No previously used register"), cx)
+                })
+            }
             return;
         };
         vim.replay_register(register, window, cx)
@@ -204,7 +217,7 @@ impl Vim {
         let count = Vim::take_count(cx);
         Vim::take_forced_motion(cx);
 
-        let Some((mut actions, selection, mode)) = Vim::update_globals(cx, |globals, _| {
+        let Some((mut actions, selection, mode, register)) = Vim::update_globals(cx, |globals, _| {
             let actions = globals.recorded_actions.clone();
             if actions.is_empty() {
                 return None;
@@ -241,13 +254,17 @@ impl Vim {
                 }
             }
 
-            Some((actions, selection, mode))
+            Some((actions, selection, mode, globals.recorded_register))
         }) else {
             return;
         };
+        // Switching mode clears the selected register, but one typed before `.` should win
+        // over the register the change was recorded with.
+        let selected_register = self.selected_register.take();
         if let Some(mode) = mode {
             self.switch_mode(mode, false, window, cx)
         }
+        self.selected_register = selected_register.or(register);
 
         match selection {
             RecordedSelection::SingleLine { cols } => {
@@ -675,6 +692,19 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_repeat_with_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("\" a y y j \" b y y k \" a p");
+        cx.assert_state("one\nˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("\" b .");
+        cx.assert_state("one\none\nˇtwo\ntwo", Mode::Normal);
+        cx.simulate_keystrokes(".");
+        cx.assert_state("one\none\ntwo\nˇone\ntwo", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_record_interrupted(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -779,4 +809,29 @@ mod test {
         cx.simulate_shared_keystrokes("@ b").await;
         cx.shared_state().await.assert_eq("aaaaaaabbbˇd");
     }
+
+    #[gpui::test]
+    async fn test_replay_last_recording(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇhello world", Mode::Normal);
+        cx.workspace(|workspace, _, _| assert_eq!(workspace.notification_ids().len(), 1));
+
+        cx.simulate_keystrokes("q a r x l q");
+        cx.assert_state("xˇello world", Mode::Normal);
+        cx.simulate_keystrokes("2 shift-q");
+        cx.assert_state("xxxˇlo world", Mode::Normal);
+
+        // a macro that replays another one
+        cx.simulate_keystrokes("q b @ a q");
+        cx.assert_state("xxxxˇo world", Mode::Normal);
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("xxxxxˇ world", Mode::Normal);
+
+        // Q while recording ends the recording and replays it
+        cx.simulate_keystrokes("q c r y l shift-q");
+        cx.assert_state("xxxxxyyˇorld", Mode::Normal);
+    }
 }
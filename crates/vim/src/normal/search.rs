@@ -1,8 +1,8 @@
-use editor::Editor;
+use editor::{Editor, movement};
 use gpui::{Context, Window, actions, impl_actions, impl_internal_actions};
-use language::Point;
+use language::{Point, SelectionGoal};
 use schemars::JsonSchema;
-use search::{BufferSearchBar, SearchOptions, buffer_search};
+use search::{BufferSearchBar, ProjectSearchView, SearchOptions, buffer_search};
 use serde_derive::Deserialize;
 use std::{iter::Peekable, str::Chars};
 use util::serde::default_true;
@@ -59,17 +59,35 @@ pub struct ReplaceCommand {
     pub(crate) replacement: Replacement,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepeatReplaceCommand {
+    pub(crate) range: CommandRange,
+    pub(crate) keep_flags: bool,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct Replacement {
     search: String,
     replacement: String,
     should_replace_all: bool,
-    is_case_sensitive: bool,
+    /// `None` when neither `i` nor `I` was given, in which case `ignorecase` decides.
+    is_case_sensitive: Option<bool>,
 }
 
-actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPreviousMatch]);
+actions!(
+    vim,
+    [
+        SearchSubmit,
+        MoveToNextMatch,
+        MoveToPreviousMatch,
+        RepeatSubstitute,
+        RepeatSubstituteGlobally,
+        NextSearchResult,
+        PreviousSearchResult
+    ]
+);
 impl_actions!(vim, [FindCommand, Search, MoveToPrevious, MoveToNext]);
-impl_internal_actions!(vim, [ReplaceCommand]);
+impl_internal_actions!(vim, [ReplaceCommand, RepeatReplaceCommand]);
 
 pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::move_to_next);
@@ -78,8 +96,14 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::move_to_previous_match);
     Vim::action(editor, cx, Vim::search);
     Vim::action(editor, cx, Vim::search_deploy);
+    Vim::action(editor, cx, Vim::cancel);
     Vim::action(editor, cx, Vim::find_command);
     Vim::action(editor, cx, Vim::replace_command);
+    Vim::action(editor, cx, Vim::repeat_replace_command);
+    Vim::action(editor, cx, Vim::repeat_substitute);
+    Vim::action(editor, cx, Vim::repeat_substitute_globally);
+    Vim::action(editor, cx, Vim::next_search_result);
+    Vim::action(editor, cx, Vim::previous_search_result);
 }
 
 impl Vim {
@@ -158,6 +182,9 @@ impl Vim {
                     if action.backwards {
                         options |= SearchOptions::BACKWARDS;
                     }
+                    if !self.ignore_case {
+                        options |= SearchOptions::CASE_SENSITIVE;
+                    }
                     search_bar.set_search_options(options, cx);
                     let prior_mode = if self.temp_mode {
                         Mode::Insert
@@ -183,6 +210,31 @@ impl Vim {
         cx.propagate();
     }
 
+    // Like Neovim, escape in Normal mode also clears the highlights of the last search,
+    // just as :noh does.
+    fn cancel(&mut self, _: &editor::actions::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        cx.propagate();
+        if self.mode != Mode::Normal
+            || !self.operator_stack.is_empty()
+            || Vim::globals(cx).pre_count.is_some()
+        {
+            return;
+        }
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        pane.update(cx, |pane, cx| {
+            let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
+                return;
+            };
+            search_bar.update(cx, |search_bar, cx| {
+                if !search_bar.is_dismissed() {
+                    search_bar.dismiss(&buffer_search::Dismiss, window, cx);
+                }
+            });
+        });
+    }
+
     pub fn search_submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.store_visual_marks(window, cx);
         let Some(pane) = self.pane(window, cx) else {
@@ -264,6 +316,22 @@ impl Vim {
         let count = Vim::take_count(cx).unwrap_or(1);
         Vim::take_forced_motion(cx);
         let prior_selections = self.editor_selections(window, cx);
+        // In Visual mode the cursor is on the last selected character, not after it, so that is
+        // where the search continues from. `search_motion` restores the visual selection.
+        if self.mode == Mode::Visual {
+            self.update_editor(window, cx, |_, editor, window, cx| {
+                editor.change_selections(None, window, cx, |s| {
+                    s.move_with(|map, selection| {
+                        let cursor = if selection.reversed || selection.is_empty() {
+                            selection.head()
+                        } else {
+                            movement::left(map, selection.end)
+                        };
+                        selection.collapse_to(cursor, SelectionGoal::None);
+                    })
+                })
+            });
+        }
 
         let success = pane.update(cx, |pane, cx| {
             let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
@@ -278,6 +346,13 @@ impl Vim {
             })
         });
         if !success {
+            if self.mode == Mode::Visual {
+                self.update_editor(window, cx, |_, editor, window, cx| {
+                    editor.change_selections(None, window, cx, |s| {
+                        s.select_ranges(prior_selections)
+                    })
+                });
+            }
             return;
         }
 
@@ -292,6 +367,69 @@ impl Vim {
         );
     }
 
+    fn next_search_result(
+        &mut self,
+        _: &NextSearchResult,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_search_result(Direction::Next, window, cx)
+    }
+
+    fn previous_search_result(
+        &mut self,
+        _: &PreviousSearchResult,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_search_result(Direction::Prev, window, cx)
+    }
+
+    /// `]q` and `[q` step through the results of the project search in the active pane,
+    /// or the buffer search matches in the active editor when there is no project search.
+    fn move_to_search_result(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+
+        let project_search = pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+            .filter(|search_view| search_view.read(cx).has_matches());
+        let buffer_search = pane
+            .read(cx)
+            .toolbar()
+            .read(cx)
+            .item_of_type::<BufferSearchBar>()
+            .filter(|search_bar| search_bar.read(cx).has_active_match());
+        if project_search.is_none() && buffer_search.is_none() {
+            return;
+        }
+
+        self.update_editor(window, cx, |_, editor, _, cx| {
+            editor.create_nav_history_entry(cx);
+        });
+        if let Some(search_view) = project_search {
+            search_view.update(cx, |search_view, cx| {
+                search_view.select_match_with_count(direction, count, window, cx)
+            });
+        } else if let Some(search_bar) = buffer_search {
+            search_bar.update(cx, |search_bar, cx| {
+                if search_bar.show(window, cx) {
+                    search_bar.select_match(direction, count, window, cx)
+                }
+            });
+        }
+    }
+
     pub fn move_to_internal(
         &mut self,
         direction: Direction,
@@ -426,6 +564,7 @@ impl Vim {
         cx: &mut Context<Self>,
     ) {
         let replacement = action.replacement.clone();
+        let is_case_sensitive = replacement.is_case_sensitive.unwrap_or(!self.ignore_case);
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
             .zip(self.workspace(window))
@@ -458,14 +597,18 @@ impl Vim {
                     return None;
                 }
 
-                if replacement.is_case_sensitive {
+                if is_case_sensitive {
                     options.set(SearchOptions::CASE_SENSITIVE, true)
                 }
                 let search = if replacement.search.is_empty() {
                     search_bar.query(cx)
                 } else {
-                    replacement.search
+                    replacement.search.clone()
                 };
+                Vim::globals(cx).last_replacement = Some(Replacement {
+                    search: search.clone(),
+                    ..replacement.clone()
+                });
                 if search_bar.should_use_smartcase_search(cx) {
                     options.set(
                         SearchOptions::CASE_SENSITIVE,
@@ -512,6 +655,66 @@ impl Vim {
             .detach_and_log_err(cx);
         })
     }
+
+    /// `&`: repeat the last `:s` on the current line (or `count` lines), dropping `g`.
+    fn repeat_substitute(
+        &mut self,
+        _: &RepeatSubstitute,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        let action = RepeatReplaceCommand {
+            range: CommandRange::current_lines(count as u32),
+            keep_flags: false,
+        };
+        self.repeat_replace_command(&action, window, cx)
+    }
+
+    /// `:&` and `:&&`: repeat the last `:s` over a range. Only `:&&` keeps `g`; the case flag
+    /// is always carried forward.
+    fn repeat_replace_command(
+        &mut self,
+        action: &RepeatReplaceCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(mut replacement) = Vim::globals(cx).last_replacement.clone() else {
+            return;
+        };
+        if !action.keep_flags {
+            replacement.should_replace_all = false;
+        }
+        let action = ReplaceCommand {
+            range: action.range.clone(),
+            replacement,
+        };
+        self.replace_command(&action, window, cx)
+    }
+
+    /// `g&`: repeat the last `:s` over the whole file with the same flags,
+    /// using the last search pattern.
+    fn repeat_substitute_globally(
+        &mut self,
+        _: &RepeatSubstituteGlobally,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        let Some(replacement) = Vim::globals(cx).last_replacement.clone() else {
+            return;
+        };
+        let action = ReplaceCommand {
+            range: CommandRange::whole_file(),
+            replacement: Replacement {
+                search: String::new(),
+                ..replacement
+            },
+        };
+        self.replace_command(&action, window, cx)
+    }
 }
 
 impl Replacement {
@@ -572,15 +775,15 @@ impl Replacement {
             search,
             replacement,
             should_replace_all: false,
-            is_case_sensitive: true,
+            is_case_sensitive: None,
         };
 
         for c in flags.chars() {
             match c {
                 'g' => replacement.should_replace_all = true,
                 'c' | 'n' => replacement.should_replace_all = false,
-                'i' => replacement.is_case_sensitive = false,
-                'I' => replacement.is_case_sensitive = true,
+                'i' => replacement.is_case_sensitive = Some(false),
+                'I' => replacement.is_case_sensitive = Some(true),
                 _ => {}
             }
         }
@@ -787,6 +990,52 @@ mod test {
         cx.assert_state("aa\nbb\nˇcc\ncc\ncc\n", Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_escape_clears_search_highlights(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇaa\ncc\ncc\n", Mode::Normal);
+        cx.simulate_keystrokes("/ c c enter");
+        cx.run_until_parked();
+        cx.assert_state("aa\nˇcc\ncc\n", Mode::Normal);
+        cx.update_editor(|editor, window, cx| {
+            assert_eq!(editor.all_text_background_highlights(window, cx).len(), 2);
+        });
+
+        cx.simulate_keystrokes("escape");
+        cx.run_until_parked();
+        cx.update_editor(|editor, window, cx| {
+            assert!(editor.all_text_background_highlights(window, cx).is_empty());
+        });
+
+        cx.simulate_keystrokes("n");
+        cx.assert_state("aa\ncc\nˇcc\n", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_search_result_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // without a search there is nothing to step through
+        cx.set_state("ˇzero one two one two one", Mode::Normal);
+        cx.simulate_keystrokes("] q");
+        cx.assert_state("ˇzero one two one two one", Mode::Normal);
+
+        cx.simulate_keystrokes("/ o n e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("zero ˇone two one two one", Mode::Normal);
+
+        cx.simulate_keystrokes("] q");
+        cx.assert_state("zero one two ˇone two one", Mode::Normal);
+        cx.simulate_keystrokes("] q");
+        cx.assert_state("zero one two one two ˇone", Mode::Normal);
+        cx.simulate_keystrokes("2 [ q");
+        cx.assert_state("zero ˇone two one two one", Mode::Normal);
+        cx.simulate_keystrokes("[ q");
+        cx.assert_state("zero one two one two ˇone", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_non_vim_search(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, false).await;
@@ -860,6 +1109,23 @@ mod test {
         cx.shared_state().await.assert_eq("a a a« a aˇ» a");
     }
 
+    #[gpui::test]
+    async fn test_v_search_n_keeps_anchor(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇone foo\ntwo xfoo\nthree foo").await;
+        cx.simulate_shared_keystrokes("/ f o o").await;
+        cx.simulate_shared_keystrokes("enter").await;
+        cx.simulate_shared_keystrokes("v j n").await;
+        cx.shared_state()
+            .await
+            .assert_eq("one «foo\ntwo xfˇ»oo\nthree foo");
+        cx.simulate_shared_keystrokes("shift-n").await;
+        cx.shared_state()
+            .await
+            .assert_eq("one «fˇ»oo\ntwo xfoo\nthree foo");
+    }
+
     #[gpui::test]
     async fn test_v_search_aa(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -957,6 +1223,111 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_repeat_substitute(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇa a
+            a a
+            a a
+            a a"
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": s / a / b enter").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "ˇb a
+            a a
+            a a
+            a a"
+        });
+        cx.simulate_shared_keystrokes("j &").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b a
+            ˇb a
+            a a
+            a a"
+        });
+        cx.simulate_shared_keystrokes("j 2 &").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b a
+            b a
+            b a
+            ˇb a"
+        });
+        cx.simulate_shared_keystrokes("g &").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b b
+            b b
+            b b
+            ˇb b"
+        });
+    }
+
+    #[gpui::test]
+    async fn test_repeat_substitute_flags(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇa a
+            a a
+            a a
+            a a"
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": s / a / b / g enter").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "ˇb b
+            a a
+            a a
+            a a"
+        });
+        cx.simulate_shared_keystrokes("j &").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b b
+            ˇb a
+            a a
+            a a"
+        });
+        cx.simulate_shared_keystrokes("j : s / a / c / g enter").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b b
+            b a
+            ˇc c
+            a a"
+        });
+        cx.simulate_shared_keystrokes("j : & & enter").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b b
+            b a
+            c c
+            ˇc c"
+        });
+        cx.simulate_shared_keystrokes("g &").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "b b
+            ˇb c
+            c c
+            c c"
+        });
+    }
+
+    #[gpui::test]
+    async fn test_repeat_substitute_case(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇA a\nA a\nA a\nA a", Mode::Normal);
+        cx.simulate_keystrokes(": s / a / b enter");
+        cx.assert_state("ˇb a\nA a\nA a\nA a", Mode::Normal);
+        cx.simulate_keystrokes("j : s / a / b / I enter");
+        cx.assert_state("b a\nˇA b\nA a\nA a", Mode::Normal);
+        cx.simulate_keystrokes("j &");
+        cx.assert_state("b a\nA b\nˇA b\nA a", Mode::Normal);
+        cx.simulate_keystrokes(": s e t space n o i c enter");
+        cx.simulate_keystrokes("j : s / a / b enter");
+        cx.assert_state("b a\nA b\nA b\nˇA b", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_replace_with_range(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
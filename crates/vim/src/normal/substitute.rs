@@ -11,24 +11,35 @@ actions!(vim, [Substitute, SubstituteLine]);
 
 pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &Substitute, window, cx| {
-        vim.start_recording(cx);
-        let count = Vim::take_count(cx);
-        Vim::take_forced_motion(cx);
-        vim.substitute(count, vim.mode == Mode::VisualLine, window, cx);
+        vim.substitute_char(window, cx)
     });
 
     Vim::action(editor, cx, |vim, _: &SubstituteLine, window, cx| {
-        vim.start_recording(cx);
-        if matches!(vim.mode, Mode::VisualBlock | Mode::Visual) {
-            vim.switch_mode(Mode::VisualLine, false, window, cx)
-        }
-        let count = Vim::take_count(cx);
-        Vim::take_forced_motion(cx);
-        vim.substitute(count, true, window, cx)
+        vim.substitute_line(window, cx)
     });
 }
 
 impl Vim {
+    /// `s`: replaces `count` characters (or the visual selection) and enters insert mode.
+    pub(crate) fn substitute_char(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.start_recording(cx);
+        let count = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        self.substitute(count, self.mode == Mode::VisualLine, window, cx);
+    }
+
+    /// `S`: replaces `count` lines (or the lines of the visual selection) and enters
+    /// insert mode.
+    pub(crate) fn substitute_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.start_recording(cx);
+        if matches!(self.mode, Mode::VisualBlock | Mode::Visual) {
+            self.switch_mode(Mode::VisualLine, false, window, cx)
+        }
+        let count = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        self.substitute(count, true, window, cx)
+    }
+
     pub fn substitute(
         &mut self,
         count: Option<usize>,
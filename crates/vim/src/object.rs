@@ -1,24 +1,30 @@
-use std::ops::Range;
+use std::{ops::Range, rc::Rc, sync::Arc};
 
 use crate::{
-    Vim,
+    CustomTextObjectConfig, Vim, VimSettings,
     motion::right,
     state::{Mode, Operator},
 };
+use anyhow::{Context as _, Result};
 use editor::{
     Bias, DisplayPoint, Editor, ToOffset,
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement::{self, FindRange},
 };
-use gpui::{Window, actions, impl_actions};
+use gpui::{App, KeyBinding, KeyBindingContextPredicate, Window, actions, impl_actions};
 use itertools::Itertools;
-use language::{BufferSnapshot, CharKind, Point, Selection, TextObject, TreeSitterOptions};
+use language::{
+    BufferSnapshot, CharClassifier, CharKind, Point, Selection, TextObject, TreeSitterOptions,
+};
 use multi_buffer::MultiBufferRow;
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::Deserialize;
+use settings::Settings;
 use ui::Context;
+use util::ResultExt;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Object {
     Word { ignore_punctuation: bool },
@@ -44,6 +50,9 @@ pub enum Object {
     Class,
     Comment,
     EntireFile,
+    /// One of the `vim.custom_text_objects`.
+    #[serde(skip)]
+    Custom(Arc<CustomTextObject>),
 }
 
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
@@ -66,6 +75,73 @@ struct IndentObj {
     include_below: bool,
 }
 
+/// Selects the custom text object with the given name from `vim.custom_text_objects`.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct TextObjectCustom {
+    name: String,
+}
+
+/// A compiled entry of `vim.custom_text_objects`.
+#[derive(Debug)]
+pub struct CustomTextObject {
+    name: String,
+    key: Option<String>,
+    start: Regex,
+    end: Regex,
+}
+
+impl CustomTextObject {
+    pub(crate) fn new(config: &CustomTextObjectConfig) -> Result<Self> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).with_context(|| {
+                format!("invalid pattern in custom text object {:?}", config.name)
+            })
+        };
+        Ok(Self {
+            name: config.name.clone(),
+            key: config.key.clone(),
+            start: compile(&config.start)?,
+            end: compile(&config.end)?,
+        })
+    }
+}
+
+impl PartialEq for CustomTextObject {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.start.as_str() == other.start.as_str()
+            && self.end.as_str() == other.end.as_str()
+    }
+}
+
+impl Eq for CustomTextObject {}
+
+/// Key bindings that select each of the `vim.custom_text_objects` after `i` or `a`.
+pub fn custom_text_object_key_bindings(cx: &App) -> Vec<KeyBinding> {
+    let Some(context) = KeyBindingContextPredicate::parse(
+        "vim_operator == a || vim_operator == i || vim_operator == cs",
+    )
+    .log_err() else {
+        return Vec::new();
+    };
+    let context = Rc::new(context);
+    VimSettings::get_global(cx)
+        .custom_text_objects
+        .iter()
+        .filter_map(|object| {
+            let key = match &object.key {
+                Some(key) => key.clone(),
+                None => object.name.chars().next()?.to_string(),
+            };
+            let action = TextObjectCustom {
+                name: object.name.clone(),
+            };
+            KeyBinding::load(&key, Box::new(action), Some(context.clone()), None).log_err()
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct CandidateRange {
     pub start: DisplayPoint,
@@ -252,7 +328,7 @@ fn find_mini_brackets(
     find_mini_delimiters(map, display_point, around, &is_bracket_delimiter)
 }
 
-impl_actions!(vim, [Word, Subword, IndentObj]);
+impl_actions!(vim, [Word, Subword, IndentObj, TextObjectCustom]);
 
 actions!(
     vim,
@@ -368,6 +444,18 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             vim.object(Object::IndentObj { include_below }, window, cx)
         },
     );
+    Vim::action(editor, cx, |vim, action: &TextObjectCustom, window, cx| {
+        let Some(object) = VimSettings::get_global(cx)
+            .custom_text_objects
+            .iter()
+            .find(|object| object.name == action.name)
+            .cloned()
+        else {
+            vim.clear_operator(window, cx);
+            return;
+        };
+        vim.object(Object::Custom(object), window, cx)
+    });
 }
 
 impl Vim {
@@ -385,7 +473,7 @@ impl Vim {
 }
 
 impl Object {
-    pub fn is_multiline(self) -> bool {
+    pub fn is_multiline(&self) -> bool {
         match self {
             Object::Word { .. }
             | Object::Subword { .. }
@@ -409,11 +497,12 @@ impl Object {
             | Object::Class
             | Object::EntireFile
             | Object::Comment
-            | Object::IndentObj { .. } => true,
+            | Object::IndentObj { .. }
+            | Object::Custom(_) => true,
         }
     }
 
-    pub fn always_expands_both_ways(self) -> bool {
+    pub fn always_expands_both_ways(&self) -> bool {
         match self {
             Object::Word { .. }
             | Object::Subword { .. }
@@ -437,11 +526,12 @@ impl Object {
             | Object::Comment
             | Object::EntireFile
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Custom(_) => true,
         }
     }
 
-    pub fn target_visual_mode(self, current_mode: Mode, around: bool) -> Mode {
+    pub fn target_visual_mode(&self, current_mode: Mode, around: bool) -> Mode {
         match self {
             Object::Word { .. }
             | Object::Subword { .. }
@@ -467,7 +557,8 @@ impl Object {
             | Object::Tag
             | Object::Comment
             | Object::Argument
-            | Object::IndentObj { .. } => Mode::Visual,
+            | Object::IndentObj { .. }
+            | Object::Custom(_) => Mode::Visual,
             Object::Method | Object::Class => {
                 if around {
                     Mode::VisualLine
@@ -480,25 +571,26 @@ impl Object {
     }
 
     pub fn range(
-        self,
+        &self,
         map: &DisplaySnapshot,
         selection: Selection<DisplayPoint>,
         around: bool,
+        times: Option<usize>,
     ) -> Option<Range<DisplayPoint>> {
         let relative_to = selection.head();
         match self {
-            Object::Word { ignore_punctuation } => {
-                if around {
-                    around_word(map, relative_to, ignore_punctuation)
-                } else {
-                    in_word(map, relative_to, ignore_punctuation)
-                }
-            }
+            Object::Word { ignore_punctuation } => word_object(
+                map,
+                relative_to,
+                *ignore_punctuation,
+                around,
+                times.unwrap_or(1),
+            ),
             Object::Subword { ignore_punctuation } => {
                 if around {
-                    around_subword(map, relative_to, ignore_punctuation)
+                    around_subword(map, relative_to, *ignore_punctuation)
                 } else {
-                    in_subword(map, relative_to, ignore_punctuation)
+                    in_subword(map, relative_to, *ignore_punctuation)
                 }
             }
             Object::Sentence => sentence(map, relative_to, around),
@@ -681,53 +773,333 @@ impl Object {
                 },
             ),
             Object::Argument => argument(map, relative_to, around),
-            Object::IndentObj { include_below } => indent(map, relative_to, around, include_below),
+            Object::IndentObj { include_below } => {
+                indent(map, relative_to, around, *include_below)
+            }
             Object::EntireFile => entire_file(map),
+            Object::Custom(object) => custom_text_object_range(map, relative_to, around, object),
+        }
+    }
+
+    /// Whether a count selects that many consecutive objects, each one starting where the
+    /// previous one ended. Objects that take a count themselves, or where it means nesting
+    /// depth, don't repeat.
+    pub fn repeats_with_count(&self) -> bool {
+        match self {
+            Object::Subword { .. } | Object::Sentence => true,
+            Object::Word { .. }
+            | Object::Paragraph
+            | Object::Quotes
+            | Object::BackQuotes
+            | Object::AnyQuotes
+            | Object::MiniQuotes
+            | Object::DoubleQuotes
+            | Object::VerticalBars
+            | Object::AnyBrackets
+            | Object::MiniBrackets
+            | Object::Parentheses
+            | Object::SquareBrackets
+            | Object::Tag
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::EntireFile
+            | Object::AngleBrackets
+            | Object::CurlyBrackets
+            | Object::Argument
+            | Object::IndentObj { .. }
+            | Object::Custom(_) => false,
         }
     }
 
     pub fn expand_selection(
-        self,
+        &self,
         map: &DisplaySnapshot,
         selection: &mut Selection<DisplayPoint>,
         around: bool,
+        times: Option<usize>,
     ) -> bool {
-        if let Some(range) = self.range(map, selection.clone(), around) {
-            selection.start = range.start;
-            selection.end = range.end;
-            true
-        } else {
-            false
+        let Some(mut range) = self.range(map, selection.clone(), around, times) else {
+            return false;
+        };
+
+        if self.repeats_with_count() {
+            for _ in 1..times.unwrap_or(1) {
+                let mut next = selection.clone();
+                next.start = range.end;
+                next.end = range.end;
+                match self.range(map, next, around, None) {
+                    Some(next_range) if next_range.end > range.end => range.end = next_range.end,
+                    _ => break,
+                }
+            }
         }
+
+        selection.start = range.start;
+        selection.end = range.end;
+        true
     }
 }
 
-/// Returns a range that surrounds the word `relative_to` is in.
+/// Returns the range of `times` words (or WORDs), following Vim's `current_word()`.
 ///
-/// If `relative_to` is at the start of a word, return the word.
-/// If `relative_to` is between words, return the space between.
-fn in_word(
+/// `iw` counts runs of whitespace as words of their own. `aw` takes each word together
+/// with the whitespace after it, and if the last word has none, the whitespace before
+/// the first word instead (unless that's indentation). Starting on whitespace, `aw`
+/// takes that whitespace and the word after it.
+fn word_object(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     ignore_punctuation: bool,
+    around: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
-    // Use motion::right so that we consider the character under the cursor when looking for the start
+    let point = relative_to.to_point(map);
     let classifier = map
         .buffer_snapshot
-        .char_classifier_at(relative_to.to_point(map))
+        .char_classifier_at(point)
         .ignore_punctuation(ignore_punctuation);
-    let start = movement::find_preceding_boundary_display_point(
+    let mut cursor = WordCursor {
         map,
-        right(map, relative_to, 1),
-        movement::FindRange::SingleLine,
-        |left, right| classifier.kind(left) != classifier.kind(right),
-    );
+        classifier,
+        point,
+    };
 
-    let end = movement::find_boundary(map, relative_to, FindRange::SingleLine, |left, right| {
-        classifier.kind(left) != classifier.kind(right)
-    });
+    cursor.back_in_line();
+    let mut start = cursor.point;
+    let mut include_white = false;
+    if cursor.is_blank() == around {
+        if !cursor.end_word() {
+            return None;
+        }
+    } else {
+        cursor.forward_word();
+        if cursor.point.column == 0 {
+            cursor.decl();
+        } else {
+            cursor.one_left();
+        }
+        include_white = around;
+    }
 
-    Some(start..end)
+    let mut inclusive = true;
+    for remaining in (1..times).rev() {
+        inclusive = true;
+        if cursor.incl() == WordStep::BufferEdge {
+            return None;
+        }
+        if around != cursor.is_blank() {
+            if !cursor.forward_word() && remaining > 1 {
+                return None;
+            }
+            // Stop on the last whitespace rather than the start of the next line.
+            inclusive = cursor.one_left();
+        } else if !cursor.end_word() {
+            return None;
+        }
+    }
+
+    if include_white && (!cursor.is_blank() || (cursor.point.column == 0 && !inclusive)) {
+        let end = cursor.point;
+        cursor.point = start;
+        if cursor.one_left() {
+            cursor.back_in_line();
+            if cursor.is_blank() && cursor.point.column > 0 {
+                start = cursor.point;
+            }
+        }
+        cursor.point = end;
+    }
+
+    let end = if inclusive {
+        match cursor.char() {
+            Some(ch) => Point::new(cursor.point.row, cursor.point.column + ch.len_utf8() as u32),
+            None => cursor.point,
+        }
+    } else if cursor.point.column == 0 && cursor.point.row > start.row {
+        let row = cursor.point.row - 1;
+        Point::new(row, cursor.line_len(row))
+    } else {
+        cursor.point
+    };
+    Some(start.to_display_point(map)..end.to_display_point(map))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WordStep {
+    InLine,
+    LineEnd,
+    CrossedLine,
+    BufferEdge,
+}
+
+/// Steps through the buffer like Vim's `inc()` and `dec()`, where the end of each line
+/// is a position of its own that counts as whitespace.
+struct WordCursor<'a> {
+    map: &'a DisplaySnapshot,
+    classifier: CharClassifier,
+    point: Point,
+}
+
+impl WordCursor<'_> {
+    fn line_len(&self, row: u32) -> u32 {
+        self.map.buffer_snapshot.line_len(MultiBufferRow(row))
+    }
+
+    fn char(&self) -> Option<char> {
+        if self.point.column >= self.line_len(self.point.row) {
+            return None;
+        }
+        self.map.buffer_snapshot.chars_at(self.point).next()
+    }
+
+    fn kind(&self) -> CharKind {
+        self.char()
+            .map_or(CharKind::Whitespace, |ch| self.classifier.kind(ch))
+    }
+
+    fn is_blank(&self) -> bool {
+        self.kind() == CharKind::Whitespace
+    }
+
+    fn inc(&mut self) -> WordStep {
+        if let Some(ch) = self.char() {
+            self.point.column += ch.len_utf8() as u32;
+            if self.char().is_some() {
+                WordStep::InLine
+            } else {
+                WordStep::LineEnd
+            }
+        } else if self.point.row < self.map.buffer_snapshot.max_point().row {
+            self.point = Point::new(self.point.row + 1, 0);
+            WordStep::CrossedLine
+        } else {
+            WordStep::BufferEdge
+        }
+    }
+
+    fn dec(&mut self) -> WordStep {
+        if self.point.column > 0 {
+            let len = self
+                .map
+                .buffer_snapshot
+                .reversed_chars_at(self.point)
+                .next()
+                .map_or(1, |ch| ch.len_utf8() as u32);
+            self.point.column -= len;
+            WordStep::InLine
+        } else if self.point.row > 0 {
+            let row = self.point.row - 1;
+            self.point = Point::new(row, self.line_len(row));
+            WordStep::CrossedLine
+        } else {
+            WordStep::BufferEdge
+        }
+    }
+
+    /// Moves forward, skipping over the end of a non-empty line.
+    fn incl(&mut self) -> WordStep {
+        let step = self.inc();
+        if step != WordStep::InLine && step != WordStep::BufferEdge && self.point.column > 0 {
+            self.inc()
+        } else {
+            step
+        }
+    }
+
+    /// Moves backward, skipping over the end of a non-empty line.
+    fn decl(&mut self) {
+        if self.dec() == WordStep::CrossedLine && self.point.column > 0 {
+            self.dec();
+        }
+    }
+
+    fn one_left(&mut self) -> bool {
+        if self.point.column == 0 {
+            return false;
+        }
+        self.dec();
+        true
+    }
+
+    /// Returns false when the end of the buffer was reached.
+    fn skip_kind(&mut self, kind: CharKind) -> bool {
+        while self.kind() == kind {
+            if self.inc() == WordStep::BufferEdge {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn back_in_line(&mut self) {
+        let kind = self.kind();
+        while self.point.column > 0 {
+            self.dec();
+            if self.kind() != kind {
+                self.inc();
+                break;
+            }
+        }
+    }
+
+    /// Moves to the end of the current word, or of the next one when on whitespace,
+    /// stopping early on an empty line.
+    fn end_word(&mut self) -> bool {
+        let kind = self.kind();
+        if self.inc() == WordStep::BufferEdge {
+            return false;
+        }
+        if kind != CharKind::Whitespace && self.kind() == kind {
+            if !self.skip_kind(kind) {
+                return false;
+            }
+        } else if kind == CharKind::Whitespace {
+            while self.is_blank() {
+                if self.point.column == 0 && self.line_len(self.point.row) == 0 {
+                    return true;
+                }
+                if self.inc() == WordStep::BufferEdge {
+                    return false;
+                }
+            }
+            if !self.skip_kind(self.kind()) {
+                return false;
+            }
+        }
+        self.dec();
+        true
+    }
+
+    /// Moves to the start of the next word, stopping at the end of the line or on an
+    /// empty line.
+    fn forward_word(&mut self) -> bool {
+        let kind = self.kind();
+        let last_line = self.point.row == self.map.buffer_snapshot.max_point().row;
+        let step = self.inc();
+        if step == WordStep::BufferEdge || (step != WordStep::InLine && last_line) {
+            return false;
+        }
+        if step != WordStep::InLine {
+            return true;
+        }
+        if kind != CharKind::Whitespace {
+            while self.kind() == kind {
+                if self.inc() != WordStep::InLine {
+                    return true;
+                }
+            }
+        }
+        while self.is_blank() {
+            if self.point.column == 0 && self.line_len(self.point.row) == 0 {
+                break;
+            }
+            if self.inc() != WordStep::InLine {
+                return true;
+            }
+        }
+        true
+    }
 }
 
 fn in_subword(
@@ -862,42 +1234,6 @@ pub fn surrounding_html_tag(
     None
 }
 
-/// Returns a range that surrounds the word and following whitespace
-/// relative_to is in.
-///
-/// If `relative_to` is at the start of a word, return the word and following whitespace.
-/// If `relative_to` is between words, return the whitespace back and the following word.
-///
-/// if in word
-///   delete that word
-///   if there is whitespace following the word, delete that as well
-///   otherwise, delete any preceding whitespace
-/// otherwise
-///   delete whitespace around cursor
-///   delete word following the cursor
-fn around_word(
-    map: &DisplaySnapshot,
-    relative_to: DisplayPoint,
-    ignore_punctuation: bool,
-) -> Option<Range<DisplayPoint>> {
-    let offset = relative_to.to_offset(map, Bias::Left);
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(offset)
-        .ignore_punctuation(ignore_punctuation);
-    let in_word = map
-        .buffer_chars_at(offset)
-        .next()
-        .map(|(c, _)| !classifier.is_whitespace(c))
-        .unwrap_or(false);
-
-    if in_word {
-        around_containing_word(map, relative_to, ignore_punctuation)
-    } else {
-        around_next_word(map, relative_to, ignore_punctuation)
-    }
-}
-
 fn around_subword(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -932,64 +1268,52 @@ fn around_subword(
     Some(start..end).map(|range| expand_to_include_whitespace(map, range, true))
 }
 
-fn around_containing_word(
-    map: &DisplaySnapshot,
-    relative_to: DisplayPoint,
-    ignore_punctuation: bool,
-) -> Option<Range<DisplayPoint>> {
-    in_word(map, relative_to, ignore_punctuation).map(|range| {
-        let line_start = DisplayPoint::new(range.start.row(), 0);
-        let is_first_word = map
-            .buffer_chars_at(line_start.to_offset(map, Bias::Left))
-            .take_while(|(ch, offset)| {
-                offset < &range.start.to_offset(map, Bias::Left) && ch.is_whitespace()
-            })
-            .count()
-            > 0;
-
-        if is_first_word {
-            // For first word on line, trim indentation
-            let mut expanded = expand_to_include_whitespace(map, range.clone(), true);
-            expanded.start = range.start;
-            expanded
-        } else {
-            expand_to_include_whitespace(map, range, true)
-        }
-    })
-}
+/// How far on either side of the cursor a custom text object is searched for.
+const CUSTOM_TEXT_OBJECT_SCAN_LIMIT: usize = 10_000;
 
-fn around_next_word(
+/// Finds the innermost match of `object` around `relative_to`: the last match of its start
+/// pattern before the cursor whose end, the first match of the end pattern after it, lies
+/// after the cursor. Without `around`, the matched patterns themselves are left out.
+fn custom_text_object_range(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
-    ignore_punctuation: bool,
+    around: bool,
+    object: &CustomTextObject,
 ) -> Option<Range<DisplayPoint>> {
-    let classifier = map
-        .buffer_snapshot
-        .char_classifier_at(relative_to.to_point(map))
-        .ignore_punctuation(ignore_punctuation);
-    // Get the start of the word
-    let start = movement::find_preceding_boundary_display_point(
-        map,
-        right(map, relative_to, 1),
-        FindRange::SingleLine,
-        |left, right| classifier.kind(left) != classifier.kind(right),
+    let snapshot = &map.buffer_snapshot;
+    let cursor = relative_to.to_offset(map, Bias::Left);
+    let scan_start = snapshot.clip_offset(
+        cursor.saturating_sub(CUSTOM_TEXT_OBJECT_SCAN_LIMIT),
+        Bias::Left,
     );
-
-    let mut word_found = false;
-    let end = movement::find_boundary(map, relative_to, FindRange::MultiLine, |left, right| {
-        let left_kind = classifier.kind(left);
-        let right_kind = classifier.kind(right);
-
-        let found = (word_found && left_kind != right_kind) || right == '\n' && left == '\n';
-
-        if right_kind != CharKind::Whitespace {
-            word_found = true;
+    let scan_end = snapshot.clip_offset(
+        (cursor + CUSTOM_TEXT_OBJECT_SCAN_LIMIT).min(snapshot.len()),
+        Bias::Right,
+    );
+    let text = snapshot
+        .text_for_range(scan_start..scan_end)
+        .collect::<String>();
+    let offset = cursor - scan_start;
+    let mut innermost = None;
+    for start in object.start.find_iter(&text) {
+        if start.start() > offset {
+            break;
         }
-
-        found
-    });
-
-    Some(start..end)
+        let Some(end) = object.end.find_at(&text, start.end()) else {
+            continue;
+        };
+        if end.end() > offset {
+            innermost = Some((start.range(), end.range()));
+        }
+    }
+    let (start, end) = innermost?;
+    let range = if around {
+        start.start..end.end
+    } else {
+        start.end..end.start
+    };
+    let range = scan_start + range.start..scan_start + range.end;
+    Some(range.start.to_display_point(map)..range.end.to_display_point(map))
 }
 
 fn entire_file(map: &DisplaySnapshot) -> Option<Range<DisplayPoint>> {
@@ -1658,9 +1982,14 @@ fn surrounding_markers(
 mod test {
     use gpui::KeyBinding;
     use indoc::indoc;
+    use settings::SettingsStore;
 
     use crate::{
-        object::{AnyBrackets, AnyQuotes, MiniBrackets},
+        CustomTextObjectConfig, VimSettings,
+        object::{
+            AnyBrackets, AnyQuotes, CUSTOM_TEXT_OBJECT_SCAN_LIMIT, MiniBrackets,
+            custom_text_object_key_bindings,
+        },
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
@@ -1717,6 +2046,59 @@ mod test {
             .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_word_object_counts(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.simulate("2 d a w", "ˇone two three four")
+            .await
+            .assert_matches();
+        cx.simulate("d 3 i w", "ˇfoo-bar baz")
+            .await
+            .assert_matches();
+        cx.simulate("2 d i w", "ˇone  two").await.assert_matches();
+        cx.simulate("2 d a shift-w", "ˇa-b c-d e-f")
+            .await
+            .assert_matches();
+        cx.simulate("c 2 i w", "one ˇtwo three four")
+            .await
+            .assert_matches();
+
+        // whitespace under the cursor is taken together with the next word
+        cx.simulate("d a w", "one ˇ  two three")
+            .await
+            .assert_matches();
+
+        // without whitespace after the last word, the whitespace before the first one
+        // is taken instead of the next line
+        cx.simulate("d a w", "one tˇwo\nthree")
+            .await
+            .assert_matches();
+        cx.simulate("2 d a w", "one tˇwo three\nfour")
+            .await
+            .assert_matches();
+
+        // deletions that span whole lines remove them
+        cx.simulate("2 d i w", "the end.\nˇ\n\nnext")
+            .await
+            .assert_matches();
+        cx.simulate("d 3 a w", "one\n  ˇtwo three\n  four")
+            .await
+            .assert_matches();
+
+        cx.set_shared_state("one ˇtwo three four").await;
+        cx.simulate_shared_keystrokes("y 2 a w").await;
+        cx.shared_clipboard().await.assert_eq("two three ");
+
+        cx.set_shared_state("ˇone two three").await;
+        cx.simulate_shared_keystrokes("v 2 a w").await;
+        cx.shared_state().await.assert_eq("«one two ˇ»three");
+
+        cx.simulate("d 3 a w", "ˇone two three four")
+            .await
+            .assert_matches();
+    }
+
     #[gpui::test]
     async fn test_visual_word_object(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1867,6 +2249,39 @@ mod test {
         }
     }
 
+    #[gpui::test]
+    async fn test_sentence_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("The quick brown? Fox Jˇumps! Over the lazy.", Mode::Normal);
+        cx.simulate_keystrokes("d i s");
+        cx.assert_state("The quick brown? ˇ Over the lazy.", Mode::Normal);
+
+        cx.set_state("The quick brown? Fox Jˇumps! Over the lazy.", Mode::Normal);
+        cx.simulate_keystrokes("d a s");
+        cx.assert_state("The quick brown? ˇOver the lazy.", Mode::Normal);
+
+        cx.set_state("The quick brown? Fox Jˇumps! Over the lazy.", Mode::Normal);
+        cx.simulate_keystrokes("c a s");
+        cx.assert_state("The quick brown? ˇOver the lazy.", Mode::Insert);
+
+        // At the end of a paragraph there is no trailing space, so the leading one is taken.
+        cx.set_state("The quick brown? Fox Jumps! Over the lazyˇ.", Mode::Normal);
+        cx.simulate_keystrokes("c a s");
+        cx.assert_state("The quick brown? Fox Jumps!ˇ", Mode::Insert);
+
+        cx.set_state("The quick brown? Fox Jˇumps! Over the lazy.", Mode::Normal);
+        cx.simulate_keystrokes("y a s");
+        cx.assert_state("The quick brown? ˇFox Jumps! Over the lazy.", Mode::Normal);
+        cx.simulate_keystrokes("P");
+        cx.assert_state("The quick brown? Fox Jumps!ˇ Fox Jumps! Over the lazy.", Mode::Normal);
+
+        // A count takes that many sentences, starting from the current one.
+        cx.set_state("One. Twˇo. Three. Four.", Mode::Normal);
+        cx.simulate_keystrokes("d 2 a s");
+        cx.assert_state("One. ˇFour.", Mode::Normal);
+    }
+
     // Test string with "`" for opening surrounders and "'" for closing surrounders
     const SURROUNDING_MARKER_STRING: &str = indoc! {"
         ˇTh'ˇe ˇ`ˇ'ˇquˇi`ˇck broˇ'wn`
@@ -2300,6 +2715,42 @@ mod test {
             "},
             Mode::Insert,
         );
+
+        // Blocks that end by dedenting, without a closing line
+        let yaml = indoc! {"
+            root:
+              child:
+                a: 1
+
+                bˇ: 2
+              other: 3
+        "};
+        cx.set_state(yaml, Mode::Normal);
+        cx.simulate_keystrokes("v i i");
+        cx.assert_state(
+            indoc! {"
+                root:
+                  child:
+                «    a: 1
+
+                    b: 2ˇ»
+                  other: 3
+            "},
+            Mode::Visual,
+        );
+        cx.set_state(yaml, Mode::Normal);
+        cx.simulate_keystrokes("v a i");
+        cx.assert_state(
+            indoc! {"
+                root:
+                «  child:
+                    a: 1
+
+                    b: 2ˇ»
+                  other: 3
+            "},
+            Mode::Visual,
+        );
     }
 
     #[gpui::test]
@@ -2326,6 +2777,39 @@ mod test {
         }
     }
 
+    #[gpui::test]
+    async fn test_custom_text_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.custom_text_objects = Some(vec![CustomTextObjectConfig {
+                    name: "log".into(),
+                    key: None,
+                    start: r"console\.log\(".into(),
+                    end: r"\)".into(),
+                }]);
+            });
+        });
+        cx.update(|_, cx| cx.bind_keys(custom_text_object_key_bindings(cx)));
+
+        cx.set_state("a; console.log(xˇ, y); b", Mode::Normal);
+        cx.simulate_keystrokes("c i l");
+        cx.assert_state("a; console.log(ˇ); b", Mode::Insert);
+
+        cx.set_state("a; console.log(x, ˇy); b", Mode::Normal);
+        cx.simulate_keystrokes("d a l");
+        cx.assert_state("a; ˇ; b", Mode::Normal);
+
+        cx.set_state("ˇa; console.log(x); b", Mode::Normal);
+        cx.simulate_keystrokes("d i l");
+        cx.assert_state("ˇa; console.log(x); b", Mode::Normal);
+
+        let padding = " ".repeat(CUSTOM_TEXT_OBJECT_SCAN_LIMIT);
+        cx.set_state(&format!("console.log({padding}ˇx)"), Mode::Normal);
+        cx.simulate_keystrokes("d i l");
+        cx.assert_state(&format!("console.log({padding}ˇx)"), Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_anyquotes_object(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
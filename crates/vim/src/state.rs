@@ -1,11 +1,12 @@
-use crate::command::command_interceptor;
+use crate::command::{ShowMarks, command_interceptor};
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
+use crate::normal::search::Replacement;
 use crate::surrounds::SurroundsType;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
-use collections::HashMap;
+use collections::{HashMap, VecDeque};
 use command_palette_hooks::{CommandPaletteFilter, CommandPaletteInterceptor};
 use db::define_connection;
 use db::sqlez_macros::sql;
@@ -23,7 +24,7 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme::ThemeSettings;
@@ -130,6 +131,7 @@ pub enum Operator {
     ToggleComments,
     ReplaceWithRegister,
     Exchange,
+    ChangeToMatch,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -194,6 +196,7 @@ impl From<String> for Register {
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
+    pub last_replacement: Option<Replacement>,
 
     pub dot_recording: bool,
     pub dot_replaying: bool,
@@ -202,10 +205,13 @@ pub struct VimGlobals {
     pub pre_count: Option<usize>,
     /// post_count is the number after an operator is specified (2 in 3d2d)
     pub post_count: Option<usize>,
+    /// register_count is the number typed before a register is selected (2 in 2"a3yy)
+    pub register_count: Option<usize>,
     pub forced_motion: bool,
     pub stop_recording_after_next_action: bool,
     pub ignore_current_insertion: bool,
     pub recorded_count: Option<usize>,
+    pub recorded_register: Option<char>,
     pub recording_actions: Vec<ReplayableAction>,
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
@@ -222,6 +228,9 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+
+    /// The directory `:cd` last left, which `:cd -` returns to.
+    pub previous_directory: Option<PathBuf>,
 }
 
 pub struct MarksState {
@@ -234,9 +243,18 @@ pub struct MarksState {
     serialized_marks: HashMap<Arc<Path>, HashMap<String, Vec<Point>>>,
     global_marks: HashMap<String, MarkLocation>,
 
+    /// The jumps made in this workspace, oldest first, including those of earlier sessions.
+    jump_list: VecDeque<(Arc<Path>, Point)>,
+    /// The jumps of earlier sessions that ctrl-o has not gone back to yet. The panes'
+    /// navigation history only covers the current session.
+    restored_jumps: Vec<(Arc<Path>, Point)>,
+
     _subscription: Subscription,
 }
 
+/// How many jumps are kept, like Vim's jumplist.
+const MAX_JUMP_LIST_LEN: usize = 100;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum MarkLocation {
     Buffer(EntityId),
@@ -271,6 +289,8 @@ impl MarksState {
                 watched_buffers: HashMap::default(),
                 serialized_marks: HashMap::default(),
                 global_marks: HashMap::default(),
+                jump_list: VecDeque::default(),
+                restored_jumps: Vec::default(),
                 _subscription: subscription,
             };
 
@@ -297,14 +317,15 @@ impl MarksState {
             let Some(workspace_id) = this.update(cx, |this, cx| this.workspace_id(cx))? else {
                 return Ok(());
             };
-            let (marks, paths) = cx
+            let (marks, paths, jumps) = cx
                 .background_spawn(async move {
                     let marks = DB.get_marks(workspace_id)?;
                     let paths = DB.get_global_marks_paths(workspace_id)?;
-                    anyhow::Ok((marks, paths))
+                    let jumps = DB.get_jump_list(workspace_id)?;
+                    anyhow::Ok((marks, paths, jumps))
                 })
                 .await?;
-            this.update(cx, |this, cx| this.loaded(marks, paths, cx))
+            this.update(cx, |this, cx| this.loaded(marks, paths, jumps, cx))
         })
         .detach_and_log_err(cx);
     }
@@ -313,12 +334,16 @@ impl MarksState {
         &mut self,
         marks: Vec<SerializedMark>,
         global_mark_paths: Vec<(String, Arc<Path>)>,
+        jumps: Vec<(Arc<Path>, Point)>,
         cx: &mut Context<Self>,
     ) {
         let Some(project) = self.project(cx) else {
             return;
         };
 
+        self.jump_list = jumps.iter().cloned().collect();
+        self.restored_jumps = jumps;
+
         for mark in marks {
             self.serialized_marks
                 .entry(mark.path)
@@ -530,6 +555,53 @@ impl MarksState {
         );
     }
 
+    pub fn record_jump(
+        &mut self,
+        multibuffer: &Entity<MultiBuffer>,
+        anchor: Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = multibuffer.read(cx).as_singleton() else {
+            return;
+        };
+        let Some(path) = self.path_for_buffer(&buffer, cx) else {
+            return;
+        };
+        let point = anchor.to_point(&multibuffer.read(cx).snapshot(cx));
+        let jump = (path.clone(), point);
+        if self.jump_list.back() == Some(&jump) {
+            return;
+        }
+        self.jump_list.push_back(jump);
+        if self.jump_list.len() > MAX_JUMP_LIST_LEN {
+            self.jump_list.pop_front();
+        }
+
+        let Some(workspace_id) = self.workspace_id(cx) else {
+            return;
+        };
+        cx.background_spawn(async move { DB.push_jump(workspace_id, path, point).await })
+            .detach_and_log_err(cx);
+    }
+
+    /// The most recent jump of an earlier session that ctrl-o hasn't gone back to yet.
+    pub fn take_restored_jump(&mut self) -> Option<(Arc<Path>, Point)> {
+        self.restored_jumps.pop()
+    }
+
+    /// Drops the jumps into a file that no longer exists.
+    pub fn forget_jumps_to(&mut self, path: &Path, cx: &mut Context<Self>) {
+        self.restored_jumps.retain(|(jump_path, _)| jump_path.as_ref() != path);
+        self.jump_list.retain(|(jump_path, _)| jump_path.as_ref() != path);
+
+        let Some(workspace_id) = self.workspace_id(cx) else {
+            return;
+        };
+        let path: Arc<Path> = path.into();
+        cx.background_spawn(async move { DB.delete_jumps_to(workspace_id, path).await })
+            .detach_and_log_err(cx);
+    }
+
     pub fn set_mark(
         &mut self,
         name: String,
@@ -573,6 +645,53 @@ impl MarksState {
         self.serialize_buffer_marks(abs_path, &buffer, cx)
     }
 
+    pub fn delete_mark(
+        &mut self,
+        name: String,
+        multi_buffer: &Entity<MultiBuffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let path = match self.global_marks.remove(&name) {
+            Some(MarkLocation::Buffer(entity_id)) => {
+                if let Some(marks) = self.multibuffer_marks.get_mut(&entity_id) {
+                    marks.remove(&name);
+                }
+                return;
+            }
+            Some(MarkLocation::Path(path)) => path,
+            None => {
+                if let Some(marks) = self.multibuffer_marks.get_mut(&multi_buffer.entity_id()) {
+                    marks.remove(&name);
+                }
+                let Some(path) = multi_buffer
+                    .read(cx)
+                    .as_singleton()
+                    .and_then(|buffer| self.path_for_buffer(&buffer, cx))
+                else {
+                    return;
+                };
+                path
+            }
+        };
+
+        let location = MarkLocation::Path(path.clone());
+        for (buffer_id, (watched_location, _, _)) in &self.watched_buffers {
+            if watched_location == &location {
+                if let Some(marks) = self.buffer_marks.get_mut(buffer_id) {
+                    marks.remove(&name);
+                }
+            }
+        }
+        if let Some(marks) = self.serialized_marks.get_mut(&path) {
+            marks.remove(&name);
+        }
+
+        if let Some(workspace_id) = self.workspace_id(cx) {
+            cx.background_spawn(async move { DB.delete_mark(workspace_id, path, name).await })
+                .detach_and_log_err(cx);
+        }
+    }
+
     pub fn get_mark(
         &self,
         name: &str,
@@ -956,6 +1075,7 @@ impl Operator {
             Operator::Rewrap => "gq",
             Operator::ReplaceWithRegister => "gR",
             Operator::Exchange => "cx",
+            Operator::ChangeToMatch => "cX",
             Operator::Outdent => "<",
             Operator::Uppercase => "gU",
             Operator::Lowercase => "gu",
@@ -999,7 +1119,8 @@ impl Operator {
             | Operator::Digraph { .. }
             | Operator::Literal { .. }
             | Operator::ChangeSurrounds { target: Some(_) }
-            | Operator::DeleteSurrounds => true,
+            | Operator::DeleteSurrounds
+            | Operator::ChangeToMatch => true,
             Operator::Change
             | Operator::Delete
             | Operator::Yank
@@ -1021,6 +1142,46 @@ impl Operator {
         }
     }
 
+    /// Whether the operator acts on a motion or text object typed after it.
+    pub fn takes_motion(&self) -> bool {
+        match self {
+            Operator::Change
+            | Operator::Delete
+            | Operator::Yank
+            | Operator::Rewrap
+            | Operator::Indent
+            | Operator::Outdent
+            | Operator::AutoIndent
+            | Operator::ShellCommand
+            | Operator::Lowercase
+            | Operator::Uppercase
+            | Operator::OppositeCase
+            | Operator::Rot13
+            | Operator::Rot47
+            | Operator::ReplaceWithRegister
+            | Operator::Exchange
+            | Operator::ToggleComments
+            | Operator::AddSurrounds { target: None } => true,
+            Operator::Object { .. }
+            | Operator::FindForward { .. }
+            | Operator::FindBackward { .. }
+            | Operator::Sneak { .. }
+            | Operator::SneakBackward { .. }
+            | Operator::Mark
+            | Operator::Digraph { .. }
+            | Operator::Literal { .. }
+            | Operator::AddSurrounds { .. }
+            | Operator::ChangeSurrounds { .. }
+            | Operator::DeleteSurrounds
+            | Operator::Jump { .. }
+            | Operator::Replace
+            | Operator::ChangeToMatch
+            | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister => false,
+        }
+    }
+
     pub fn starts_dot_recording(&self) -> bool {
         match self {
             Operator::Change
@@ -1041,7 +1202,8 @@ impl Operator {
             | Operator::AddSurrounds { target: None }
             | Operator::ChangeSurrounds { target: None }
             | Operator::DeleteSurrounds
-            | Operator::Exchange => true,
+            | Operator::Exchange
+            | Operator::ChangeToMatch => true,
             Operator::Yank
             | Operator::Object { .. }
             | Operator::FindForward { .. }
@@ -1286,6 +1448,7 @@ pub struct MarksViewDelegate {
     matches: Vec<MarksMatch>,
     point_column_width: usize,
     workspace: WeakEntity<Workspace>,
+    names: Option<String>,
 }
 
 impl PickerDelegate for MarksViewDelegate {
@@ -1317,6 +1480,7 @@ impl PickerDelegate for MarksViewDelegate {
         let Some(workspace) = self.workspace.upgrade().clone() else {
             return Task::ready(());
         };
+        let names = self.names.clone();
         cx.spawn(async move |picker, cx| {
             let mut matches = Vec::new();
             let _ = workspace.update(cx, |workspace, cx| {
@@ -1438,7 +1602,12 @@ impl PickerDelegate for MarksViewDelegate {
                                 .and_then(|map| map.get(name))
                                 .and_then(|points| points.first())
                             {
-                                let info = MarksMatchInfo::Path(path.clone());
+                                let path = workspace
+                                    .project()
+                                    .read(cx)
+                                    .find_worktree(path, cx)
+                                    .map_or_else(|| path.clone(), |(_, path)| path.into());
+                                let info = MarksMatchInfo::Path(path);
                                 matches.push(MarksMatch {
                                     name: name.clone(),
                                     position,
@@ -1449,6 +1618,13 @@ impl PickerDelegate for MarksViewDelegate {
                     }
                 }
             });
+            if let Some(names) = names {
+                matches.retain(|m| {
+                    m.name
+                        .chars()
+                        .all(|c| names.contains(c) || c == '\'' && names.contains('`'))
+                });
+            }
             let _ = picker.update(cx, |picker, cx| {
                 matches.sort_by_key(|a| {
                     (
@@ -1579,19 +1755,28 @@ pub struct MarksView {}
 impl MarksView {
     fn register(workspace: &mut Workspace, _window: Option<&mut Window>) {
         workspace.register_action(|workspace, _: &ToggleMarksView, window, cx| {
-            Self::toggle(workspace, window, cx);
+            Self::toggle(workspace, None, window, cx);
+        });
+        workspace.register_action(|workspace, action: &ShowMarks, window, cx| {
+            Self::toggle(workspace, Some(action.names.clone()), window, cx);
         });
     }
 
-    pub fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    pub fn toggle(
+        workspace: &mut Workspace,
+        names: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
         let handle = cx.weak_entity();
         workspace.toggle_modal(window, cx, move |window, cx| {
-            MarksView::new(handle, window, cx)
+            MarksView::new(handle, names, window, cx)
         });
     }
 
     fn new(
         workspace: WeakEntity<Workspace>,
+        names: Option<String>,
         window: &mut Window,
         cx: &mut Context<Picker<MarksViewDelegate>>,
     ) -> Picker<MarksViewDelegate> {
@@ -1601,6 +1786,7 @@ impl MarksView {
             point_column_width: 0,
             matches,
             workspace,
+            names,
         };
         Picker::nonsearchable_uniform_list(delegate, window, cx)
             .width(rems(36.))
@@ -1628,6 +1814,15 @@ define_connection! (
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_jumps (
+                workspace_id INTEGER,
+                position INTEGER,
+                path BLOB,
+                value TEXT
+            );
+            CREATE UNIQUE INDEX idx_vim_jumps ON vim_jumps (workspace_id, position);
+        ),
     ];
 );
 
@@ -1707,6 +1902,26 @@ impl VimDb {
         .await
     }
 
+    pub(crate) async fn delete_mark(
+        &self,
+        workspace_id: WorkspaceId,
+        path: Arc<Path>,
+        mark_name: String,
+    ) -> Result<()> {
+        log::debug!("Deleting mark {mark_name} for {path:?}");
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_marks
+                    WHERE workspace_id = ? AND mark_name = ? AND path = ?
+            ))?((workspace_id, mark_name.clone(), path))?;
+            conn.exec_bound(sql!(
+                DELETE FROM vim_global_marks_paths
+                    WHERE workspace_id = ? AND mark_name = ?
+            ))?((workspace_id, mark_name))
+        })
+        .await
+    }
+
     pub fn get_global_marks_paths(
         &self,
         workspace_id: WorkspaceId,
@@ -1716,4 +1931,215 @@ impl VimDb {
             WHERE workspace_id = ?
         ))?(workspace_id)
     }
+
+    /// Appends a jump and drops the oldest ones beyond `MAX_JUMP_LIST_LEN`.
+    pub(crate) async fn push_jump(
+        &self,
+        workspace_id: WorkspaceId,
+        path: Arc<Path>,
+        point: Point,
+    ) -> Result<()> {
+        let serialized = serde_json::to_string(&(point.row, point.column))?;
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                INSERT INTO vim_jumps
+                    (workspace_id, position, path, value)
+                VALUES
+                    (
+                        ?1,
+                        (
+                            SELECT IFNULL(MAX(position), -1) + 1 FROM vim_jumps
+                                WHERE workspace_id = ?1
+                        ),
+                        ?2,
+                        ?3
+                    )
+            ))?((workspace_id, path, serialized))?;
+            conn.exec_bound(sql!(
+                DELETE FROM vim_jumps
+                    WHERE workspace_id = ?1 AND position NOT IN (
+                        SELECT position FROM vim_jumps
+                            WHERE workspace_id = ?1
+                            ORDER BY position DESC
+                            LIMIT ?2
+                    )
+            ))?((workspace_id, MAX_JUMP_LIST_LEN))
+        })
+        .await
+    }
+
+    pub(crate) async fn delete_jumps_to(
+        &self,
+        workspace_id: WorkspaceId,
+        path: Arc<Path>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_jumps WHERE workspace_id = ? AND path = ?
+            ))?((workspace_id, path))
+        })
+        .await
+    }
+
+    fn get_jump_list(&self, workspace_id: WorkspaceId) -> Result<Vec<(Arc<Path>, Point)>> {
+        let result: Vec<(Arc<Path>, String)> = self.select_bound(sql!(
+            SELECT path, value FROM vim_jumps
+                WHERE workspace_id = ?
+                ORDER BY position
+        ))?(workspace_id)?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(path, value)| {
+                let (row, column): (u32, u32) = serde_json::from_str(&value).log_err()?;
+                Some((path, Point { row, column }))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::VimTestContext;
+
+    #[gpui::test]
+    async fn test_marks_view_filter(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇone\ntwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("m a j m b j m shift-c");
+
+        let mark_names = |cx: &mut VimTestContext| {
+            cx.run_until_parked();
+            cx.workspace(|workspace, _, cx| {
+                let picker = workspace
+                    .active_modal::<Picker<MarksViewDelegate>>(cx)
+                    .expect("marks view should be open");
+                picker
+                    .read(cx)
+                    .delegate
+                    .matches
+                    .iter()
+                    .map(|m| m.name.clone())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        cx.simulate_keystrokes(": m a r k s space a shift-c enter");
+        assert_eq!(mark_names(&mut cx), vec!["a", "C"]);
+        cx.simulate_keystrokes("escape");
+
+        cx.simulate_keystrokes(": m a r k s enter");
+        let names = mark_names(&mut cx);
+        assert!(["a", "b", "C"].iter().all(|name| names.contains(&name.to_string())));
+    }
+
+    fn load_marks(cx: &mut VimTestContext, workspace_id: WorkspaceId) {
+        cx.update_workspace(|workspace, _, cx| {
+            workspace.set_database_id(workspace_id);
+            let entity_id = cx.entity_id();
+            let marks_state = Vim::globals(cx).marks.get(&entity_id).cloned();
+            marks_state.unwrap().update(cx, |ms, cx| ms.load(cx));
+        });
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_restore_jump_list(
+        cx: &mut gpui::TestAppContext,
+        cx_b: &mut gpui::TestAppContext,
+    ) {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let lines = (0..30).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        let text = lines.join("\n");
+
+        let mut cx = VimTestContext::new(cx, true).await;
+        load_marks(&mut cx, workspace_id);
+        cx.set_state(&text.replace("line 5", "ˇline 5"), Mode::Normal);
+        cx.simulate_keystrokes("shift-g");
+        // Writes are queued in order, so this one completes after the jump list's.
+        DB.write(|_| ()).await;
+        let jumps = DB.get_jump_list(workspace_id).unwrap();
+        assert_eq!(
+            jumps.iter().map(|(_, point)| *point).collect::<Vec<_>>(),
+            vec![Point::new(5, 0)]
+        );
+
+        let mut cx = VimTestContext::new(cx_b, true).await;
+        cx.set_state(&format!("ˇ{text}"), Mode::Normal);
+        load_marks(&mut cx, workspace_id);
+        cx.simulate_keystrokes("ctrl-o");
+        cx.run_until_parked();
+        cx.assert_state(&text.replace("line 5", "ˇline 5"), Mode::Normal);
+
+        let marks_state = cx.update_workspace(|_, _, cx| {
+            let entity_id = cx.entity_id();
+            Vim::globals(cx).marks.get(&entity_id).cloned().unwrap()
+        });
+        cx.update_editor(|editor, _, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let anchors = [
+                snapshot.anchor_before(Point::new(0, 0)),
+                snapshot.anchor_before(Point::new(1, 0)),
+            ];
+            marks_state.update(cx, |ms, cx| {
+                for i in 0..MAX_JUMP_LIST_LEN + 5 {
+                    ms.record_jump(editor.buffer(), anchors[i % 2], cx);
+                }
+                assert_eq!(ms.jump_list.len(), MAX_JUMP_LIST_LEN);
+            });
+        });
+        cx.run_until_parked();
+        DB.write(|_| ()).await;
+        let jumps = DB.get_jump_list(workspace_id).unwrap();
+        assert_eq!(jumps.len(), MAX_JUMP_LIST_LEN);
+        assert_eq!(jumps.last().map(|(_, point)| *point), Some(Point::new(0, 0)));
+    }
+
+    #[gpui::test]
+    async fn test_serialize_and_delete_marks() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let path: Arc<Path> = Path::new("/root/dir/file.rs").into();
+
+        DB.set_marks(
+            workspace_id,
+            path.clone(),
+            HashMap::from_iter([
+                ("a".to_string(), vec![Point::new(1, 2)]),
+                ("A".to_string(), vec![Point::new(3, 0)]),
+            ]),
+        )
+        .await
+        .unwrap();
+        DB.set_global_mark_path(workspace_id, "A".to_string(), path.clone())
+            .await
+            .unwrap();
+
+        let mut marks = DB.get_marks(workspace_id).unwrap();
+        marks.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            marks
+                .iter()
+                .map(|mark| (mark.name.as_str(), mark.path.clone(), mark.points.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("A", path.clone(), vec![Point::new(3, 0)]),
+                ("a", path.clone(), vec![Point::new(1, 2)]),
+            ]
+        );
+        assert_eq!(
+            DB.get_global_marks_paths(workspace_id).unwrap(),
+            vec![("A".to_string(), path.clone())]
+        );
+
+        DB.delete_mark(workspace_id, path.clone(), "A".to_string())
+            .await
+            .unwrap();
+        let marks = DB.get_marks(workspace_id).unwrap();
+        assert_eq!(
+            marks.iter().map(|mark| mark.name.as_str()).collect::<Vec<_>>(),
+            vec!["a"]
+        );
+        assert!(DB.get_global_marks_paths(workspace_id).unwrap().is_empty());
+    }
 }
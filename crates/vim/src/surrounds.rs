@@ -52,7 +52,7 @@ impl Vim {
                 for selection in &display_selections {
                     let range = match &target {
                         SurroundsType::Object(object, around) => {
-                            object.range(&display_map, selection.clone(), *around)
+                            object.range(&display_map, selection.clone(), *around, None)
                         }
                         SurroundsType::Motion(motion) => {
                             motion
@@ -73,7 +73,12 @@ impl Vim {
                                         );
                                         range.end = movement::saturating_right(
                                             &display_map,
-                                            motion::last_non_whitespace(&display_map, range.end, 1),
+                                            motion::last_non_whitespace(
+                                                &display_map,
+                                                false,
+                                                range.end,
+                                                1,
+                                            ),
                                         );
                                     }
                                     range
@@ -150,7 +155,9 @@ impl Vim {
 
                 for selection in &display_selections {
                     let start = selection.start.to_offset(&display_map, Bias::Left);
-                    if let Some(range) = pair_object.range(&display_map, selection.clone(), true) {
+                    if let Some(range) =
+                        pair_object.range(&display_map, selection.clone(), true, None)
+                    {
                         // If the current parenthesis object is single-line,
                         // then we need to filter whether it is the current line or not
                         if !pair_object.is_multiline() {
@@ -224,7 +231,7 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Some(will_replace_pair) = object_to_bracket_pair(target) {
+        if let Some(will_replace_pair) = object_to_bracket_pair(&target) {
             self.stop_recording(cx);
             self.update_editor(window, cx, |_, editor, window, cx| {
                 editor.transact(window, cx, |editor, window, cx| {
@@ -247,7 +254,9 @@ impl Vim {
 
                     for selection in &selections {
                         let start = selection.start.to_offset(&display_map, Bias::Left);
-                        if let Some(range) = target.range(&display_map, selection.clone(), true) {
+                        if let Some(range) =
+                            target.range(&display_map, selection.clone(), true, None)
+                        {
                             if !target.is_multiline() {
                                 let is_same_row = selection.start.row() == range.start.row()
                                     && selection.end.row() == range.end.row();
@@ -334,7 +343,7 @@ impl Vim {
     /// If no valid pair of brackets is found for any cursor, the method returns `false`.
     pub fn check_and_move_to_valid_bracket_pair(
         &mut self,
-        object: Object,
+        object: &Object,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> bool {
@@ -348,7 +357,9 @@ impl Vim {
 
                     for selection in &selections {
                         let start = selection.start.to_offset(&display_map, Bias::Left);
-                        if let Some(range) = object.range(&display_map, selection.clone(), true) {
+                        if let Some(range) =
+                            object.range(&display_map, selection.clone(), true, None)
+                        {
                             // If the current parenthesis object is single-line,
                             // then we need to filter whether it is the current line or not
                             if object.is_multiline()
@@ -484,7 +495,7 @@ fn pair_to_object(pair: &BracketPair) -> Option<Object> {
     }
 }
 
-fn object_to_bracket_pair(object: Object) -> Option<BracketPair> {
+fn object_to_bracket_pair(object: &Object) -> Option<BracketPair> {
     match object {
         Object::Quotes => Some(BracketPair {
             start: "'".to_string(),
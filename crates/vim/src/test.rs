@@ -2,16 +2,18 @@ mod neovim_backed_test_context;
 mod neovim_connection;
 mod vim_test_context;
 
-use std::time::Duration;
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
 use collections::HashMap;
 use command_palette::CommandPalette;
 use editor::{
-    DisplayPoint, Editor, EditorMode, MultiBuffer, actions::DeleteLine, display_map::DisplayRow,
-    test::editor_test_context::EditorTestContext,
+    DisplayPoint, Editor, EditorMode, MultiBuffer,
+    actions::DeleteLine,
+    display_map::DisplayRow,
+    test::{editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext},
 };
 use futures::StreamExt;
-use gpui::{KeyBinding, Modifiers, MouseButton, TestAppContext};
+use gpui::{Focusable, KeyBinding, Modifiers, MouseButton, TestAppContext, point, px};
 use language::Point;
 pub use neovim_backed_test_context::*;
 use settings::SettingsStore;
@@ -19,9 +21,10 @@ pub use vim_test_context::*;
 
 use indoc::indoc;
 use search::BufferSearchBar;
+use ui::ContextMenu;
 use workspace::WorkspaceSettings;
 
-use crate::{PushSneak, PushSneakBackward, insert::NormalBefore, motion, state::Mode};
+use crate::{PushSneak, PushSneakBackward, VimSettings, insert::NormalBefore, motion, state::Mode};
 
 #[gpui::test]
 async fn test_initially_disabled(cx: &mut gpui::TestAppContext) {
@@ -239,6 +242,44 @@ async fn test_escape_command_palette(cx: &mut gpui::TestAppContext) {
     cx.assert_state("aˇbc\n", Mode::Insert);
 }
 
+#[gpui::test]
+async fn test_motions_in_command_palette(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+    cx.set_state("ˇabc\n", Mode::Normal);
+
+    let motions = [
+        ("vim::NextWordStart", "w"),
+        ("vim::PreviousWordStart", "b"),
+        ("vim::StartOfDocument", "g g"),
+        ("vim::EndOfDocument", "shift-g"),
+    ];
+    cx.update_editor(|editor, window, cx| {
+        let focus_handle = editor.focus_handle(cx);
+        let available_actions = window.available_actions(cx);
+        for (name, keystrokes) in motions {
+            let action = cx.build_action(name, None).unwrap();
+            assert!(
+                available_actions
+                    .iter()
+                    .any(|available| available.partial_eq(&*action)),
+                "{name} is not available in the command palette"
+            );
+            let binding = window
+                .bindings_for_action_in(&*action, &focus_handle)
+                .pop()
+                .map(|binding| {
+                    binding
+                        .keystrokes()
+                        .iter()
+                        .map(|keystroke| keystroke.unparse())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+            assert_eq!(binding.as_deref(), Some(keystrokes), "{name}");
+        }
+    });
+}
+
 #[gpui::test]
 async fn test_escape_cancels(cx: &mut gpui::TestAppContext) {
     let mut cx = VimTestContext::new(cx, true).await;
@@ -947,6 +988,42 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     cx.assert_state("const afterˇ = 2; console.log(after)", Mode::Normal)
 }
 
+#[gpui::test]
+async fn test_goto_tag_definition(cx: &mut gpui::TestAppContext) {
+    VimTestContext::init(cx);
+    let cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            definition_provider: Some(lsp::OneOf::Left(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let mut cx = VimTestContext::new_with_lsp(cx, true);
+
+    cx.set_state("fn test() {}\nfn main() { teˇst(); }", Mode::Normal);
+    let def_range = cx.lsp_range("fn «testˇ»() {}\nfn main() { test(); }");
+    let mut definition_request = cx.set_request_handler::<lsp::request::GotoDefinition, _, _>(
+        move |url, _, _| async move {
+            Ok(Some(lsp::GotoDefinitionResponse::Scalar(lsp::Location {
+                uri: url.clone(),
+                range: def_range,
+            })))
+        },
+    );
+
+    cx.dispatch_action(motion::GotoTagDefinition);
+    definition_request.next().await.unwrap();
+    cx.run_until_parked();
+    cx.assert_state("fn ˇtest() {}\nfn main() { test(); }", Mode::Normal);
+
+    cx.set_state("fn test() {}\nfn main() { «teˇ»st(); }", Mode::Visual);
+    cx.simulate_keystrokes("ctrl-]");
+    definition_request.next().await.unwrap();
+    cx.run_until_parked();
+    cx.assert_state("fn ˇtest() {}\nfn main() { test(); }", Mode::Normal);
+}
+
 // TODO: this test is flaky on our linux CI machines
 #[cfg(target_os = "macos")]
 #[gpui::test]
@@ -1073,6 +1150,20 @@ async fn test_undo(cx: &mut gpui::TestAppContext) {
         3"});
 }
 
+#[gpui::test]
+async fn test_undo_insert_session(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.set_shared_state("ˇworld").await;
+    cx.simulate_shared_keystrokes("i h e y backspace l l o enter escape")
+        .await;
+    cx.shared_state().await.assert_eq("hello\nˇworld");
+    cx.simulate_shared_keystrokes("u").await;
+    cx.shared_state().await.assert_eq("ˇworld");
+    cx.simulate_shared_keystrokes("ctrl-r").await;
+    cx.shared_state().await.assert_eq("ˇhello\nworld");
+}
+
 #[gpui::test]
 async fn test_mouse_selection(cx: &mut TestAppContext) {
     let mut cx = VimTestContext::new(cx, true).await;
@@ -1399,6 +1490,97 @@ async fn test_sneak(cx: &mut gpui::TestAppContext) {
     cx.assert_state(r#"11 12ˇ 13 14"#, Mode::Normal);
     cx.simulate_keystrokes(";");
     cx.assert_state(r#"11ˇ 12 13 14"#, Mode::Normal);
+
+    // Sneak with a count, which is used up by the jump
+    cx.set_state("ˇab ab ab ab ab", Mode::Normal);
+    cx.simulate_keystrokes("3 s a b");
+    cx.assert_state("ab ab ab ˇab ab", Mode::Normal);
+    cx.simulate_keystrokes("S a b");
+    cx.assert_state("ab ab ˇab ab ab", Mode::Normal);
+    cx.simulate_keystrokes("2 S a b");
+    cx.assert_state("ˇab ab ab ab ab", Mode::Normal);
+}
+
+#[gpui::test]
+async fn test_sneak_repeat_reversed(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.update(|_window, cx| {
+        cx.bind_keys([
+            KeyBinding::new(
+                "s",
+                PushSneak { first_char: None },
+                Some("vim_mode == normal"),
+            ),
+            KeyBinding::new(
+                "shift-s",
+                PushSneakBackward { first_char: None },
+                Some("vim_mode == normal"),
+            ),
+        ])
+    });
+
+    cx.set_state("ˇab cd ab", Mode::Normal);
+    cx.simulate_keystrokes("s a b");
+    cx.assert_state("ab cd ˇab", Mode::Normal);
+    cx.simulate_keystrokes(",");
+    cx.assert_state("ˇab cd ab", Mode::Normal);
+
+    // The match right before the cursor is found when going back
+    cx.set_state("ˇabab", Mode::Normal);
+    cx.simulate_keystrokes("s a b");
+    cx.assert_state("abˇab", Mode::Normal);
+    cx.simulate_keystrokes(",");
+    cx.assert_state("ˇabab", Mode::Normal);
+
+    cx.set_state("ab\ncd ˇab", Mode::Normal);
+    cx.simulate_keystrokes("shift-s a b");
+    cx.assert_state("ˇab\ncd ab", Mode::Normal);
+    cx.simulate_keystrokes(",");
+    cx.assert_state("ab\ncd ˇab", Mode::Normal);
+    cx.simulate_keystrokes(";");
+    cx.assert_state("ˇab\ncd ab", Mode::Normal);
+}
+
+#[gpui::test]
+async fn test_sneak_disabled(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.update(|_window, cx| {
+        cx.bind_keys([
+            KeyBinding::new(
+                "s",
+                PushSneak { first_char: None },
+                Some("vim_mode == normal || vim_mode == visual"),
+            ),
+            KeyBinding::new(
+                "shift-s",
+                PushSneakBackward { first_char: None },
+                Some("vim_mode == normal || vim_mode == visual"),
+            ),
+        ])
+    });
+    cx.update_global(|store: &mut SettingsStore, cx| {
+        store.update_user_settings::<VimSettings>(cx, |s| {
+            s.enable_sneak = Some(false);
+        });
+    });
+
+    cx.set_state("ˇone two", Mode::Normal);
+    cx.simulate_keystrokes("3 s");
+    cx.assert_state("ˇ two", Mode::Insert);
+
+    cx.set_state("one\n  twˇo\nthree", Mode::Normal);
+    cx.simulate_keystrokes("shift-s");
+    cx.assert_state("one\n  ˇ\nthree", Mode::Insert);
+
+    cx.set_state("one «twoˇ» three", Mode::Visual);
+    cx.simulate_keystrokes("s");
+    cx.assert_state("one ˇ three", Mode::Insert);
+
+    cx.set_state("one\n  t«woˇ»\nthree", Mode::Visual);
+    cx.simulate_keystrokes("shift-s");
+    cx.assert_state("one\n  ˇ\nthree", Mode::Insert);
 }
 
 #[gpui::test]
@@ -1538,6 +1720,45 @@ async fn test_remap_recursion(cx: &mut gpui::TestAppContext) {
     cx.shared_state().await.assert_eq("ˇlo");
 }
 
+#[gpui::test]
+async fn test_operator_pending_context(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+    cx.update(|_, cx| {
+        cx.bind_keys([KeyBinding::new(
+            "x",
+            workspace::SendKeystrokes("$".to_string()),
+            Some("VimOperatorPending"),
+        )]);
+        cx.bind_keys([KeyBinding::new(
+            "q",
+            workspace::SendKeystrokes("w".to_string()),
+            Some("vim_pending_operator == gU"),
+        )])
+    });
+
+    // outside of an operator the keys keep their usual meaning
+    cx.set_state("one ˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("x");
+    cx.assert_state("one ˇwo three", Mode::Normal);
+
+    cx.set_state("one ˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("d x");
+    cx.assert_state("oneˇ ", Mode::Normal);
+
+    cx.set_state("one ˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("g shift-u x");
+    cx.assert_state("one ˇTWO THREE", Mode::Normal);
+
+    cx.set_state("one ˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("g shift-u q");
+    cx.assert_state("one ˇTWO three", Mode::Normal);
+
+    // the context is cleared once the operator is cancelled
+    cx.set_state("one ˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("d escape x");
+    cx.assert_state("one ˇwo three", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_escape_while_waiting(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1680,6 +1901,48 @@ async fn test_sentence_forwards(cx: &mut gpui::TestAppContext) {
     cx.set_shared_state("helˇlo.\n\n\nworld.").await;
 }
 
+#[gpui::test]
+async fn test_sentence_forwards_without_trailing_newline(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.set_shared_state("ˇOne. Two.").await;
+    cx.simulate_shared_keystrokes("2 )").await;
+    cx.shared_state().await.assert_eq("One. Twoˇ.");
+
+    cx.set_shared_state("ˇOne. Two").await;
+    cx.simulate_shared_keystrokes("2 )").await;
+    cx.shared_state().await.assert_eq("One. Twˇo");
+
+    cx.set_shared_state("ˇOne. Two. Three").await;
+    cx.simulate_shared_keystrokes("2 )").await;
+    cx.shared_state().await.assert_eq("One. Two. ˇThree");
+    cx.simulate_shared_keystrokes("5 )").await;
+    cx.shared_state().await.assert_eq("One. Two. Threˇe");
+}
+
+#[gpui::test]
+async fn test_smart_sentence_detection(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("ˇAsk Mr. Smith, e.g. about it. Then leave.", Mode::Normal);
+    cx.simulate_keystrokes(")");
+    cx.assert_state("Ask Mr. ˇSmith, e.g. about it. Then leave.", Mode::Normal);
+
+    cx.update_global(|store: &mut SettingsStore, cx| {
+        store.update_user_settings::<VimSettings>(cx, |s| {
+            s.smart_sentence_detection = Some(true);
+        });
+    });
+
+    cx.set_state("ˇAsk Mr. Smith, e.g. about it. Then leave.", Mode::Normal);
+    cx.simulate_keystrokes(")");
+    cx.assert_state("Ask Mr. Smith, e.g. about it. ˇThen leave.", Mode::Normal);
+    cx.simulate_keystrokes("(");
+    cx.assert_state("ˇAsk Mr. Smith, e.g. about it. Then leave.", Mode::Normal);
+    cx.simulate_keystrokes("d )");
+    cx.assert_state("ˇThen leave.", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_ctrl_o_visual(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1905,6 +2168,186 @@ async fn test_folded_multibuffer_excerpts(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_excerpt_motions(cx: &mut gpui::TestAppContext) {
+    VimTestContext::init(cx);
+    cx.update(|cx| {
+        VimTestContext::init_keybindings(true, cx);
+    });
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        let multi_buffer = MultiBuffer::build_multi(
+            [
+                ("111\n222\n333\n", vec![Point::row_range(0..2)]),
+                ("aaa\nbbb\nccc\n", vec![Point::row_range(0..2)]),
+                ("AAA\nBBB\nCCC\n", vec![Point::row_range(0..2)]),
+            ],
+            cx,
+        );
+        Editor::new(EditorMode::full(), multi_buffer, None, window, cx)
+    });
+    let mut cx = EditorTestContext::for_editor_in(editor.clone(), cx).await;
+
+    cx.simulate_keystrokes("] b");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        111
+        222
+        [EXCERPT]
+        ˇaaa
+        bbb
+        [EXCERPT]
+        AAA
+        BBB
+        "
+    });
+    cx.simulate_keystrokes("5 ] b");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        111
+        222
+        [EXCERPT]
+        aaa
+        bbb
+        [EXCERPT]
+        ˇAAA
+        BBB
+        "
+    });
+    cx.simulate_keystrokes("2 [ b");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        ˇ111
+        222
+        [EXCERPT]
+        aaa
+        bbb
+        [EXCERPT]
+        AAA
+        BBB
+        "
+    });
+    cx.simulate_keystrokes("2 ] b");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        111
+        222
+        [EXCERPT]
+        aaa
+        bbb
+        [EXCERPT]
+        ˇAAA
+        BBB
+        "
+    });
+
+    // from the middle of an excerpt, [b goes to its start
+    cx.simulate_keystrokes("j [ b");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        111
+        222
+        [EXCERPT]
+        aaa
+        bbb
+        [EXCERPT]
+        ˇAAA
+        BBB
+        "
+    });
+
+    // operators stop at the end of the current excerpt
+    cx.simulate_keystrokes("2 [ b y ] b p");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        111
+        ˇ111
+        222
+        222
+        [EXCERPT]
+        aaa
+        bbb
+        [EXCERPT]
+        AAA
+        BBB
+        "
+    });
+}
+
+#[gpui::test]
+async fn test_comment_motions_in_multibuffer(cx: &mut gpui::TestAppContext) {
+    VimTestContext::init(cx);
+    cx.update(|cx| {
+        VimTestContext::init_keybindings(true, cx);
+    });
+    let language = std::sync::Arc::new(
+        language::Language::new(
+            language::LanguageConfig::default(),
+            Some(language::tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_text_object_query("(block_comment) @comment.around")
+        .unwrap(),
+    );
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        let multi_buffer = MultiBuffer::build_multi(
+            [
+                ("fn a() {}\n/* one */\nfn b() {}\n", vec![Point::row_range(0..3)]),
+                ("fn c() {}\nfn d() {}\n", vec![Point::row_range(0..2)]),
+                ("/* two */\nfn e() {}\n", vec![Point::row_range(0..2)]),
+            ],
+            cx,
+        );
+        for buffer in multi_buffer.read(cx).all_buffers() {
+            buffer.update(cx, |buffer, cx| buffer.set_language(Some(language.clone()), cx));
+        }
+        Editor::new(EditorMode::full(), multi_buffer, None, window, cx)
+    });
+    let mut cx = EditorTestContext::for_editor_in(editor.clone(), cx).await;
+    cx.run_until_parked();
+
+    cx.simulate_keystrokes("] /");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        fn a() {}
+        /* one *ˇ/
+        fn b() {}
+        [EXCERPT]
+        fn c() {}
+        fn d() {}
+        [EXCERPT]
+        /* two */
+        fn e() {}
+        "
+    });
+    cx.simulate_keystrokes("] /");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        fn a() {}
+        /* one */
+        fn b() {}
+        [EXCERPT]
+        fn c() {}
+        fn d() {}
+        [EXCERPT]
+        /* two *ˇ/
+        fn e() {}
+        "
+    });
+    cx.simulate_keystrokes("2 [ /");
+    cx.assert_excerpts_with_selections(indoc! {"
+        [EXCERPT]
+        fn a() {}
+        ˇ/* one */
+        fn b() {}
+        [EXCERPT]
+        fn c() {}
+        fn d() {}
+        [EXCERPT]
+        /* two */
+        fn e() {}
+        "
+    });
+}
+
 #[gpui::test]
 async fn test_delete_paragraph_motion(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1972,3 +2415,38 @@ async fn test_delete_unmatched_brace(cx: &mut gpui::TestAppContext) {
         .await
         .assert_eq("  oth(wow)\n  oth(wow)\n");
 }
+
+#[gpui::test]
+async fn test_context_menu_keyboard_navigation(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+    cx.set_state("ˇone\ntwo\nthree", Mode::Normal);
+
+    let confirmed = Rc::new(RefCell::new(Vec::new()));
+    let deploy_menu = |cx: &mut VimTestContext| {
+        let confirmed = confirmed.clone();
+        cx.update_editor(|editor, window, cx| {
+            let menu = ContextMenu::build(window, cx, |menu, _, _| {
+                ["First", "Second", "Third"]
+                    .into_iter()
+                    .fold(menu, |menu, label| {
+                        let confirmed = confirmed.clone();
+                        menu.entry(label, None, move |_, _| confirmed.borrow_mut().push(label))
+                    })
+            });
+            editor.deploy_mouse_context_menu(point(px(0.), px(0.)), menu, window, cx);
+        });
+    };
+
+    deploy_menu(&mut cx);
+    cx.simulate_keystrokes("j j k j enter");
+    assert_eq!(*confirmed.borrow(), ["Second"]);
+    cx.assert_state("ˇone\ntwo\nthree", Mode::Normal);
+
+    deploy_menu(&mut cx);
+    cx.simulate_keystrokes("k escape");
+    assert_eq!(*confirmed.borrow(), ["Second"]);
+    cx.update_editor(|editor, window, cx| assert!(!editor.mouse_menu_is_focused(window, cx)));
+
+    cx.simulate_keystrokes("j");
+    cx.assert_state("one\nˇtwo\nthree", Mode::Normal);
+}
@@ -30,11 +30,12 @@ use gpui::{
     Render, Subscription, Task, WeakEntity, Window, actions, impl_actions,
 };
 use insert::{NormalBefore, TemporaryNormal};
-use language::{CharKind, CursorShape, Point, Selection, SelectionGoal, TransactionId};
+use language::{CharKind, CursorShape, LanguageName, Point, Selection, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
-use motion::Motion;
+pub use object::custom_text_object_key_bindings;
+use motion::{Keyword, Motion};
 use normal::search::SearchSubmit;
-use object::Object;
+use object::{CustomTextObject, Object};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
@@ -140,6 +141,7 @@ actions!(
         PushChange,
         PushDelete,
         Exchange,
+        PushChangeToMatch,
         PushYank,
         PushReplace,
         PushDeleteSurrounds,
@@ -330,6 +332,8 @@ pub(crate) struct Vim {
 
     selected_register: Option<char>,
     pub search: SearchState,
+    /// Whether `/` and `?` ignore case, toggled with `:set [no]ignorecase`.
+    pub(crate) ignore_case: bool,
 
     editor: WeakEntity<Editor>,
 
@@ -375,6 +379,7 @@ impl Vim {
             status_label: None,
             selected_register: None,
             search: SearchState::default(),
+            ignore_case: true,
 
             last_command: None,
             running_command: None,
@@ -507,6 +512,10 @@ impl Vim {
             });
 
             Vim::action(editor, cx, |vim, action: &PushSneak, window, cx| {
+                if !VimSettings::get_global(cx).enable_sneak {
+                    vim.substitute_char(window, cx);
+                    return;
+                }
                 vim.push_operator(
                     Operator::Sneak {
                         first_char: action.first_char,
@@ -517,6 +526,10 @@ impl Vim {
             });
 
             Vim::action(editor, cx, |vim, action: &PushSneakBackward, window, cx| {
+                if !VimSettings::get_global(cx).enable_sneak {
+                    vim.substitute_line(window, cx);
+                    return;
+                }
                 vim.push_operator(
                     Operator::SneakBackward {
                         first_char: action.first_char,
@@ -536,7 +549,7 @@ impl Vim {
                 |vim, action: &PushChangeSurrounds, window, cx| {
                     vim.push_operator(
                         Operator::ChangeSurrounds {
-                            target: action.target,
+                            target: action.target.clone(),
                         },
                         window,
                         cx,
@@ -660,6 +673,10 @@ impl Vim {
                 }
             });
 
+            Vim::action(editor, cx, |vim, _: &PushChangeToMatch, window, cx| {
+                vim.push_operator(Operator::ChangeToMatch, window, cx)
+            });
+
             Vim::action(editor, cx, |vim, _: &ClearExchange, window, cx| {
                 vim.clear_exchange(window, cx)
             });
@@ -853,6 +870,7 @@ impl Vim {
                         "'".to_string()
                     };
                     vim.set_mark(mark, vec![*anchor], editor.buffer(), window, cx);
+                    vim.record_jump(*anchor, editor.buffer(), window, cx);
                 });
             }
             _ => {}
@@ -872,6 +890,7 @@ impl Vim {
                 | Operator::ChangeSurrounds { .. }
                 | Operator::DeleteSurrounds
                 | Operator::Exchange
+                | Operator::ChangeToMatch
         ) {
             self.operator_stack.clear();
         };
@@ -1000,12 +1019,16 @@ impl Vim {
             return global_state.recorded_count;
         }
 
-        let count = if global_state.post_count.is_none() && global_state.pre_count.is_none() {
+        let count = if global_state.post_count.is_none()
+            && global_state.pre_count.is_none()
+            && global_state.register_count.is_none()
+        {
             return None;
         } else {
             Some(
                 global_state.post_count.take().unwrap_or(1)
-                    * global_state.pre_count.take().unwrap_or(1),
+                    * global_state.pre_count.take().unwrap_or(1)
+                    * global_state.register_count.take().unwrap_or(1),
             )
         };
 
@@ -1127,6 +1150,18 @@ impl Vim {
         }
         context.set("vim_mode", mode);
         context.set("vim_operator", operator_id);
+
+        // Unlike `vim_operator`, this stays on `d` while `d` waits for `a`/`i`/`f` to finish
+        // its motion.
+        if let Some(pending) = self
+            .operator_stack
+            .iter()
+            .rev()
+            .find(|operator| operator.takes_motion())
+        {
+            context.add("VimOperatorPending");
+            context.set("vim_pending_operator", pending.id());
+        }
     }
 
     fn focused(&mut self, preserve_selection: bool, window: &mut Window, cx: &mut Context<Self>) {
@@ -1259,6 +1294,7 @@ impl Vim {
                 globals.dot_recording = true;
                 globals.recording_actions = Default::default();
                 globals.recorded_count = None;
+                globals.recorded_register = self.selected_register;
 
                 let selections = self.editor().map(|editor| {
                     editor.update(cx, |editor, cx| {
@@ -1367,6 +1403,13 @@ impl Vim {
             self.selected_register
                 .replace(register.chars().next().unwrap());
         }
+        // A count typed after the register starts afresh, and is multiplied by
+        // the one typed before it when the count is taken.
+        let globals = Vim::globals(cx);
+        if let Some(count) = globals.pre_count.take() {
+            globals.register_count =
+                Some(count.saturating_mul(globals.register_count.unwrap_or(1)));
+        }
         self.operator_stack.clear();
         self.sync_vim_settings(window, cx);
     }
@@ -1573,6 +1616,10 @@ impl Vim {
                 }
                 _ => self.clear_operator(window, cx),
             },
+            Some(Operator::ChangeToMatch) => match self.mode {
+                Mode::Normal => self.change_to_match(text, window, cx),
+                _ => self.clear_operator(window, cx),
+            },
             Some(Operator::Digraph { first_char }) => {
                 if let Some(first_char) = first_char {
                     if let Some(second_char) = text.chars().next() {
@@ -1715,16 +1762,46 @@ struct CursorShapeSettings {
     pub insert: Option<CursorShape>,
 }
 
-#[derive(Deserialize)]
 struct VimSettings {
     pub default_mode: Mode,
     pub toggle_relative_line_numbers: bool,
     pub use_system_clipboard: UseSystemClipboard,
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
+    pub enable_sneak: bool,
+    pub smart_sentence_detection: bool,
+    pub center_on_jump: bool,
+    pub visual_paste_keeps_register: bool,
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub matchpairs: Vec<String>,
+    pub keyword: Option<Arc<Keyword>>,
+    pub languages: HashMap<LanguageName, VimLanguageSettingsContent>,
+    pub custom_text_objects: Vec<Arc<CustomTextObject>>,
+}
+
+impl VimSettings {
+    /// Parses the `"<:>"`-style `matchpairs` for the given language, falling back
+    /// to the global setting when the language doesn't override it.
+    fn match_pairs(&self, language: Option<&LanguageName>) -> Vec<(char, char)> {
+        let pairs = language
+            .and_then(|language| self.languages.get(language))
+            .and_then(|settings| settings.matchpairs.as_ref())
+            .unwrap_or(&self.matchpairs);
+        pairs
+            .iter()
+            .filter_map(|pair| {
+                let mut chars = pair.chars();
+                match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                    (Some(open), Some(':'), Some(close), None) if open != close => {
+                        Some((open, close))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1734,9 +1811,35 @@ struct VimSettingsContent {
     pub use_system_clipboard: Option<UseSystemClipboard>,
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
+    pub enable_sneak: Option<bool>,
+    pub smart_sentence_detection: Option<bool>,
+    pub center_on_jump: Option<bool>,
+    pub visual_paste_keeps_register: Option<bool>,
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    pub matchpairs: Option<Vec<String>>,
+    pub iskeyword: Option<String>,
+    pub languages: Option<HashMap<LanguageName, VimLanguageSettingsContent>>,
+    pub custom_text_objects: Option<Vec<CustomTextObjectConfig>>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+struct VimLanguageSettingsContent {
+    pub matchpairs: Option<Vec<String>>,
+}
+
+/// A text object that spans from a match of the `start` regex to the first match of the
+/// `end` regex after it.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+struct CustomTextObjectConfig {
+    /// The name that `vim::TextObjectCustom` refers to the object by.
+    pub name: String,
+    /// The key that selects the object after `i` or `a`. Defaults to the first letter of
+    /// the name.
+    pub key: Option<String>,
+    pub start: String,
+    pub end: String,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1791,11 +1894,31 @@ impl Settings for VimSettings {
             use_smartcase_find: settings
                 .use_smartcase_find
                 .ok_or_else(Self::missing_default)?,
+            enable_sneak: settings.enable_sneak.ok_or_else(Self::missing_default)?,
+            smart_sentence_detection: settings
+                .smart_sentence_detection
+                .ok_or_else(Self::missing_default)?,
+            center_on_jump: settings.center_on_jump.ok_or_else(Self::missing_default)?,
+            visual_paste_keeps_register: settings
+                .visual_paste_keeps_register
+                .ok_or_else(Self::missing_default)?,
             custom_digraphs: settings.custom_digraphs.ok_or_else(Self::missing_default)?,
             highlight_on_yank_duration: settings
                 .highlight_on_yank_duration
                 .ok_or_else(Self::missing_default)?,
             cursor_shape: settings.cursor_shape.ok_or_else(Self::missing_default)?,
+            matchpairs: settings.matchpairs.ok_or_else(Self::missing_default)?,
+            keyword: settings
+                .iskeyword
+                .map(|spec| Keyword::parse(&spec).map(Arc::new))
+                .transpose()?,
+            languages: settings.languages.ok_or_else(Self::missing_default)?,
+            custom_text_objects: settings
+                .custom_text_objects
+                .ok_or_else(Self::missing_default)?
+                .iter()
+                .map(|config| CustomTextObject::new(config).map(Arc::new))
+                .collect::<Result<_>>()?,
         })
     }
 
@@ -4781,6 +4781,11 @@ impl Workspace {
         self.database_id
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_database_id(&mut self, database_id: WorkspaceId) {
+        self.database_id = Some(database_id);
+    }
+
     pub fn session_id(&self) -> Option<String> {
         self.session_id.clone()
     }
@@ -487,6 +487,8 @@ fn main() {
             cx,
         );
         supermaven::init(app_state.client.clone(), cx);
+        codeium::init(app_state.client.http_client(), cx);
+        ollama_completion::init(app_state.client.http_client(), cx);
         language_model::init(app_state.client.clone(), cx);
         language_models::init(
             app_state.user_store.clone(),
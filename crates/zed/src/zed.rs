@@ -218,6 +218,30 @@ pub fn initialize_workspace(
                 popover_menu_handle.toggle(window, cx);
             }
         });
+        workspace.register_action({
+            let fs = app_state.fs.clone();
+            move |workspace, _: &inline_completion_button::CycleProvider, _, cx| {
+                inline_completion_button::cycle_provider(workspace, fs.clone(), cx)
+            }
+        });
+        workspace.register_action({
+            let fs = app_state.fs.clone();
+            move |workspace, _: &inline_completion_button::ToggleForAllFiles, _, cx| {
+                inline_completion_button::toggle_for_all_files(workspace, fs.clone(), cx)
+            }
+        });
+        workspace.register_action({
+            let fs = app_state.fs.clone();
+            move |workspace, _: &inline_completion_button::ToggleForLanguage, _, cx| {
+                inline_completion_button::toggle_for_language(workspace, fs.clone(), cx)
+            }
+        });
+        workspace.register_action({
+            let fs = app_state.fs.clone();
+            move |workspace, _: &inline_completion_button::ExcludeFile, _, cx| {
+                inline_completion_button::exclude_active_file(workspace, fs.clone(), cx)
+            }
+        });
 
         let search_button = cx.new(|_| search::search_status_button::SearchButton::new());
         let diagnostic_summary =
@@ -1331,6 +1355,9 @@ fn show_markdown_app_notification<F>(
 fn reload_keymaps(cx: &mut App, user_key_bindings: Vec<KeyBinding>) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
+    if VimModeSetting::get_global(cx).0 {
+        cx.bind_keys(vim::custom_text_object_key_bindings(cx));
+    }
     cx.bind_keys(user_key_bindings);
     cx.set_menus(app_menus());
     // On Windows, this is set in the `update_jump_list` method of the `HistoryManager`.
@@ -1,20 +1,33 @@
 use client::{Client, UserStore};
+use codeium::{Codeium, CodeiumCompletionProvider};
 use collections::HashMap;
 use copilot::{Copilot, CopilotCompletionProvider};
-use editor::Editor;
-use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, WeakEntity};
+use editor::{Editor, EditorEvent};
+use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Subscription, WeakEntity};
+use inline_completion_button::{ProviderOverride, provider_override};
 use language::language_settings::{EditPredictionProvider, all_language_settings};
+use ollama_completion::{LocalModel, OllamaCompletionProvider};
 use settings::SettingsStore;
 use smol::stream::StreamExt;
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{cell::RefCell, mem, rc::Rc, sync::Arc};
 use supermaven::{Supermaven, SupermavenCompletionProvider};
 use ui::Window;
 use util::ResultExt;
 use workspace::Workspace;
 use zeta::{ProviderDataCollection, ZetaInlineCompletionProvider};
 
+/// An editor that gets edit predictions, with the provider it was last given.
+struct RegisteredEditor {
+    window: AnyWindowHandle,
+    provider: EditPredictionProvider,
+    /// Zed's rating actions, bound to the editor's current Zeta provider.
+    _rating_actions: Option<Subscription>,
+}
+
+type RegisteredEditors = Rc<RefCell<HashMap<WeakEntity<Editor>, RegisteredEditor>>>;
+
 pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
-    let editors: Rc<RefCell<HashMap<WeakEntity<Editor>, AnyWindowHandle>>> = Rc::default();
+    let editors: RegisteredEditors = Rc::default();
     cx.observe_new({
         let editors = editors.clone();
         let client = client.clone();
@@ -40,11 +53,44 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
             })
             .detach();
 
-            editors
-                .borrow_mut()
-                .insert(editor_handle, window.window_handle());
-            let provider = all_language_settings(None, cx).edit_predictions.provider;
-            assign_edit_prediction_provider(
+            // The buffer's language, and with it the language's provider, is often only known
+            // after the editor has been created.
+            let this = cx.entity();
+            cx.subscribe_in(&this, window, {
+                let editors = editors.clone();
+                let client = client.clone();
+                let user_store = user_store.clone();
+                move |editor, _, event: &EditorEvent, window, cx| {
+                    if let EditorEvent::Reparsed(_) = event {
+                        let provider = edit_prediction_provider_for_editor(editor, cx);
+                        let changed = editors
+                            .borrow_mut()
+                            .get_mut(&cx.entity().downgrade())
+                            .is_some_and(|registered| {
+                                mem::replace(&mut registered.provider, provider) != provider
+                            });
+                        if changed {
+                            let rating_actions = assign_edit_prediction_provider(
+                                editor,
+                                provider,
+                                &client,
+                                user_store.clone(),
+                                window,
+                                cx,
+                            );
+                            if let Some(registered) =
+                                editors.borrow_mut().get_mut(&cx.entity().downgrade())
+                            {
+                                registered._rating_actions = rating_actions;
+                            }
+                        }
+                    }
+                }
+            })
+            .detach();
+
+            let provider = edit_prediction_provider_for_editor(editor, cx);
+            let rating_actions = assign_edit_prediction_provider(
                 editor,
                 provider,
                 &client,
@@ -52,6 +98,14 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                 window,
                 cx,
             );
+            editors.borrow_mut().insert(
+                editor_handle,
+                RegisteredEditor {
+                    window: window.window_handle(),
+                    provider,
+                    _rating_actions: rating_actions,
+                },
+            );
         }
     })
     .detach();
@@ -70,7 +124,7 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                 cx.update(|cx| {
                     assign_edit_prediction_providers(
                         &editors,
-                        provider,
+                        true,
                         &client,
                         user_store.clone(),
                         cx,
@@ -82,13 +136,24 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     })
     .detach();
 
+    cx.observe_global::<ProviderOverride>({
+        let editors = editors.clone();
+        let client = client.clone();
+        let user_store = user_store.clone();
+        move |cx| {
+            assign_edit_prediction_providers(&editors, false, &client, user_store.clone(), cx);
+        }
+    })
+    .detach();
+
     cx.observe_global::<SettingsStore>({
         let editors = editors.clone();
         let client = client.clone();
         let user_store = user_store.clone();
         move |cx| {
-            let new_provider = all_language_settings(None, cx).edit_predictions.provider;
+            assign_edit_prediction_providers(&editors, false, &client, user_store.clone(), cx);
 
+            let new_provider = all_language_settings(None, cx).edit_predictions.provider;
             if new_provider != provider {
                 let tos_accepted = user_store
                     .read(cx)
@@ -103,13 +168,6 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                 );
 
                 provider = new_provider;
-                assign_edit_prediction_providers(
-                    &editors,
-                    provider,
-                    &client,
-                    user_store.clone(),
-                    cx,
-                );
 
                 if !tos_accepted {
                     match provider {
@@ -129,7 +187,9 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                         }
                         EditPredictionProvider::None
                         | EditPredictionProvider::Copilot
-                        | EditPredictionProvider::Supermaven => {}
+                        | EditPredictionProvider::Supermaven
+                        | EditPredictionProvider::Codeium
+                        | EditPredictionProvider::Ollama => {}
                     }
                 }
             }
@@ -144,24 +204,44 @@ fn clear_zeta_edit_history(_: &zeta::ClearHistory, cx: &mut App) {
     }
 }
 
+/// The provider for the editor's buffer, which its language can override, unless the status
+/// bar overrides the provider for every editor.
+fn edit_prediction_provider_for_editor(editor: &Editor, cx: &App) -> EditPredictionProvider {
+    if let Some(provider) = provider_override(cx) {
+        return provider;
+    }
+    let language = editor
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .and_then(|buffer| buffer.read(cx).language().map(|language| language.name()));
+    all_language_settings(None, cx).edit_prediction_provider(language.as_ref())
+}
+
+/// Gives every editor the provider for its language. Unless `force` is set, editors that
+/// already have that provider keep their current provider instance.
 fn assign_edit_prediction_providers(
-    editors: &Rc<RefCell<HashMap<WeakEntity<Editor>, AnyWindowHandle>>>,
-    provider: EditPredictionProvider,
+    editors: &RegisteredEditors,
+    force: bool,
     client: &Arc<Client>,
     user_store: Entity<UserStore>,
     cx: &mut App,
 ) {
-    for (editor, window) in editors.borrow().iter() {
-        _ = window.update(cx, |_window, window, cx| {
+    for (editor, registered) in editors.borrow_mut().iter_mut() {
+        _ = registered.window.update(cx, |_window, window, cx| {
             _ = editor.update(cx, |editor, cx| {
-                assign_edit_prediction_provider(
-                    editor,
-                    provider,
-                    &client,
-                    user_store.clone(),
-                    window,
-                    cx,
-                );
+                let provider = edit_prediction_provider_for_editor(editor, cx);
+                if force || provider != registered.provider {
+                    registered.provider = provider;
+                    registered._rating_actions = assign_edit_prediction_provider(
+                        editor,
+                        provider,
+                        &client,
+                        user_store.clone(),
+                        window,
+                        cx,
+                    );
+                }
             })
         });
     }
@@ -214,7 +294,7 @@ fn assign_edit_prediction_provider(
     user_store: Entity<UserStore>,
     window: &mut Window,
     cx: &mut Context<Editor>,
-) {
+) -> Option<Subscription> {
     // TODO: Do we really want to collect data only for singleton buffers?
     let singleton_buffer = editor.buffer().read(cx).as_singleton();
 
@@ -241,6 +321,18 @@ fn assign_edit_prediction_provider(
                 editor.set_edit_prediction_provider(Some(provider), window, cx);
             }
         }
+        EditPredictionProvider::Codeium => {
+            if let Some(codeium) = Codeium::global(cx) {
+                let provider = cx.new(|_| CodeiumCompletionProvider::new(codeium));
+                editor.set_edit_prediction_provider(Some(provider), window, cx);
+            }
+        }
+        EditPredictionProvider::Ollama => {
+            if let Some(local_model) = LocalModel::global(cx) {
+                let provider = cx.new(|_| OllamaCompletionProvider::new(local_model));
+                editor.set_edit_prediction_provider(Some(provider), window, cx);
+            }
+        }
         EditPredictionProvider::Zed => {
             if client.status().borrow().is_connected() {
                 let mut worktree = None;
@@ -280,8 +372,12 @@ fn assign_edit_prediction_provider(
                 let provider =
                     cx.new(|_| zeta::ZetaInlineCompletionProvider::new(zeta, data_collection));
 
+                let rating_actions =
+                    ZetaInlineCompletionProvider::register_rating_actions(&provider, editor);
                 editor.set_edit_prediction_provider(Some(provider), window, cx);
+                return Some(rating_actions);
             }
         }
     }
+    None
 }
@@ -9,7 +9,10 @@ mod rate_completion_modal;
 pub(crate) use completion_diff_element::*;
 use db::kvp::KEY_VALUE_STORE;
 pub use init::*;
-use inline_completion::{DataCollectionState, EditPredictionUsage};
+use inline_completion::{
+    DataCollectionState, EditPredictionAcceptanceStats, EditPredictionError,
+    EditPredictionRequestStats, EditPredictionUsage,
+};
 use license_detection::LICENSE_FILES_TO_CHECK;
 pub use license_detection::is_license_eligible_for_data_collection;
 pub use rate_completion_modal::*;
@@ -18,6 +21,7 @@ use anyhow::{Context as _, Result, anyhow};
 use arrayvec::ArrayVec;
 use client::{Client, UserStore};
 use collections::{HashMap, HashSet, VecDeque};
+use editor::Editor;
 use futures::AsyncReadExt;
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EntityId, Global, SemanticVersion,
@@ -26,13 +30,14 @@ use gpui::{
 use http_client::{HttpClient, Method};
 use input_excerpt::excerpt_for_cursor_position;
 use language::{
-    Anchor, Buffer, BufferSnapshot, EditPreview, OffsetRangeExt, ToOffset, ToPoint, text_diff,
+    Anchor, Buffer, BufferSnapshot, EditPreview, File, LanguageName, OffsetRangeExt, ToOffset,
+    ToPoint, language_settings::AllLanguageSettingsContent, text_diff,
 };
 use language_model::{LlmApiToken, RefreshLlmTokenListener};
 use postage::watch;
 use project::Project;
 use release_channel::AppVersion;
-use settings::WorktreeId;
+use settings::{SettingsStore, WorktreeId, parse_json_with_comments};
 use std::str::FromStr;
 use std::{
     borrow::Cow,
@@ -50,8 +55,8 @@ use telemetry_events::InlineCompletionRating;
 use thiserror::Error;
 use util::ResultExt;
 use uuid::Uuid;
-use workspace::Workspace;
 use workspace::notifications::{ErrorMessagePrompt, NotificationId};
+use workspace::{Toast, Workspace};
 use worktree::Worktree;
 use zed_llm_client::{
     EXPIRED_LLM_TOKEN_HEADER_NAME, MINIMUM_REQUIRED_VERSION_HEADER_NAME, PredictEditsBody,
@@ -73,6 +78,9 @@ const MAX_EVENT_TOKENS: usize = 500;
 const MAX_EVENT_COUNT: usize = 16;
 
 actions!(edit_prediction, [ClearHistory]);
+actions!(zeta, [RateLastCompletionGood, RateLastCompletionBad]);
+
+struct CompletionRatedToast;
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
 pub struct InlineCompletionId(Uuid);
@@ -195,6 +203,8 @@ pub struct Zeta {
     update_required: bool,
     _user_store_subscription: Subscription,
     license_detection_watchers: HashMap<WorktreeId, Rc<LicenseDetectionWatcher>>,
+    /// Shared by the providers of all editors, so that it covers the whole session.
+    acceptance_stats: EditPredictionAcceptanceStats,
 }
 
 impl Zeta {
@@ -282,6 +292,7 @@ impl Zeta {
                 }
             }),
             license_detection_watchers: HashMap::default(),
+            acceptance_stats: EditPredictionAcceptanceStats::default(),
         }
     }
 
@@ -1212,7 +1223,11 @@ impl Event {
 #[derive(Debug, Clone)]
 struct CurrentInlineCompletion {
     buffer_id: EntityId,
+    language: Option<LanguageName>,
     completion: InlineCompletion,
+    accepted: bool,
+    /// Whether the editor has asked for this completion since it arrived.
+    suggested: bool,
 }
 
 impl CurrentInlineCompletion {
@@ -1287,6 +1302,7 @@ pub struct ProviderDataCollection {
     /// When set to None, data collection is not possible in the provider buffer
     choice: Option<Entity<DataCollectionChoice>>,
     license_detection_watcher: Option<Rc<LicenseDetectionWatcher>>,
+    file: Option<Arc<dyn File>>,
 }
 
 impl ProviderDataCollection {
@@ -1306,24 +1322,30 @@ impl ProviderDataCollection {
                 .get(&file.worktree_id(cx))
                 .cloned()?;
 
-            Some((choice, license_detection_watcher))
+            Some((choice, license_detection_watcher, file.clone()))
         });
 
-        if let Some((choice, watcher)) = choice_and_watcher {
+        if let Some((choice, watcher, file)) = choice_and_watcher {
             ProviderDataCollection {
                 choice: Some(choice),
                 license_detection_watcher: Some(watcher),
+                file: Some(file),
             }
         } else {
             ProviderDataCollection {
                 choice: None,
                 license_detection_watcher: None,
+                file: None,
             }
         }
     }
 
     pub fn can_collect_data(&self, cx: &App) -> bool {
-        self.is_data_collection_enabled(cx) && self.is_project_open_source()
+        can_collect_data(
+            self.worktree_override(cx),
+            self.is_data_collection_enabled(cx),
+            self.is_project_open_source(),
+        )
     }
 
     pub fn is_data_collection_enabled(&self, cx: &App) -> bool {
@@ -1332,6 +1354,11 @@ impl ProviderDataCollection {
             .is_some_and(|choice| choice.read(cx).is_enabled())
     }
 
+    /// The `data_collection` setting from the worktree's own settings files.
+    fn worktree_override(&self, cx: &App) -> Option<bool> {
+        local_data_collection_setting(self.file.as_ref()?, cx)
+    }
+
     fn is_project_open_source(&self) -> bool {
         self.license_detection_watcher
             .as_ref()
@@ -1356,6 +1383,30 @@ impl ProviderDataCollection {
     }
 }
 
+/// A project's `data_collection` setting replaces the license detection, but only the user can
+/// opt in. Otherwise a repository could commit the setting and collect data from everyone who
+/// opens it.
+fn can_collect_data(worktree_override: Option<bool>, opted_in: bool, is_open_source: bool) -> bool {
+    match worktree_override {
+        Some(enabled) => enabled && opted_in,
+        None => opted_in && is_open_source,
+    }
+}
+
+/// Reads `data_collection` from the settings files inside the file's worktree, ignoring the
+/// user's settings. The settings file closest to the file wins.
+fn local_data_collection_setting(file: &Arc<dyn File>, cx: &App) -> Option<bool> {
+    cx.global::<SettingsStore>()
+        .local_settings(file.worktree_id(cx))
+        .filter(|(directory, _)| file.path().starts_with(directory))
+        .filter_map(|(directory, content)| {
+            let content = parse_json_with_comments::<AllLanguageSettingsContent>(&content).ok()?;
+            Some((directory, content.edit_predictions?.data_collection?))
+        })
+        .max_by_key(|(directory, _)| directory.components().count())
+        .map(|(_, enabled)| enabled)
+}
+
 pub struct ZetaInlineCompletionProvider {
     zeta: Entity<Zeta>,
     pending_completions: ArrayVec<PendingCompletion, 2>,
@@ -1364,6 +1415,8 @@ pub struct ZetaInlineCompletionProvider {
     /// None if this is entirely disabled for this provider
     provider_data_collection: ProviderDataCollection,
     last_request_timestamp: Instant,
+    request_stats: EditPredictionRequestStats,
+    last_shown_completion: Option<InlineCompletion>,
 }
 
 impl ZetaInlineCompletionProvider {
@@ -1377,8 +1430,67 @@ impl ZetaInlineCompletionProvider {
             current_completion: None,
             provider_data_collection,
             last_request_timestamp: Instant::now(),
+            request_stats: EditPredictionRequestStats::default(),
+            last_shown_completion: None,
         }
     }
+
+    /// Rates the completion this provider showed most recently, even if it has since been
+    /// accepted or discarded.
+    pub fn rate_last_shown_completion(
+        &mut self,
+        rating: InlineCompletionRating,
+        cx: &mut Context<Self>,
+    ) {
+        let message = match self.last_shown_completion.as_ref() {
+            Some(completion) => {
+                let message = match rating {
+                    InlineCompletionRating::Positive => "Rated the last edit prediction as good",
+                    InlineCompletionRating::Negative => "Rated the last edit prediction as bad",
+                };
+                self.zeta.update(cx, |zeta, cx| {
+                    zeta.rate_completion(completion, rating, String::new(), cx);
+                });
+                message
+            }
+            None => "No edit prediction has been shown in this editor yet",
+        };
+
+        let Some(workspace) = self.zeta.read(cx).workspace.clone() else {
+            return;
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<CompletionRatedToast>(), message)
+                        .autohide(),
+                    cx,
+                );
+            })
+            .ok();
+    }
+
+    /// Routes the rating actions dispatched in `editor` to `provider` until the returned
+    /// subscription is dropped, which must happen when the editor gets another provider.
+    pub fn register_rating_actions(provider: &Entity<Self>, editor: &mut Editor) -> Subscription {
+        let weak_provider = provider.downgrade();
+        let rate_good = editor.register_action(move |_: &RateLastCompletionGood, _, cx| {
+            weak_provider
+                .update(cx, |provider, cx| {
+                    provider.rate_last_shown_completion(InlineCompletionRating::Positive, cx)
+                })
+                .ok();
+        });
+        let weak_provider = provider.downgrade();
+        let rate_bad = editor.register_action(move |_: &RateLastCompletionBad, _, cx| {
+            weak_provider
+                .update(cx, |provider, cx| {
+                    provider.rate_last_shown_completion(InlineCompletionRating::Negative, cx)
+                })
+                .ok();
+        });
+        Subscription::join(rate_good, rate_bad)
+    }
 }
 
 impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider {
@@ -1400,14 +1512,19 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
 
     fn data_collection_state(&self, cx: &App) -> DataCollectionState {
         let is_project_open_source = self.provider_data_collection.is_project_open_source();
+        let worktree_override = self.provider_data_collection.worktree_override(cx);
+        let is_worktree_override = worktree_override.is_some();
+        let opted_in = self.provider_data_collection.is_data_collection_enabled(cx);
 
-        if self.provider_data_collection.is_data_collection_enabled(cx) {
+        if worktree_override.map_or(opted_in, |enabled| enabled && opted_in) {
             DataCollectionState::Enabled {
                 is_project_open_source,
+                is_worktree_override,
             }
         } else {
             DataCollectionState::Disabled {
                 is_project_open_source,
+                is_worktree_override,
             }
         }
     }
@@ -1420,6 +1537,25 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         self.zeta.read(cx).last_usage
     }
 
+    fn last_request_latency(&self, _cx: &App) -> Option<Duration> {
+        self.request_stats.last_latency()
+    }
+
+    fn last_error(&self, _cx: &App) -> Option<EditPredictionError> {
+        self.request_stats.last_error()
+    }
+
+    fn acceptance_stats(&self, cx: &App) -> Option<EditPredictionAcceptanceStats> {
+        Some(self.zeta.read(cx).acceptance_stats.clone())
+    }
+
+    fn reset_acceptance_stats(&mut self, cx: &mut App) {
+        self.zeta.update(cx, |zeta, cx| {
+            zeta.acceptance_stats = EditPredictionAcceptanceStats::default();
+            cx.notify();
+        });
+    }
+
     fn is_enabled(
         &self,
         _buffer: &Entity<Buffer>,
@@ -1437,6 +1573,12 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         !self.pending_completions.is_empty()
     }
 
+    fn has_pending_suggestion(&self, _cx: &App) -> bool {
+        self.current_completion
+            .as_ref()
+            .map_or(false, |completion| !completion.suggested)
+    }
+
     fn refresh(
         &mut self,
         project: Option<Entity<Project>>,
@@ -1468,6 +1610,10 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         self.next_pending_completion_id += 1;
         let can_collect_data = self.provider_data_collection.can_collect_data(cx);
         let last_request_timestamp = self.last_request_timestamp;
+        let language = buffer
+            .read(cx)
+            .language_at(position)
+            .map(|language| language.name());
 
         let task = cx.spawn(async move |this, cx| {
             if let Some(timeout) = (last_request_timestamp + Self::THROTTLE_TIMEOUT)
@@ -1491,11 +1637,19 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
 
             let completion = match completion_request {
                 Ok(completion_request) => {
+                    let started_at = Instant::now();
                     let completion_request = completion_request.await;
+                    this.update(cx, |this, _| {
+                        this.request_stats.record(started_at, &completion_request)
+                    })
+                    .ok();
                     completion_request.map(|c| {
                         c.map(|completion| CurrentInlineCompletion {
                             buffer_id: buffer.entity_id(),
+                            language,
                             completion,
+                            accepted: false,
+                            suggested: false,
                         })
                     })
                 }
@@ -1531,13 +1685,19 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
                     if new_completion.should_replace_completion(&old_completion, &snapshot) {
                         this.zeta.update(cx, |zeta, cx| {
                             zeta.completion_shown(&new_completion.completion, cx);
+                            zeta.acceptance_stats
+                                .record_shown(new_completion.language.clone());
                         });
+                        this.last_shown_completion = Some(new_completion.completion.clone());
                         this.current_completion = Some(new_completion);
                     }
                 } else {
                     this.zeta.update(cx, |zeta, cx| {
                         zeta.completion_shown(&new_completion.completion, cx);
+                        zeta.acceptance_stats
+                            .record_shown(new_completion.language.clone());
                     });
+                    this.last_shown_completion = Some(new_completion.completion.clone());
                     this.current_completion = Some(new_completion);
                 }
 
@@ -1572,8 +1732,18 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         // Right now we don't support cycling.
     }
 
-    fn accept(&mut self, _cx: &mut Context<Self>) {
+    fn accept(&mut self, cx: &mut Context<Self>) {
         self.pending_completions.clear();
+        // Partially accepting a prediction accepts it again, but it only counts once.
+        if let Some(current_completion) = self.current_completion.as_mut() {
+            if !current_completion.accepted {
+                current_completion.accepted = true;
+                let language = current_completion.language.clone();
+                self.zeta.update(cx, |zeta, _| {
+                    zeta.acceptance_stats.record_accepted(language);
+                });
+            }
+        }
     }
 
     fn discard(&mut self, _cx: &mut Context<Self>) {
@@ -1590,6 +1760,7 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         let CurrentInlineCompletion {
             buffer_id,
             completion,
+            suggested,
             ..
         } = self.current_completion.as_mut()?;
 
@@ -1635,6 +1806,7 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
             }
         }
 
+        *suggested = true;
         Some(inline_completion::InlineCompletion {
             id: Some(completion.id.to_string().into()),
             edits: edits[edit_start_ix..edit_end_ix].to_vec(),
@@ -1654,15 +1826,181 @@ fn tokens_for_bytes(bytes: usize) -> usize {
 mod tests {
     use client::test::FakeServer;
     use clock::FakeSystemClock;
-    use gpui::TestAppContext;
+    use gpui::{Focusable, TestAppContext};
     use http_client::FakeHttpClient;
     use indoc::indoc;
+    use inline_completion::EditPredictionProvider as _;
     use language::Point;
     use rpc::proto;
-    use settings::SettingsStore;
+    use settings::{LocalSettingsKind, SettingsStore};
 
     use super::*;
 
+    #[test]
+    fn test_worktree_data_collection_override() {
+        // Only the user can opt in, whatever the project says.
+        assert!(!can_collect_data(Some(true), false, true));
+        assert!(!can_collect_data(Some(true), false, false));
+        // Once they have, a project can opt in even if its license isn't detected.
+        assert!(can_collect_data(Some(true), true, false));
+        // A project can always opt out.
+        assert!(!can_collect_data(Some(false), true, true));
+        // Without a project setting, the license detection decides.
+        assert!(can_collect_data(None, true, true));
+        assert!(!can_collect_data(None, true, false));
+        assert!(!can_collect_data(None, false, true));
+    }
+
+    #[gpui::test]
+    fn test_data_collection_setting_comes_from_worktree(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+        });
+        let file: Arc<dyn File> = Arc::new(language::TestFile {
+            path: Path::new("src/main.rs").into(),
+            root_name: "project".into(),
+            local_root: None,
+        });
+        let worktree_id = WorktreeId::from_usize(0);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(r#"{"edit_predictions": {"data_collection": true}}"#, cx)
+                    .unwrap();
+            });
+            assert_eq!(local_data_collection_setting(&file, cx), None);
+
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_local_settings(
+                        worktree_id,
+                        Path::new("").into(),
+                        LocalSettingsKind::Settings,
+                        Some(r#"{"edit_predictions": {"data_collection": true}}"#),
+                        cx,
+                    )
+                    .unwrap();
+            });
+            assert_eq!(local_data_collection_setting(&file, cx), Some(true));
+
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_local_settings(
+                        worktree_id,
+                        Path::new("src").into(),
+                        LocalSettingsKind::Settings,
+                        Some(r#"{"edit_predictions": {"data_collection": false}}"#),
+                        cx,
+                    )
+                    .unwrap();
+            });
+            assert_eq!(local_data_collection_setting(&file, cx), Some(false));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rating_after_provider_reassignment(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+        });
+        let http_client = FakeHttpClient::with_404_response();
+        let client = cx.update(|cx| Client::new(Arc::new(FakeSystemClock::new()), http_client, cx));
+        cx.update(|cx| {
+            RefreshLlmTokenListener::register(client.clone(), cx);
+        });
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        let zeta = cx.new(|cx| Zeta::new(None, client, user_store, cx));
+        let buffer = cx.new(|cx| Buffer::local("lorem ipsum", cx));
+
+        let mut providers = Vec::new();
+        for _ in 0..2 {
+            let completion = shown_completion(&buffer, cx).await;
+            let completion_id = completion.id;
+            let provider = cx.new(|cx| {
+                let data_collection = ProviderDataCollection::new(zeta.clone(), None, cx);
+                let mut provider = ZetaInlineCompletionProvider::new(zeta.clone(), data_collection);
+                provider.last_shown_completion = Some(completion);
+                provider
+            });
+            providers.push((provider, completion_id));
+        }
+        let (old_provider, old_completion_id) = &providers[0];
+        let (new_provider, new_completion_id) = &providers[1];
+
+        let (editor, cx) =
+            cx.add_window_view(|window, cx| Editor::for_buffer(buffer.clone(), None, window, cx));
+        let rating_actions = editor.update(cx, |editor, _| {
+            ZetaInlineCompletionProvider::register_rating_actions(old_provider, editor)
+        });
+        drop(rating_actions);
+        let _rating_actions = editor.update(cx, |editor, _| {
+            ZetaInlineCompletionProvider::register_rating_actions(new_provider, editor)
+        });
+        cx.update(|window, cx| window.focus(&editor.focus_handle(cx)));
+        cx.run_until_parked();
+
+        cx.dispatch_action(RateLastCompletionGood);
+        zeta.read_with(cx, |zeta, _| {
+            assert!(!zeta.is_completion_rated(*old_completion_id));
+            assert!(zeta.is_completion_rated(*new_completion_id));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_acceptance_stats_are_shared_by_providers(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+        });
+        let http_client = FakeHttpClient::with_404_response();
+        let client = cx.update(|cx| Client::new(Arc::new(FakeSystemClock::new()), http_client, cx));
+        cx.update(|cx| {
+            RefreshLlmTokenListener::register(client.clone(), cx);
+        });
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        let zeta = cx.new(|cx| Zeta::new(None, client, user_store, cx));
+        let buffer = cx.new(|cx| Buffer::local("lorem ipsum", cx));
+        let [first_provider, second_provider] = [(); 2].map(|_| {
+            cx.new(|cx| {
+                let data_collection = ProviderDataCollection::new(zeta.clone(), None, cx);
+                ZetaInlineCompletionProvider::new(zeta.clone(), data_collection)
+            })
+        });
+
+        let completion = shown_completion(&buffer, cx).await;
+        first_provider.update(cx, |provider, cx| {
+            provider.current_completion = Some(CurrentInlineCompletion {
+                buffer_id: buffer.entity_id(),
+                language: Some(LanguageName::new("Rust")),
+                completion,
+                accepted: false,
+                suggested: true,
+            });
+            provider.accept(cx);
+        });
+
+        second_provider.update(cx, |provider, cx| {
+            let stats = provider.acceptance_stats(cx).unwrap();
+            assert_eq!(stats.total().accepted, 1);
+            assert_eq!(stats.top_languages(3)[0].0, LanguageName::new("Rust"));
+
+            provider.reset_acceptance_stats(cx);
+        });
+        first_provider.read_with(cx, |provider, cx| {
+            let stats = provider.acceptance_stats(cx).unwrap();
+            assert_eq!(stats.total(), Default::default());
+        });
+    }
+
     #[gpui::test]
     async fn test_inline_completion_basic_interpolation(cx: &mut TestAppContext) {
         let buffer = cx.new(|cx| Buffer::local("Lorem ipsum dolor", cx));
@@ -1959,6 +2297,32 @@ mod tests {
             .collect::<Vec<_>>()
     }
 
+    async fn shown_completion(
+        buffer: &Entity<Buffer>,
+        cx: &mut TestAppContext,
+    ) -> InlineCompletion {
+        let edits: Arc<[(Range<Anchor>, String)]> =
+            cx.update(|cx| to_completion_edits([(0..5, "LOREM".to_string())], buffer, cx).into());
+        let edit_preview = cx
+            .read(|cx| buffer.read(cx).preview_edits(edits.clone(), cx))
+            .await;
+        InlineCompletion {
+            edits,
+            edit_preview,
+            path: Path::new("").into(),
+            snapshot: cx.read(|cx| buffer.read(cx).snapshot()),
+            id: InlineCompletionId(Uuid::new_v4()),
+            excerpt_range: 0..0,
+            cursor_offset: 0,
+            input_outline: "".into(),
+            input_events: "".into(),
+            input_excerpt: "".into(),
+            output_excerpt: "".into(),
+            request_sent_at: Instant::now(),
+            response_received_at: Instant::now(),
+        }
+    }
+
     fn to_completion_edits(
         iterator: impl IntoIterator<Item = (Range<usize>, String)>,
         buffer: &Entity<Buffer>,